@@ -7,19 +7,21 @@ extern crate tokio;
 use std::env::var;
 
 use bitmex::model::chat::PostChatRequest;
-use bitmex::{BitMEX, Result};
-use tokio::runtime::current_thread::Runtime;
+use bitmex::BitMEX;
+use tokio::runtime::Runtime;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 fn main() -> Result<()> {
     ::dotenv::dotenv().ok();
     ::env_logger::init();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
     let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
     let fut = bm.post_chat(PostChatRequest {
         message: "hello2 from bot".to_string(),
         ..Default::default()
-    })?;
+    });
 
     let ret = rt.block_on(fut)?;
     println!("{:?}", ret);
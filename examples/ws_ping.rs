@@ -3,31 +3,33 @@ extern crate dotenv;
 extern crate env_logger;
 extern crate futures;
 extern crate tokio;
-extern crate tungstenite;
 
 use bitmex::model::websocket::Command;
-use bitmex::{BitMEX, Result};
-use futures::{Future, Sink, Stream};
+use bitmex::{BitMEX, BitMEXError};
+use futures::{SinkExt, StreamExt};
 use std::env::var;
-use tokio::runtime::current_thread::Runtime;
+use tokio::runtime::Runtime;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 fn main() -> Result<()> {
     ::dotenv::dotenv().ok();
     ::env_logger::init();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
     let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let job = bm
-        .websocket()
-        .and_then(|ws| {
-            println!("WebSocket handshake has been successfully completed");
-            ws.send(Command::Ping)
-        }).and_then(|ws| ws.map(|msg| println!("{:?}", msg)).collect())
-        .map_err(|e| {
-            println!("Error during the websocket handshake occurred: {}", e);
-            e
-        });
+    let job = async {
+        let mut ws = bm.websocket().await?;
+        println!("WebSocket handshake has been successfully completed");
+        ws.send(Command::Ping).await?;
+        while let Some(msg) = ws.next().await {
+            println!("{:?}", msg?);
+        }
+        Ok(())
+    };
 
-    rt.block_on(job)?;
-    Ok(())
+    rt.block_on(job).map_err(|e: BitMEXError| {
+        println!("Error during the websocket handshake occurred: {}", e);
+        e.into()
+    })
 }
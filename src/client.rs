@@ -1,5 +1,8 @@
 mod announcement;
 mod api_key;
+#[cfg(any(test, feature = "blocking"))]
+pub mod blocking;
+pub mod book;
 mod chat;
 mod execution;
 mod funding;
@@ -12,17 +15,40 @@ mod order;
 mod order_book;
 mod position;
 mod quote;
+mod schema;
+pub mod server_time;
 mod settlement;
+pub mod symbol_cache;
+pub mod table_cache;
+pub mod topic_streams;
+pub mod tracker;
 mod trade;
 mod user;
 mod user_event;
 pub mod websocket;
 
-use crate::transport::Transport;
+use std::env::var;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::warn;
+
+use url::Url;
+
+use crate::consts::{ws_url_for, Network};
+use crate::error::{BitMEXError, Result};
+use crate::model::order::{check_cl_ord_id_namespace, PostOrderRequest};
+use crate::signing::ApiSecret;
+use crate::transport::{BoxFuture, RetryConfig, Transport, TransportBuilder, TransportTrait};
+
+use self::symbol_cache::SymbolCache;
 
 #[derive(Clone)]
-pub struct BitMEX {
-    pub(crate) transport: Transport,
+pub struct BitMEX<T = Transport> {
+    pub(crate) transport: T,
+    symbols: Option<Arc<SymbolCache>>,
+    cl_ord_id_namespace: Option<Arc<str>>,
+    ws_url: Arc<str>,
 }
 
 impl Default for BitMEX {
@@ -33,12 +59,230 @@ impl Default for BitMEX {
 
 impl BitMEX {
     pub fn new() -> Self {
-        BitMEX { transport: Transport::new() }
+        BitMEX::with_transport(Transport::new())
     }
 
     pub fn with_credential(api_key: &str, api_secret: &str) -> Self {
+        BitMEX::with_transport(Transport::with_credential(api_key, api_secret))
+    }
+
+    pub fn builder() -> BitMEXBuilder {
+        BitMEXBuilder::default()
+    }
+
+    /// Configures the client from `BITMEX_API_KEY`, `BITMEX_API_SECRET`, `BITMEX_NETWORK` (`mainnet`
+    /// or `testnet`) and `BITMEX_BASE_URL`, all optional. Without both key and secret the client
+    /// only reaches the public endpoints. Fails with `BitMEXError::InvalidConfig` if the network or
+    /// the base url can't be used.
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name| var(name).filter(|value: &String| !value.is_empty());
+        let mut builder = Self::builder();
+        match (var("BITMEX_API_KEY"), var("BITMEX_API_SECRET")) {
+            (Some(key), Some(secret)) => builder = builder.api_key(&key).owned_api_secret(ApiSecret::new(secret)),
+            (None, None) => {}
+            _ => warn!("Only one of BITMEX_API_KEY and BITMEX_API_SECRET is set, using the public endpoints only"),
+        }
+        if let Some(network) = var("BITMEX_NETWORK") {
+            builder = builder.network(network.parse()?);
+        }
+        if let Some(base_url) = var("BITMEX_BASE_URL") {
+            builder = builder.base_url(&base_url);
+        }
+        builder.build().map_err(|e| match e {
+            BitMEXError::Url(e) => BitMEXError::InvalidConfig(format!("BITMEX_BASE_URL is not a url: {}", e)),
+            e => e,
+        })
+    }
+}
+
+/// Configures a `BitMEX` client in one place, see `TransportBuilder` for the HTTP settings
+#[derive(Clone, Debug, Default)]
+pub struct BitMEXBuilder {
+    api_key: Option<String>,
+    api_secret: Option<Arc<ApiSecret>>,
+    base_url: Option<String>,
+    transport: TransportBuilder,
+}
+
+impl BitMEXBuilder {
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn api_secret(self, api_secret: &str) -> Self {
+        self.owned_api_secret(api_secret.into())
+    }
+
+    /// Like `api_secret`, but takes the secret without copying it
+    pub fn owned_api_secret(mut self, api_secret: ApiSecret) -> Self {
+        self.api_secret = Some(Arc::new(api_secret));
+        self
+    }
+
+    pub fn network(mut self, network: Network) -> Self {
+        self.base_url = Some(network.rest_url().into());
+        self
+    }
+
+    /// Has to be an https url
+    pub fn base_url(mut self, url: &str) -> Self {
+        self.base_url = Some(url.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.transport = self.transport.timeout(timeout);
+        self
+    }
+
+    pub fn retry(mut self, config: RetryConfig) -> Self {
+        self.transport = self.transport.retry(config);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.transport = self.transport.user_agent(user_agent);
+        self
+    }
+
+    /// See `TransportBuilder::proxy`
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.transport = self.transport.proxy(url);
+        self
+    }
+
+    /// Fails with `BitMEXError::InvalidConfig` if only one of api key and secret is set, the base
+    /// url isn't https or the proxy isn't an http url
+    pub fn build(self) -> Result<BitMEX> {
+        let mut transport = self.transport;
+        match (self.api_key, self.api_secret) {
+            (Some(key), Some(secret)) => transport = transport.shared_secret(&key, secret),
+            (None, None) => {}
+            _ => return Err(BitMEXError::InvalidConfig("api_key and api_secret have to be set together".into())),
+        }
+        if let Some(base_url) = self.base_url {
+            if Url::parse(&base_url)?.scheme() != "https" {
+                return Err(BitMEXError::InvalidConfig(format!("Base url {} is not https", base_url)));
+            }
+            transport = transport.base_url(&base_url);
+        }
+        Ok(BitMEX::with_transport(transport.build()?))
+    }
+}
+
+impl<T: TransportTrait> BitMEX<T> {
+    /// Use a custom HTTP backend, e.g. a mock or a throttled transport. The websocket connects to
+    /// the deployment of the transport's base url, see `ws_url`.
+    pub fn with_transport(transport: T) -> Self {
+        let ws_url = ws_url_for(transport.base_url()).unwrap_or_else(|| Network::Mainnet.ws_url().to_string());
         BitMEX {
-            transport: Transport::with_credential(api_key, api_secret),
+            ws_url: ws_url.into(),
+            transport,
+            symbols: None,
+            cl_ord_id_namespace: None,
+        }
+    }
+
+    /// Prefixes the `clOrdID`s generated by `post_order_idempotent` with `namespace`, see
+    /// `PostOrderRequest::with_auto_cl_ord_id`. Fails with `BitMEXError::InvalidConfig` for a
+    /// namespace over 20 characters.
+    pub fn with_cl_ord_id_namespace(mut self, namespace: &str) -> Result<Self> {
+        check_cl_ord_id_namespace(namespace)?;
+        self.cl_ord_id_namespace = Some(namespace.into());
+        Ok(self)
+    }
+
+    /// Rejects order and position requests for a symbol missing from `symbols` with
+    /// `BitMEXError::UnknownSymbol`, without sending them. Off by default, see `SymbolCache::fetch`.
+    pub fn with_symbol_cache(mut self, symbols: SymbolCache) -> Self {
+        self.symbols = Some(Arc::new(symbols));
+        self
+    }
+
+    pub fn symbol_cache(&self) -> Option<&SymbolCache> {
+        self.symbols.as_deref()
+    }
+
+    // Sends the request unless a symbol fails the symbol cache
+    pub(crate) fn checked<S: AsRef<str>, O: Send + 'static>(&self, symbols: impl IntoIterator<Item = S>, send: impl FnOnce() -> BoxFuture<O>) -> BoxFuture<O> {
+        if let Some(cache) = &self.symbols {
+            if let Err(e) = symbols.into_iter().try_for_each(|symbol| cache.check(symbol.as_ref())) {
+                return Box::pin(async move { Err(e) });
+            }
+        }
+        send()
+    }
+
+    // Rounds the prices of a new order to the tick size from the symbol cache, if it has one
+    pub(crate) fn on_tick(&self, order: PostOrderRequest) -> Result<PostOrderRequest> {
+        match self.symbols.as_ref().and_then(|cache| cache.tick_size(&order.symbol)) {
+            Some(tick_size) => order.with_tick_size(tick_size),
+            None => Ok(order),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::BitMEX;
+    use crate::consts::Network;
+    use crate::error::{BitMEXError, Result};
+    use crate::transport::{RetryConfig, TransportTrait};
+
+    #[test]
+    fn test_builder() -> Result<()> {
+        let bm = BitMEX::builder().build()?;
+        assert_eq!((bm.transport.api_key(), bm.transport.base_url()), (None, *crate::consts::REST_URL));
+
+        let bm = BitMEX::builder()
+            .api_key("key")
+            .api_secret("secret")
+            .network(Network::Testnet)
+            .timeout(Duration::from_secs(10))
+            .retry(RetryConfig::default())
+            .proxy("http://proxy:8080")
+            .user_agent("my-bot/1.0")
+            .build()?;
+        assert_eq!((bm.transport.api_key(), bm.transport.base_url()), (Some("key"), "https://testnet.bitmex.com/api/v1"));
+        assert_eq!(bm.ws_url(), "wss://testnet.bitmex.com/realtime");
+
+        let bm = BitMEX::builder().base_url("https://gateway.example.com/api/v1/").build()?;
+        assert_eq!(bm.transport.base_url(), "https://gateway.example.com/api/v1");
+        assert_eq!(bm.ws_url(), "wss://gateway.example.com/realtime");
+
+        for builder in [BitMEX::builder().api_secret("secret"), BitMEX::builder().api_key("key"), BitMEX::builder().base_url("http://localhost:8080/api/v1"), BitMEX::builder().proxy("socks5://proxy:1080")] {
+            assert!(matches!(builder.build(), Err(BitMEXError::InvalidConfig(_))));
+        }
+        assert!(matches!(BitMEX::builder().base_url("/api/v1").build(), Err(BitMEXError::Url(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_env() -> Result<()> {
+        let from = |vars: &[(&str, &str)]| {
+            let vars: HashMap<_, _> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            BitMEX::from_vars(|name| vars.get(name).cloned())
+        };
+
+        let bm = from(&[])?;
+        assert_eq!(bm.transport.api_key(), None);
+        let bm = from(&[("BITMEX_API_KEY", "key"), ("BITMEX_API_SECRET", "secret"), ("BITMEX_NETWORK", "Testnet")])?;
+        assert_eq!((bm.transport.api_key(), bm.transport.base_url()), (Some("key"), "https://testnet.bitmex.com/api/v1"));
+        assert_eq!(bm.ws_url(), "wss://testnet.bitmex.com/realtime");
+        let bm = from(&[("BITMEX_API_KEY", "key"), ("BITMEX_API_SECRET", ""), ("BITMEX_BASE_URL", "https://gateway.example.com/api/v1")])?;
+        assert_eq!((bm.transport.api_key(), bm.transport.base_url()), (None, "https://gateway.example.com/api/v1"));
+
+        for vars in [[("BITMEX_NETWORK", "devnet")], [("BITMEX_BASE_URL", "localhost")], [("BITMEX_BASE_URL", "http://localhost:8080")]] {
+            assert!(matches!(from(&vars), Err(BitMEXError::InvalidConfig(_))));
         }
+        Ok(())
     }
 }
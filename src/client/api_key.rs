@@ -1,4 +1,3 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
@@ -6,24 +5,25 @@ use crate::model::api_key::{
     DeleteApiKeyRequest, DeleteApiKeyResponse, GetApiKeyResponse, PostApiKeyDisableRequest, PostApiKeyDisableResponse, PostApiKeyEnableRequest, PostApiKeyEnableResponse,
     PostApiKeyRequest, PostApiKeyResponse,
 };
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_api_key(&self) -> Result<impl Future<Item = Vec<GetApiKeyResponse>, Error = Error>> {
-        Ok(self.transport.signed_get::<_, ()>("/apiKey", None)?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_api_key(&self) -> impl Future<Output = Result<Vec<GetApiKeyResponse>>> {
+        self.transport.signed_get::<_, ()>("/apiKey", None)
     }
 
-    pub fn post_api_key(&self, req: PostApiKeyRequest) -> Result<impl Future<Item = Vec<PostApiKeyResponse>, Error = Error>> {
-        Ok(self.transport.signed_post("/apiKey", Some(req))?)
+    pub fn post_api_key(&self, req: PostApiKeyRequest) -> impl Future<Output = Result<Vec<PostApiKeyResponse>>> {
+        self.transport.signed_post("/apiKey", Some(req))
     }
 
-    pub fn delete_api_key(&self, req: DeleteApiKeyRequest) -> Result<impl Future<Item = DeleteApiKeyResponse, Error = Error>> {
-        Ok(self.transport.signed_delete("/apiKey", Some(req))?)
+    pub fn delete_api_key(&self, req: DeleteApiKeyRequest) -> impl Future<Output = Result<DeleteApiKeyResponse>> {
+        self.transport.signed_delete("/apiKey", Some(req))
     }
-    pub fn post_api_key_disable(&self, req: PostApiKeyDisableRequest) -> Result<impl Future<Item = PostApiKeyDisableResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/apiKey/disable", Some(req))?)
+    pub fn post_api_key_disable(&self, req: PostApiKeyDisableRequest) -> impl Future<Output = Result<PostApiKeyDisableResponse>> {
+        self.transport.signed_post("/apiKey/disable", Some(req))
     }
-    pub fn post_api_key_enable(&self, req: PostApiKeyEnableRequest) -> Result<impl Future<Item = PostApiKeyEnableResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/apiKey/enable", Some(req))?)
+    pub fn post_api_key_enable(&self, req: PostApiKeyEnableRequest) -> impl Future<Output = Result<PostApiKeyEnableResponse>> {
+        self.transport.signed_post("/apiKey/enable", Some(req))
     }
 }
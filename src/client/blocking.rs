@@ -0,0 +1,90 @@
+use std::future::Future;
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::error::Result;
+use crate::model::funding::{GetFundingRequest, GetFundingResponse};
+use crate::model::instrument::{GetInstrumentRequest, GetInstrumentResponse};
+use crate::model::order_book::{GetOrderBookL2Request, GetOrderBookL2Response};
+use crate::model::quote::{GetQuoteBucketedRequest, GetQuoteBucketedResponse};
+use crate::model::trade::{GetTradeBucketedRequest, GetTradeBucketedResponse, GetTradeRequest, GetTradeResponse};
+use crate::transport::TransportTrait;
+use crate::BitMEX;
+
+impl<T: TransportTrait> BitMEX<T> {
+    /// A synchronous view of the client, for scripts that don't want to run a reactor
+    pub fn blocking(&self) -> Result<Blocking<'_, T>> {
+        let rt = Builder::new_current_thread().enable_all().build()?;
+        Ok(Blocking { bm: self, rt })
+    }
+}
+
+/// Runs client calls to completion on its own single threaded runtime.
+/// Must not be used from within an async context.
+pub struct Blocking<'a, T> {
+    bm: &'a BitMEX<T>,
+    rt: Runtime,
+}
+
+impl<'a, T: TransportTrait> Blocking<'a, T> {
+    /// Runs any client call, e.g. `blocking.run(|bm| bm.get_position(req))`
+    pub fn run<F, O, C>(&self, call: C) -> Result<O>
+    where
+        C: FnOnce(&'a BitMEX<T>) -> F,
+        F: Future<Output = Result<O>>,
+    {
+        self.rt.block_on(call(self.bm))
+    }
+
+    pub fn get_instrument(&self, req: GetInstrumentRequest) -> Result<Vec<GetInstrumentResponse>> {
+        self.run(|bm| bm.get_instrument(req))
+    }
+
+    pub fn get_trade(&self, req: GetTradeRequest) -> Result<GetTradeResponse> {
+        self.run(|bm| bm.get_trade(req))
+    }
+
+    pub fn get_trade_bucketed(&self, req: GetTradeBucketedRequest) -> Result<GetTradeBucketedResponse> {
+        self.run(|bm| bm.get_trade_bucketed(req))
+    }
+
+    pub fn get_quote_bucketed(&self, req: GetQuoteBucketedRequest) -> Result<GetQuoteBucketedResponse> {
+        self.run(|bm| bm.get_quote_bucketed(req))
+    }
+
+    pub fn get_funding(&self, req: GetFundingRequest) -> Result<Vec<GetFundingResponse>> {
+        self.run(|bm| bm.get_funding(req))
+    }
+
+    pub fn get_order_book_l2(&self, req: GetOrderBookL2Request) -> Result<Vec<GetOrderBookL2Response>> {
+        self.run(|bm| bm.get_order_book_l2(req))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::Result;
+    use crate::model::order_book::GetOrderBookL2Request;
+    use crate::transport::MockTransport;
+    use crate::BitMEX;
+
+    #[test]
+    fn test_blocking() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(r#"[{"symbol":"XBTUSD","id":8799386750,"side":"Sell","size":10,"price":6132.5}]"#);
+        tr.push_response("[]");
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let blocking = bm.blocking()?;
+        let book = blocking.get_order_book_l2(GetOrderBookL2Request {
+            symbol: "XBTUSD".into(),
+            depth: Some(1),
+        })?;
+        assert_eq!(book.len(), 1);
+
+        let orders = blocking.run(|bm| bm.get_open_orders(None))?;
+        assert!(orders.is_empty());
+        assert_eq!(tr.requests()[1].endpoint, "/order");
+        Ok(())
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::future;
+use futures::stream::{Stream, TryStreamExt};
+
+use crate::client::websocket::BitMEXWebsocket;
+use crate::error::{BitMEXError, Result};
+use crate::model::definitions::OrderBookL2;
+use crate::model::websocket::{Action, Message, TableMessage, Topic};
+use crate::model::Side;
+use crate::BitMEX;
+
+/// A price level of an L2 book
+#[derive(Clone, Debug, PartialEq)]
+pub struct BookLevel {
+    pub id: i64,
+    pub side: Side,
+    pub price: f64,
+    pub size: i64,
+}
+
+/// The best bid and ask of a book, as price and size
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TopOfBook {
+    pub best_bid: Option<(f64, i64)>,
+    pub best_ask: Option<(f64, i64)>,
+}
+
+impl TopOfBook {
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask?.0 - self.best_bid?.0)
+    }
+}
+
+/// An inconsistency of an `OrderBook` found by `OrderBook::validate`
+#[derive(Clone, Debug, PartialEq)]
+pub enum BookViolation {
+    /// The best bid is at or above the best ask
+    Crossed { bid: f64, ask: f64 },
+    /// A level without a positive size
+    EmptyLevel { id: i64, size: i64 },
+    /// Two levels of one side at the same price. Levels are keyed by id, so a duplicate id shows
+    /// up as its price held by two ids, e.g. after a missed delete.
+    DuplicatePrice { side: Side, price: f64, ids: (i64, i64) },
+}
+
+/// The L2 book of one symbol, maintained from the `orderBookL2` or `orderBookL2_25` websocket topic.
+/// Rows of other symbols are ignored, the partial replaces everything.
+#[derive(Clone, Debug)]
+pub struct OrderBook {
+    symbol: String,
+    levels: HashMap<i64, BookLevel>,
+}
+
+impl OrderBook {
+    pub fn new(symbol: &str) -> Self {
+        OrderBook {
+            symbol: symbol.to_string(),
+            levels: HashMap::new(),
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// The bids, highest first
+    pub fn bids(&self) -> Vec<BookLevel> {
+        self.side(Side::Buy, |a, b| b.price.total_cmp(&a.price))
+    }
+
+    /// The asks, lowest first
+    pub fn asks(&self) -> Vec<BookLevel> {
+        self.side(Side::Sell, |a, b| a.price.total_cmp(&b.price))
+    }
+
+    pub fn best_bid(&self) -> Option<(f64, i64)> {
+        self.best(Side::Buy, |price, best| price > best)
+    }
+
+    pub fn best_ask(&self) -> Option<(f64, i64)> {
+        self.best(Side::Sell, |price, best| price < best)
+    }
+
+    pub fn top_of_book(&self) -> TopOfBook {
+        TopOfBook {
+            best_bid: self.best_bid(),
+            best_ask: self.best_ask(),
+        }
+    }
+
+    /// Checks that the book isn't crossed, every level has a positive size and no price is held by
+    /// two levels. Fails with `BitMEXError::InvalidBook` listing every violation.
+    pub fn validate(&self) -> Result<()> {
+        let mut violations = vec![];
+        if let (Some((bid, _)), Some((ask, _))) = (self.best_bid(), self.best_ask()) {
+            if bid >= ask {
+                violations.push(BookViolation::Crossed { bid, ask });
+            }
+        }
+        for side in [self.bids(), self.asks()].iter() {
+            for level in side.iter().filter(|level| level.size <= 0) {
+                violations.push(BookViolation::EmptyLevel { id: level.id, size: level.size });
+            }
+            // Sorted by price, so levels at the same price are neighbours
+            for pair in side.windows(2).filter(|pair| pair[0].price == pair[1].price) {
+                let (side, price) = (pair[0].side.clone(), pair[0].price);
+                violations.push(BookViolation::DuplicatePrice { side, price, ids: (pair[0].id, pair[1].id) });
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(BitMEXError::InvalidBook(violations))
+        }
+    }
+
+    /// Applies a book message, returns whether the book changed. Inserts without a price are
+    /// skipped. An update of an id missing from the book fails with `BitMEXError::BookDesync`
+    /// after the rest of the message was applied: a delta was missed, resubscribe to get a new
+    /// partial. A partial only replaces the book if it is filtered by the book's symbol or has rows
+    /// of it, the partials of other symbols on the same socket leave it alone.
+    pub fn apply(&mut self, msg: &TableMessage<OrderBookL2>) -> Result<bool> {
+        let symbol = &self.symbol;
+        let replaced = matches!(msg.action, Action::Partial)
+            && (msg.filter.as_ref().and_then(|filter| filter.symbol()) == Some(symbol.as_str()) || msg.data.iter().any(|row| row.symbol == *symbol));
+        if replaced {
+            self.levels.clear();
+        }
+        let mut changed = replaced;
+        let mut missing = None;
+        for row in msg.data.iter().filter(|row| row.symbol == *symbol) {
+            match msg.action {
+                Action::Partial | Action::Insert => {
+                    if let (Some(price), Some(size)) = (row.price, row.size) {
+                        let level = BookLevel {
+                            id: row.id,
+                            side: row.side.clone(),
+                            price,
+                            size,
+                        };
+                        self.levels.insert(row.id, level);
+                        changed = true;
+                    }
+                }
+                Action::Update => match self.levels.get_mut(&row.id) {
+                    Some(level) => {
+                        level.size = row.size.unwrap_or(level.size);
+                        level.price = row.price.unwrap_or(level.price);
+                        // BitMEX may move a level to the other side of the book
+                        if let Side::Buy | Side::Sell = row.side {
+                            level.side = row.side.clone();
+                        }
+                        changed = true;
+                    }
+                    None => missing = missing.or(Some(row.id)),
+                },
+                Action::Delete => changed |= self.levels.remove(&row.id).is_some(),
+                Action::Unknown(_) => {}
+            }
+        }
+        match missing {
+            Some(id) => Err(BitMEXError::BookDesync { symbol: self.symbol.clone(), id }),
+            None => Ok(changed),
+        }
+    }
+
+    fn side(&self, side: Side, order: impl Fn(&BookLevel, &BookLevel) -> std::cmp::Ordering) -> Vec<BookLevel> {
+        let mut levels: Vec<_> = self.levels.values().filter(|level| level.side == side).cloned().collect();
+        levels.sort_by(order);
+        levels
+    }
+
+    fn best(&self, side: Side, better: impl Fn(f64, f64) -> bool) -> Option<(f64, i64)> {
+        self.levels
+            .values()
+            .filter(|level| level.side == side)
+            .fold(None, |best: Option<&BookLevel>, level| match best {
+                Some(best) if !better(level.price, best.price) => Some(best),
+                _ => Some(level),
+            })
+            .map(|level| (level.price, level.size))
+    }
+}
+
+impl<T> BitMEX<T> {
+    /// Subscribes to `orderBookL2:<symbol>` on a new socket and yields the book every time it changed,
+    /// see `OrderBookStream`
+    pub async fn order_book_stream(&self, symbol: &str) -> Result<OrderBookStream> {
+        let mut ws = self.websocket().await?;
+        ws.subscribe(&[(Topic::OrderBookL2(None), Some(symbol))]).await?;
+        Ok(OrderBookStream::new(symbol, ws))
+    }
+}
+
+/// Maintains an `OrderBook` from a websocket and yields a copy of it for every batch of changes.
+///
+/// Only the latest state is kept: every poll applies all messages already received before yielding,
+/// a consumer falling behind skips the intermediate books instead of buffering them. A
+/// `SequenceGap` or `BookDesync` of the book is returned as an error, the book is stale until the
+/// next partial.
+pub struct OrderBookStream<S = BitMEXWebsocket> {
+    stream: S,
+    book: OrderBook,
+    changed: bool,
+    done: bool,
+}
+
+impl<S: Stream<Item = Result<Message>> + Unpin> OrderBookStream<S> {
+    pub fn new(symbol: &str, stream: S) -> Self {
+        OrderBookStream {
+            stream,
+            book: OrderBook::new(symbol),
+            changed: false,
+            done: false,
+        }
+    }
+
+    /// The book as of the messages applied so far
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Yields the best bid and ask whenever one of them changed
+    pub fn top_of_book(self) -> impl Stream<Item = Result<TopOfBook>> {
+        let mut last = None;
+        self.try_filter_map(move |book| {
+            let top = book.top_of_book();
+            let changed = last != Some(top);
+            last = Some(top);
+            future::ready(Ok(if changed { Some(top) } else { None }))
+        })
+    }
+}
+
+impl<S: Stream<Item = Result<Message>> + Unpin> Stream for OrderBookStream<S> {
+    type Item = Result<OrderBook>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.done {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(Ok(Message::OrderBookL2(msg)))) => match this.book.apply(&msg) {
+                    Ok(changed) => this.changed |= changed,
+                    Err(e) => {
+                        // The other rows of the message were applied
+                        this.changed = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                },
+                Poll::Ready(Some(Ok(Message::SequenceGap(table)))) if table.starts_with("orderBookL2") => {
+                    return Poll::Ready(Some(Err(BitMEXError::SequenceGap(table))));
+                }
+                Poll::Ready(Some(Ok(_))) => {}
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if this.changed {
+            this.changed = false;
+            Poll::Ready(Some(Ok(this.book.clone())))
+        } else if this.done {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::future::poll_fn;
+    use std::task::Poll;
+
+    use futures::channel::mpsc;
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt, TryStreamExt};
+    use serde_json::from_str;
+
+    use super::{BookViolation, OrderBook, OrderBookStream, TopOfBook};
+    use crate::error::{BitMEXError, Result};
+    use crate::model::Side;
+    use crate::model::websocket::Message;
+
+    const PARTIAL: &str = r#"{"table":"orderBookL2_25","action":"partial","keys":["symbol","id","side"],"filter":{"symbol":"XBTUSD"},"data":[{"symbol":"XBTUSD","id":8799629900,"side":"Sell","size":200,"price":3701},{"symbol":"XBTUSD","id":8799630000,"side":"Sell","size":100,"price":3700},{"symbol":"XBTUSD","id":8799630100,"side":"Buy","size":300,"price":3699},{"symbol":"XBTUSD","id":8799630200,"side":"Buy","size":50,"price":3698}]}"#;
+
+    fn message(json: &str) -> Result<Message> {
+        Ok(from_str(json)?)
+    }
+
+    // Pending once, like a socket with nothing received yet
+    async fn yield_now() {
+        let mut yielded = false;
+        poll_fn(|cx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await
+    }
+
+    #[test]
+    fn test_order_book_stream() -> Result<()> {
+        let (tx, rx) = mpsc::unbounded();
+        let mut books = OrderBookStream::new("XBTUSD", rx);
+
+        tx.unbounded_send(message(PARTIAL)).unwrap();
+        let book = block_on(books.next()).unwrap()?;
+        assert_eq!(book.len(), 4);
+        assert_eq!((book.best_bid(), book.best_ask()), (Some((3699., 300)), Some((3700., 100))));
+        let prices: Vec<_> = book.bids().iter().map(|level| level.price).collect();
+        assert_eq!(prices, vec![3699., 3698.]);
+
+        // A consumer that falls behind only sees the latest book
+        tx.unbounded_send(message(r#"{"table":"orderBookL2_25","action":"update","data":[{"symbol":"XBTUSD","id":8799630000,"side":"Sell","size":150}]}"#)).unwrap();
+        tx.unbounded_send(message(r#"{"table":"orderBookL2_25","action":"delete","data":[{"symbol":"XBTUSD","id":8799630100,"side":"Buy"}]}"#)).unwrap();
+        tx.unbounded_send(message(r#"{"table":"orderBookL2_25","action":"insert","data":[{"symbol":"ETHUSD","id":29699987000,"side":"Buy","size":10,"price":130}]}"#)).unwrap();
+        tx.unbounded_send(message(r#"{"table":"trade","action":"insert","data":[{"timestamp":"2019-01-01T00:00:00.000Z","symbol":"XBTUSD"}]}"#)).unwrap();
+        let book = block_on(books.next()).unwrap()?;
+        assert_eq!(book.top_of_book(), TopOfBook { best_bid: Some((3698., 50)), best_ask: Some((3700., 150)) });
+        assert_eq!(book.len(), 3);
+
+        // Nothing changed for this symbol, so nothing is yielded before the end
+        tx.unbounded_send(message(r#"{"table":"orderBookL2_25","action":"delete","data":[{"symbol":"ETHUSD","id":29699987000,"side":"Buy"}]}"#)).unwrap();
+        drop(tx);
+        assert!(block_on(books.next()).is_none());
+        assert_eq!(books.book().top_of_book().spread(), Some(2.));
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_of_book() -> Result<()> {
+        let messages = vec![
+            message(PARTIAL),
+            message(r#"{"table":"orderBookL2_25","action":"update","data":[{"symbol":"XBTUSD","id":8799630200,"side":"Buy","size":75}]}"#),
+            message(r#"{"table":"orderBookL2_25","action":"update","data":[{"symbol":"XBTUSD","id":8799630100,"side":"Buy","size":10}]}"#),
+        ];
+        // Every message is a batch of its own
+        let messages = stream::iter(messages).then(|msg| async {
+            yield_now().await;
+            msg
+        });
+        let tops: Vec<_> = block_on(OrderBookStream::new("XBTUSD", Box::pin(messages)).top_of_book().try_collect())?;
+        assert_eq!(tops.len(), 2);
+        assert_eq!(tops[1].best_bid, Some((3699., 10)));
+        Ok(())
+    }
+
+    fn book(json: &str) -> Result<OrderBook> {
+        let mut book = OrderBook::new("XBTUSD");
+        match message(json)? {
+            Message::OrderBookL2(msg) => book.apply(&msg)?,
+            other => panic!("Unexpected message {:?}", other),
+        };
+        Ok(book)
+    }
+
+    #[test]
+    fn test_validate() -> Result<()> {
+        book(PARTIAL)?.validate()?;
+
+        let broken = book(
+            r#"{"table":"orderBookL2_25","action":"partial","data":[{"symbol":"XBTUSD","id":1,"side":"Sell","size":100,"price":3699},{"symbol":"XBTUSD","id":2,"side":"Buy","size":0,"price":3700},{"symbol":"XBTUSD","id":3,"side":"Buy","size":10,"price":3698},{"symbol":"XBTUSD","id":4,"side":"Buy","size":20,"price":3698}]}"#,
+        )?;
+        match broken.validate() {
+            Err(BitMEXError::InvalidBook(violations)) => {
+                assert_eq!(violations.len(), 3);
+                assert!(violations.contains(&BookViolation::Crossed { bid: 3700., ask: 3699. }));
+                assert!(violations.contains(&BookViolation::EmptyLevel { id: 2, size: 0 }));
+                assert!(violations.iter().any(|v| matches!(v, BookViolation::DuplicatePrice { side: Side::Buy, price, .. } if *price == 3698.)));
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_book_desync() -> Result<()> {
+        let messages = vec![
+            message(PARTIAL),
+            message(r#"{"table":"orderBookL2_25","action":"update","data":[{"symbol":"XBTUSD","id":42,"side":"Buy","size":10},{"symbol":"XBTUSD","id":8799630100,"side":"Buy","size":10}]}"#),
+        ];
+        let mut books = OrderBookStream::new("XBTUSD", stream::iter(messages));
+        assert!(matches!(block_on(books.next()), Some(Err(BitMEXError::BookDesync { ref symbol, id: 42 })) if symbol == "XBTUSD"));
+        // The known level of the update was applied all the same
+        let book = block_on(books.next()).unwrap()?;
+        assert_eq!(book.best_bid(), Some((3699., 10)));
+        assert!(block_on(books.next()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_of_other_symbol() -> Result<()> {
+        let mut book = OrderBook::new("XBTUSD");
+        let messages = [
+            PARTIAL,
+            r#"{"table":"orderBookL2_25","action":"partial","keys":["symbol","id","side"],"filter":{"symbol":"ETHUSD"},"data":[{"symbol":"ETHUSD","id":29699987000,"side":"Buy","size":10,"price":130}]}"#,
+            r#"{"table":"orderBookL2_25","action":"partial","keys":["symbol","id","side"],"filter":{"symbol":"XBTUSD"},"data":[]}"#,
+        ];
+        let mut changed = vec![];
+        for msg in messages.iter() {
+            match message(msg)? {
+                Message::OrderBookL2(msg) => changed.push((book.apply(&msg)?, book.len())),
+                other => panic!("Unexpected message {:?}", other),
+            }
+        }
+        // The ETHUSD partial leaves the book as it was, an empty XBTUSD partial still clears it
+        assert_eq!(changed, vec![(true, 4), (false, 4), (true, 0)]);
+        Ok(())
+    }
+}
@@ -1,16 +1,18 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::execution::{GetExecutionRequest, GetExecutionResponse, GetExecutionTradeHistoryRequest, GetExecutionTradeHistoryResponse};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_execution(&self, req: GetExecutionRequest) -> Result<impl Future<Item = Vec<GetExecutionResponse>, Error = Error>> {
-        Ok(self.transport.signed_get("/execution", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    /// All executions of the account, including order status changes like cancels and triggers
+    pub fn get_execution(&self, req: GetExecutionRequest) -> impl Future<Output = Result<Vec<GetExecutionResponse>>> {
+        self.transport.signed_get("/execution", Some(req))
     }
 
-    pub fn get_execution_history(&self, req: GetExecutionTradeHistoryRequest) -> Result<impl Future<Item = Vec<GetExecutionTradeHistoryResponse>, Error = Error>> {
-        Ok(self.transport.signed_get("/execution/tradeHistory", Some(req))?)
+    /// Only the executions that are fills, `execType` `Trade`. Use these to reconcile PnL.
+    pub fn get_execution_history(&self, req: GetExecutionTradeHistoryRequest) -> impl Future<Output = Result<Vec<GetExecutionTradeHistoryResponse>>> {
+        self.transport.signed_get("/execution/tradeHistory", Some(req))
     }
 }
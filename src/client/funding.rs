@@ -1,12 +1,12 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::funding::{GetFundingRequest, GetFundingResponse};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_funding(&self, req: GetFundingRequest) -> Result<impl Future<Item = Vec<GetFundingResponse>, Error = Error>> {
-        Ok(self.transport.get("/funding", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_funding(&self, req: GetFundingRequest) -> impl Future<Output = Result<Vec<GetFundingResponse>>> {
+        self.transport.get("/funding", Some(req))
     }
 }
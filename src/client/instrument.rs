@@ -1,4 +1,3 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
@@ -6,30 +5,81 @@ use crate::model::instrument::{
     GetInstrumentActiveAndIndicesResponse, GetInstrumentActiveIntervalsResponse, GetInstrumentActiveResponse, GetInstrumentCompositeIndexRequest,
     GetInstrumentCompositeIndexResponse, GetInstrumentIndicesResponse, GetInstrumentRequest, GetInstrumentResponse,
 };
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_instrument(&self, req: GetInstrumentRequest) -> Result<impl Future<Item = Vec<GetInstrumentResponse>, Error = Error>> {
-        Ok(self.transport.get("/instrument", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_instrument(&self, req: GetInstrumentRequest) -> impl Future<Output = Result<Vec<GetInstrumentResponse>>> {
+        self.transport.get("/instrument", Some(req))
     }
 
-    pub fn get_instrument_active(&self) -> Result<impl Future<Item = Vec<GetInstrumentActiveResponse>, Error = Error>> {
-        Ok(self.transport.get::<_, ()>("/instrument/active", None)?)
+    pub fn get_instrument_active(&self) -> impl Future<Output = Result<Vec<GetInstrumentActiveResponse>>> {
+        self.transport.get::<_, ()>("/instrument/active", None)
     }
 
-    pub fn get_instrument_active_and_indices(&self) -> Result<impl Future<Item = Vec<GetInstrumentActiveAndIndicesResponse>, Error = Error>> {
-        Ok(self.transport.get::<_, ()>("/instrument/activeAndIndices", None)?)
+    pub fn get_instrument_active_and_indices(&self) -> impl Future<Output = Result<Vec<GetInstrumentActiveAndIndicesResponse>>> {
+        self.transport.get::<_, ()>("/instrument/activeAndIndices", None)
     }
 
-    pub fn get_instrument_active_interval(&self) -> Result<impl Future<Item = GetInstrumentActiveIntervalsResponse, Error = Error>> {
-        Ok(self.transport.get::<_, ()>("/instrument/activeIntervals", None)?)
+    pub fn get_instrument_active_interval(&self) -> impl Future<Output = Result<GetInstrumentActiveIntervalsResponse>> {
+        self.transport.get::<_, ()>("/instrument/activeIntervals", None)
     }
 
-    pub fn get_instrument_composite_index(&self, req: GetInstrumentCompositeIndexRequest) -> Result<impl Future<Item = Vec<GetInstrumentCompositeIndexResponse>, Error = Error>> {
-        Ok(self.transport.get("/instrument/compositeIndex", Some(req))?)
+    pub fn get_instrument_composite_index(&self, req: GetInstrumentCompositeIndexRequest) -> impl Future<Output = Result<Vec<GetInstrumentCompositeIndexResponse>>> {
+        self.transport.get("/instrument/compositeIndex", Some(req))
     }
 
-    pub fn get_instrument_indices(&self) -> Result<impl Future<Item = Vec<GetInstrumentIndicesResponse>, Error = Error>> {
-        Ok(self.transport.get::<_, ()>("/instrument/indices", None)?)
+    /// The components of an index like `.BXBT`, with their weights and last prices
+    pub fn get_composite_index(&self, index_symbol: &str) -> impl Future<Output = Result<Vec<GetInstrumentCompositeIndexResponse>>> {
+        self.get_instrument_composite_index(GetInstrumentCompositeIndexRequest {
+            symbol: Some(index_symbol.to_string()),
+            ..Default::default()
+        })
+    }
+
+    pub fn get_instrument_indices(&self) -> impl Future<Output = Result<Vec<GetInstrumentIndicesResponse>>> {
+        self.transport.get::<_, ()>("/instrument/indices", None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+
+    use crate::error::Result;
+    use crate::model::instrument::GetInstrumentRequest;
+    use crate::model::TickDirection;
+    use crate::transport::MockTransport;
+    use crate::BitMEX;
+
+    #[test]
+    fn test_get_instrument() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(
+            r#"[{"symbol":"XBTUSD","rootSymbol":"XBT","state":"Open","typ":"FFWCSX","tickSize":0.5,"lotSize":1,"maxOrderQty":10000000,"multiplier":-100000000,"settlCurrency":"XBt","isQuanto":false,"isInverse":true,"initMargin":0.01,"maintMargin":0.005,"fundingRate":0.0001,"indicativeFundingRate":0.0001,"fundingTimestamp":"2019-01-01T04:00:00.000Z","fundingInterval":"2000-01-01T08:00:00.000Z","lastPrice":3700.5,"lastTickDirection":"PlusTick","markPrice":3701.24,"fairMethod":"FundingRate","markMethod":"FairPrice","settledPriceAdjustmentRate":null,"settledPrice":null,"timestamp":"2019-01-01T00:00:00.000Z"}]"#,
+        );
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let instruments = block_on(bm.get_instrument(GetInstrumentRequest::default()))?;
+        let xbt = &instruments[0];
+        assert_eq!((xbt.tick_size, xbt.lot_size, xbt.multiplier), (Some(0.5), Some(1.), Some(-100000000.)));
+        assert_eq!((xbt.is_inverse, xbt.funding_rate, xbt.settled_price_adjustment_rate), (Some(true), Some(0.0001), None));
+        assert_eq!(xbt.last_tick_direction, Some(TickDirection::PlusTick));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_composite_index() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(r#"[{"timestamp":"2019-01-01T00:00:00.000Z","symbol":".BXBT","indexSymbol":".BXBT","reference":"BSTP","lastPrice":3700.5,"weight":0.25,"logged":"2019-01-01T00:00:00.000Z"}]"#);
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let components = block_on(bm.get_composite_index(".BXBT"))?;
+        assert_eq!((components[0].reference.as_str(), components[0].weight), ("BSTP", Some(0.25)));
+
+        let req = &tr.requests()[0];
+        assert_eq!(req.endpoint, "/instrument/compositeIndex");
+        assert!(req.query.contains(&("symbol".to_string(), ".BXBT".to_string())));
+        Ok(())
     }
 }
@@ -1,16 +1,16 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::leaderboard::{GetLeaderboardNameResponse, GetLeaderboardRequest, GetLeaderboardResponse};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_leaderboard(&self, req: GetLeaderboardRequest) -> Result<impl Future<Item = Vec<GetLeaderboardResponse>, Error = Error>> {
-        Ok(self.transport.get("/leaderboard", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_leaderboard(&self, req: GetLeaderboardRequest) -> impl Future<Output = Result<Vec<GetLeaderboardResponse>>> {
+        self.transport.get("/leaderboard", Some(req))
     }
 
-    pub fn get_leaderboard_name(&self) -> Result<impl Future<Item = GetLeaderboardNameResponse, Error = Error>> {
-        Ok(self.transport.signed_get::<_, ()>("/leaderboard/name", None)?)
+    pub fn get_leaderboard_name(&self) -> impl Future<Output = Result<GetLeaderboardNameResponse>> {
+        self.transport.signed_get::<_, ()>("/leaderboard/name", None)
     }
 }
@@ -1,12 +1,12 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::liquidation::{GetLiquidationRequest, GetLiquidationResponse};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_liquidation(&self, req: GetLiquidationRequest) -> Result<impl Future<Item = Vec<GetLiquidationResponse>, Error = Error>> {
-        Ok(self.transport.get("/liquidation", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_liquidation(&self, req: GetLiquidationRequest) -> impl Future<Output = Result<Vec<GetLiquidationResponse>>> {
+        self.transport.get("/liquidation", Some(req))
     }
 }
@@ -1,48 +1,488 @@
-use failure::Error;
-use futures::Future;
-use serde_json::json;
+use chrono::{DateTime, Utc};
+use futures::{future, stream, Future, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
 
-use crate::error::Result;
+use crate::error::{BitMEXError, Result};
 use crate::model::order::{
-    DeleteOrderAllRequest, DeleteOrderAllResponse, DeleteOrderRequest, DeleteOrderResponse, GetOrderRequest, GetOrderResponse, PostOrderCancelAllAfterRequest,
+    CancelOrderRequest, CancelOrderResponse, CancelOrdersByFilterRequest, CancelOrdersByFilterResponse, DeleteOrderAllRequest, DeleteOrderAllResponse, DeleteOrderRequest, DeleteOrderResponse, GetOrderRequest, GetOrderResponse, OrderId, PostOrderCancelAllAfterRequest,
     PostOrderCancelAllAfterResponse, PostOrderClosePositionRequest, PostOrderClosePositionResponse, PostOrderRequest, PostOrderResponse, PutOrderRequest, PutOrderResponse,
 };
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_order(&self, req: GetOrderRequest) -> Result<impl Future<Item = GetOrderResponse, Error = Error>> {
-        Ok(self.transport.signed_get("/order", Some(req))?)
+// BitMEX caps `count` at 500 rows per request
+const ORDER_PAGE_SIZE: u64 = 500;
+
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_order(&self, req: GetOrderRequest) -> impl Future<Output = Result<Vec<GetOrderResponse>>> {
+        self.transport.signed_get("/order", Some(req))
+    }
+
+    /// One page of orders, `count` defaults to BitMEX's 100 and is capped at 500. `filter` is
+    /// matched against the order's fields, e.g. `{"ordStatus": "Filled"}`.
+    pub fn get_orders(&self, symbol: Option<&str>, filter: Option<Value>, count: Option<u64>, start: Option<u64>, reverse: Option<bool>) -> impl Future<Output = Result<Vec<GetOrderResponse>>> {
+        let mut req = GetOrderRequest {
+            symbol: symbol.map(ToString::to_string),
+            filter,
+            start,
+            reverse,
+            ..Default::default()
+        };
+        if let Some(count) = count {
+            req.count = count;
+        }
+        self.get_order(req)
+    }
+
+    /// All open orders, of `symbol` or of every symbol. Fetched in pages of 500 until a short page
+    /// comes back, so that no order is missed on an account with many.
+    pub fn get_open_orders(&self, symbol: Option<&str>) -> impl Future<Output = Result<Vec<GetOrderResponse>>> + '_ {
+        self.paged_orders(GetOrderRequest {
+            symbol: symbol.map(ToString::to_string),
+            filter: Some(json!({ "open": true })),
+            ..Default::default()
+        })
+        .try_collect()
+    }
+
+    /// The order history between `start_time` and `end_time`, oldest first. Pages of 500 orders are
+    /// fetched as the stream is consumed, the stream ends after the first error.
+    pub fn stream_orders(
+        &self,
+        symbol: Option<&str>,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> impl Stream<Item = Result<GetOrderResponse>> + '_ {
+        self.paged_orders(GetOrderRequest {
+            symbol: symbol.map(ToString::to_string),
+            start_time,
+            end_time,
+            ..Default::default()
+        })
+    }
+
+    // The orders matching `req`, fetched in pages of 500 as the stream is consumed
+    pub(crate) fn paged_orders<O>(&self, req: GetOrderRequest) -> impl Stream<Item = Result<O>> + '_
+    where
+        O: DeserializeOwned + Send + 'static,
+    {
+        let req = GetOrderRequest { count: ORDER_PAGE_SIZE, ..req };
+        stream::try_unfold(Some(req.start.unwrap_or(0)), move |start: Option<u64>| {
+            let page = start.map(|start| (start, self.transport.signed_get::<Vec<O>, _>("/order", Some(GetOrderRequest { start: Some(start), ..req.clone() }))));
+            async move {
+                let (start, page) = match page {
+                    Some(page) => page,
+                    None => return Result::<_>::Ok(None),
+                };
+                let page = page.await?;
+                let len = page.len() as u64;
+                let next = if len < ORDER_PAGE_SIZE { None } else { Some(start + len) };
+                Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+    }
+
+    pub fn put_order(&self, req: PutOrderRequest) -> impl Future<Output = Result<PutOrderResponse>> {
+        self.checked([req.symbol.clone()], || self.transport.signed_put("/order", Some(req)))
+    }
+
+    /// Amends several orders in one request, each identified by its `order_id` or `orig_cl_ord_id`.
+    /// Counts as a single request against the rate limit.
+    pub fn put_order_bulk(&self, req: &[PutOrderRequest]) -> impl Future<Output = Result<Vec<PutOrderResponse>>> {
+        self.checked(req.iter().map(|order| order.symbol.as_str()), || self.transport.signed_put("/order/bulk", Some(json! {{ "orders": req }})))
+    }
+
+    /// Places an order. With a symbol cache that knows the tick size, the prices are rounded to it
+    /// first, see `PostOrderRequest::with_tick_size`.
+    pub fn post_order(&self, req: PostOrderRequest) -> impl Future<Output = Result<PostOrderResponse>> {
+        match self.on_tick(req) {
+            Ok(req) => self.checked([req.symbol.clone()], || self.transport.signed_post("/order", Some(req))),
+            Err(e) => Box::pin(future::ready(Err(e))),
+        }
+    }
+
+    /// Like `post_order`, but generates a `clOrdID` if `req` has none, prefixed with the namespace set
+    /// by `with_cl_ord_id_namespace`, if any. The id is written to `req` before sending, so posting
+    /// the same `req` again after an error or timeout reuses it and BitMEX rejects the order
+    /// instead of placing it twice. The id is echoed back in the response's `cl_ord_id`.
+    pub fn post_order_idempotent(&self, req: &mut PostOrderRequest) -> impl Future<Output = Result<PostOrderResponse>> {
+        if req.cl_ord_id.is_none() {
+            let order = PostOrderRequest::default();
+            let order = match &self.cl_ord_id_namespace {
+                Some(namespace) => order.with_auto_cl_ord_id(namespace),
+                None => Ok(order.with_generated_cl_ord_id()),
+            };
+            match order {
+                Ok(order) => req.cl_ord_id = order.cl_ord_id,
+                Err(e) => return future::Either::Right(future::ready(Err(e))),
+            }
+        }
+        future::Either::Left(self.post_order(req.clone()))
+    }
+
+    /// Amends the order `cancel_id` to the quantity, prices and text of
+    /// `new_order`, or places `new_order` if the order is already filled or canceled and BitMEX
+    /// rejects the amend with `Invalid ordStatus`. Returns the amended order twice, or `None` and the
+    /// new order. The amend keeps the side of the old order, other errors are returned as is.
+    pub async fn cancel_and_replace(&self, cancel_id: OrderId, new_order: PostOrderRequest) -> Result<(Option<PutOrderResponse>, PostOrderResponse)> {
+        let (order_id, orig_cl_ord_id) = match cancel_id {
+            OrderId::OrderID(id) => (Some(id), None),
+            OrderId::ClOrdID(id) => (None, Some(id)),
+        };
+        let amend = PutOrderRequest {
+            symbol: new_order.symbol.clone(),
+            order_id,
+            orig_cl_ord_id,
+            cl_ord_id: new_order.cl_ord_id.clone(),
+            simple_order_qty: new_order.simple_order_qty.map(f64::abs),
+            order_qty: new_order.order_qty.map(f64::abs),
+            price: new_order.price,
+            stop_px: new_order.stop_px,
+            peg_offset_value: new_order.peg_offset_value,
+            text: new_order.text.clone(),
+            ..Default::default()
+        };
+        match self.put_order(amend).await {
+            Ok(amended) => Ok((Some(amended.clone()), amended)),
+            Err(BitMEXError::Api { ref message, .. }) if message.contains("Invalid ordStatus") => Ok((None, self.post_order(new_order).await?)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Like `post_order`, for several orders in one request
+    pub fn post_order_bulk(&self, req: &[PostOrderRequest]) -> impl Future<Output = Result<Vec<PostOrderResponse>>> {
+        match req.iter().cloned().map(|order| self.on_tick(order)).collect::<Result<Vec<_>>>() {
+            Ok(req) => self.checked(req.iter().map(|order| order.symbol.as_str()), || self.transport.signed_post("/order/bulk", Some(json! {{ "orders": req }}))),
+            Err(e) => Box::pin(future::ready(Err(e))),
+        }
+    }
+
+    pub fn delete_order(&self, req: DeleteOrderRequest) -> impl Future<Output = Result<Vec<DeleteOrderResponse>>> {
+        self.transport.signed_delete("/order", Some(req))
     }
 
-    pub fn put_order(&self, req: PutOrderRequest) -> Result<impl Future<Item = PutOrderResponse, Error = Error>> {
-        Ok(self.transport.signed_put("/order", Some(req))?)
+    /// Like `delete_order`, but with the parameters in the body. `OrderId`s convert into the request.
+    pub fn cancel_order(&self, req: CancelOrderRequest) -> impl Future<Output = Result<Vec<CancelOrderResponse>>> {
+        self.transport.signed_delete_body("/order", Some(req))
     }
 
-    pub fn put_order_bulk(&self, req: &[PutOrderRequest]) -> Result<impl Future<Item = Vec<PostOrderResponse>, Error = Error>> {
-        Ok(self.transport.signed_post("/order/bulk", Some(json! {{ "orders": req }}))?)
+    pub fn cancel_orders_by_filter(&self, req: CancelOrdersByFilterRequest) -> impl Future<Output = Result<Vec<CancelOrdersByFilterResponse>>> {
+        self.checked(req.symbol.clone(), || self.transport.signed_delete_body("/order/all", Some(req)))
     }
 
-    pub fn post_order(&self, req: PostOrderRequest) -> Result<impl Future<Item = PostOrderResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/order", Some(req))?)
+    pub fn delete_order_all(&self, req: DeleteOrderAllRequest) -> impl Future<Output = Result<Vec<DeleteOrderAllResponse>>> {
+        self.checked(req.symbol.clone(), || self.transport.signed_delete("/order/all", Some(req)))
     }
 
-    pub fn post_order_bulk(&self, req: &[PostOrderRequest]) -> Result<impl Future<Item = Vec<PostOrderResponse>, Error = Error>> {
-        Ok(self.transport.signed_post("/order/bulk", Some(json! {{ "orders": req }}))?)
+    pub fn post_order_cancel_all_after(&self, req: PostOrderCancelAllAfterRequest) -> impl Future<Output = Result<PostOrderCancelAllAfterResponse>> {
+        self.transport.signed_post("/order/cancelAllAfter", Some(req))
     }
 
-    pub fn delete_order(&self, req: DeleteOrderRequest) -> Result<impl Future<Item = Vec<DeleteOrderResponse>, Error = Error>> {
-        Ok(self.transport.signed_delete("/order", Some(req))?)
+    pub fn post_order_close_position(&self, req: PostOrderClosePositionRequest) -> impl Future<Output = Result<PostOrderClosePositionResponse>> {
+        self.checked([req.symbol.clone()], || self.transport.signed_post("/order/closePosition", Some(req)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use futures::executor::block_on;
+    use futures::TryStreamExt;
+    use hyper::header::{HeaderValue, RETRY_AFTER};
+    use hyper::{Method, StatusCode};
+    use serde_json::{from_str, json, to_string, Value};
+    use tokio::runtime::Runtime;
+    use uuid::Uuid;
+
+    use crate::error::{BitMEXError, Result};
+    use crate::model::order::{CancelOrderRequest, CancelOrdersByFilterRequest, OrderId, PostOrderRequest, PutOrderRequest};
+    use crate::model::Side;
+    use crate::transport::{MockTransport, RawResponse, RetryTransport};
+    use crate::{BitMEX, SymbolCache};
+
+    #[test]
+    fn test_get_open_orders() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(order_page(500));
+        tr.push_response(order_page(2));
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let orders = block_on(bm.get_open_orders(Some("XBTUSD")))?;
+        assert_eq!(orders.len(), 502);
+
+        let reqs = tr.requests();
+        assert_eq!(reqs.len(), 2);
+        for req in &reqs {
+            assert!(req.signed);
+            assert_eq!(req.endpoint, "/order");
+            assert!(req.query.contains(&("filter".to_string(), r#"{"open":true}"#.to_string())));
+            assert!(req.query.contains(&("symbol".to_string(), "XBTUSD".to_string())));
+            assert!(req.query.contains(&("count".to_string(), "500".to_string())));
+        }
+        assert!(reqs[1].query.contains(&("start".to_string(), "500".to_string())));
+        Ok(())
     }
 
-    pub fn delete_order_all(&self, req: DeleteOrderAllRequest) -> Result<impl Future<Item = Vec<DeleteOrderAllResponse>, Error = Error>> {
-        Ok(self.transport.signed_delete("/order/all", Some(req))?)
+    #[test]
+    fn test_get_orders() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(order_page(2));
+        tr.push_response("[]");
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let orders = block_on(bm.get_orders(Some("XBTUSD"), Some(json!({ "ordStatus": "Filled" })), Some(2), Some(10), Some(true)))?;
+        assert_eq!(orders.len(), 2);
+        block_on(bm.get_orders(None, None, None, None, None))?;
+
+        let reqs = tr.requests();
+        let expected = [("symbol", "XBTUSD"), ("filter", r#"{"ordStatus":"Filled"}"#), ("count", "2"), ("start", "10"), ("reverse", "true")];
+        for (k, v) in expected.iter() {
+            assert!(reqs[0].query.contains(&(k.to_string(), v.to_string())), "{} missing", k);
+        }
+        assert_eq!(reqs[1].query, vec![("count".to_string(), "100".to_string())]);
+        Ok(())
     }
 
-    pub fn post_order_cancel_all_after(&self, req: PostOrderCancelAllAfterRequest) -> Result<impl Future<Item = PostOrderCancelAllAfterResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/order/cancelAllAfter", Some(req))?)
+    #[test]
+    fn test_cancel_order() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response("[]");
+        tr.push_response("[]");
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let id = Uuid::parse_str("9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e").unwrap();
+        block_on(bm.cancel_order(OrderId::OrderID(id).into()))?;
+        block_on(bm.cancel_order(CancelOrderRequest {
+            text: Some("bye".into()),
+            ..OrderId::ClOrdID("my-order".into()).into()
+        }))?;
+
+        let reqs = tr.requests();
+        assert_eq!((&reqs[0].method, reqs[0].query.len()), (&Method::DELETE, 0));
+        assert_eq!(reqs[0].body, r#"{"orderID":"9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e"}"#);
+        assert_eq!(reqs[1].body, r#"{"clOrdID":"my-order","text":"bye"}"#);
+        Ok(())
     }
 
-    pub fn post_order_close_position(&self, req: PostOrderClosePositionRequest) -> Result<impl Future<Item = PostOrderClosePositionResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/order/closePosition", Some(req))?)
+    #[test]
+    fn test_cancel_orders_by_filter() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response("[]");
+
+        let bm = BitMEX::with_transport(tr.clone());
+        block_on(bm.cancel_orders_by_filter(CancelOrdersByFilterRequest {
+            symbol: Some("XBTUSD".into()),
+            filter: Some(json!({ "side": "Buy" })),
+            text: None,
+        }))?;
+
+        let req = &tr.requests()[0];
+        assert_eq!((req.endpoint.as_str(), req.signed), ("/order/all", true));
+        assert_eq!(req.body, r#"{"symbol":"XBTUSD","filter":{"side":"Buy"}}"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel_and_replace() -> Result<()> {
+        let tr = MockTransport::new();
+        let bm = BitMEX::with_transport(tr.clone());
+        let order: Value = from_str(&order_page(1))?;
+        let order = to_string(&order[0])?;
+        let new_order = || PostOrderRequest {
+            price: Some(6000.5),
+            ..PostOrderRequest::new("XBTUSD", Side::Sell, 1)
+        };
+
+        tr.push_response(order.clone());
+        let id = Uuid::parse_str("9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e").unwrap();
+        let (amended, placed) = block_on(bm.cancel_and_replace(OrderId::OrderID(id), new_order()))?;
+        assert_eq!(amended.map(|order| order.order_id), Some(placed.order_id));
+        let req = &tr.requests()[0];
+        assert_eq!((&req.method, req.endpoint.as_str()), (&Method::PUT, "/order"));
+        assert_eq!(req.body, r#"{"symbol":"XBTUSD","orderID":"9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e","orderQty":1.0,"price":6000.5}"#);
+
+        // The order is gone, so a new one is placed
+        tr.push_status(StatusCode::BAD_REQUEST, r#"{"error":{"message":"Invalid ordStatus","name":"HTTPError"}}"#);
+        tr.push_response(order.clone());
+        let (amended, _) = block_on(bm.cancel_and_replace(OrderId::ClOrdID("my-order".into()), new_order()))?;
+        assert!(amended.is_none());
+        let reqs = tr.requests();
+        assert_eq!(reqs[1].body, r#"{"symbol":"XBTUSD","origClOrdID":"my-order","orderQty":1.0,"price":6000.5}"#);
+        assert_eq!((&reqs[2].method, reqs[2].body.as_str()), (&Method::POST, r#"{"symbol":"XBTUSD","side":"Sell","orderQty":1.0,"price":6000.5}"#));
+
+        tr.push_status(StatusCode::BAD_REQUEST, r#"{"error":{"message":"Invalid orderQty","name":"ValidationError"}}"#);
+        assert!(block_on(bm.cancel_and_replace(OrderId::ClOrdID("my-order".into()), new_order())).is_err());
+        assert_eq!(tr.requests().len(), 4);
+
+        // A clOrdID generated by with_generated_cl_ord_id looks like an orderID, but is sent as origClOrdID
+        tr.push_response(order);
+        let cl_ord_id = PostOrderRequest::default().with_generated_cl_ord_id().cl_ord_id.unwrap();
+        let (amended, _) = block_on(bm.cancel_and_replace(OrderId::ClOrdID(cl_ord_id.clone()), new_order()))?;
+        assert!(amended.is_some());
+        let body: Value = from_str(&tr.requests()[4].body)?;
+        assert_eq!((body["origClOrdID"].as_str(), body.get("orderID")), (Some(cl_ord_id.as_str()), None));
+        Ok(())
+    }
+
+    fn order_page(len: usize) -> String {
+        let order = json!({
+            "orderID": "9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e", "clOrdID": "", "clOrdLinkID": "", "account": 2, "symbol": "XBTUSD", "side": "Buy",
+            "orderQty": 1, "price": 6000.5, "pegPriceType": "", "currency": "USD", "settlCurrency": "XBt", "ordType": "Limit", "timeInForce": "GoodTillCancel",
+            "execInst": "", "contingencyType": "", "exDestination": "XBME", "ordStatus": "Filled", "triggered": "", "workingIndicator": false, "ordRejReason": "",
+            "leavesQty": 0, "cumQty": 1, "avgPx": 6000.5, "multiLegReportingType": "SingleSecurity", "text": "", "transactTime": "2019-01-01T00:00:00.000Z",
+            "timestamp": "2019-01-01T00:00:00.000Z"
+        });
+        to_string(&vec![order; len]).unwrap()
+    }
+
+    #[test]
+    fn test_stream_orders() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(order_page(500));
+        tr.push_response(order_page(3));
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let orders: Vec<_> = block_on(bm.stream_orders(Some("XBTUSD"), None, None).try_collect())?;
+        assert_eq!(orders.len(), 503);
+
+        let reqs = tr.requests();
+        assert_eq!(reqs.len(), 2);
+        assert!(reqs[0].query.contains(&("count".to_string(), "500".to_string())));
+        assert!(reqs[0].query.contains(&("start".to_string(), "0".to_string())));
+        assert!(reqs[1].query.contains(&("start".to_string(), "500".to_string())));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_orders_rate_limited() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(order_page(500));
+        let mut rate_limited = RawResponse::with_status(StatusCode::TOO_MANY_REQUESTS, "");
+        rate_limited.headers.insert(RETRY_AFTER, HeaderValue::from(0));
+        tr.push_raw(rate_limited);
+        tr.push_response(order_page(3));
+
+        let rt = Runtime::new()?;
+        let bm = BitMEX::with_transport(RetryTransport::new(tr.clone(), 1).with_backoff(Duration::from_secs(600)));
+        let orders: Vec<_> = rt.block_on(bm.stream_orders(Some("XBTUSD"), None, None).try_collect())?;
+        assert_eq!(orders.len(), 503);
+
+        // The rate limited page is fetched again after the Retry-After
+        let starts: Vec<_> = tr.requests().iter().filter_map(|req| req.query.iter().find(|(k, _)| k == "start").map(|(_, v)| v.clone())).collect();
+        assert_eq!(starts, vec!["0", "500", "500"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_post_order_idempotent() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_error(BitMEXError::RateLimited { retry_after: None, reset_at: None });
+
+        let bm = BitMEX::with_transport(tr.clone());
+        tr.push_error(BitMEXError::Timeout);
+        let mut req = PostOrderRequest {
+            symbol: "XBTUSD".into(),
+            ..Default::default()
+        };
+        assert!(block_on(bm.post_order_idempotent(&mut req)).is_err());
+        let id = req.cl_ord_id.clone().unwrap();
+        assert!(Uuid::parse_str(&id).is_ok());
+
+        // Sending the same order again after the failure reuses its id
+        assert!(block_on(bm.post_order_idempotent(&mut req)).is_err());
+        let ids: Vec<Value> = tr.requests().iter().map(|req| from_str::<Value>(&req.body).map(|body| body["clOrdID"].clone())).collect::<serde_json::Result<_>>()?;
+        assert_eq!(ids, vec![json!(id), json!(id)]);
+
+        tr.push_error(BitMEXError::Timeout);
+        assert!(matches!(bm.clone().with_cl_ord_id_namespace("market_maker_for_xbtusd/"), Err(BitMEXError::InvalidConfig(_))));
+        let bm = bm.with_cl_ord_id_namespace("mm/")?;
+        assert!(block_on(bm.post_order_idempotent(&mut PostOrderRequest::new("XBTUSD", Side::Buy, 1))).is_err());
+        let body: Value = from_str(&tr.requests()[2].body)?;
+        assert!(body["clOrdID"].as_str().unwrap().starts_with("mm/"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_put_order_bulk() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(
+            r#"[{"orderID":"9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e","clOrdID":"b","origClOrdID":"a","account":2,"symbol":"XBTUSD","side":"Buy","orderQty":110,"price":3700,"ordType":"Limit","timeInForce":"GoodTillCancel","ordStatus":"New","leavesQty":110,"cumQty":0,"transactTime":"2019-01-01T00:00:00.000Z","timestamp":"2019-01-01T00:00:00.000Z"}]"#,
+        );
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let order_id = Uuid::parse_str("9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e").unwrap();
+        let req = [
+            PutOrderRequest {
+                symbol: "XBTUSD".into(),
+                order_id: Some(order_id),
+                order_qty: Some(110.),
+                ..Default::default()
+            },
+            PutOrderRequest {
+                symbol: "XBTUSD".into(),
+                orig_cl_ord_id: Some("a".into()),
+                cl_ord_id: Some("b".into()),
+                price: Some(3700.),
+                ..Default::default()
+            },
+        ];
+        let orders = block_on(bm.put_order_bulk(&req))?;
+        assert_eq!(orders[0].order_qty, 110);
+
+        let req = &tr.requests()[0];
+        assert_eq!((&req.method, req.endpoint.as_str(), req.signed), (&Method::PUT, "/order/bulk", true));
+        let body: Value = from_str(&req.body)?;
+        assert_eq!(body["orders"][0]["orderID"], json!(order_id));
+        assert_eq!((&body["orders"][1]["origClOrdID"], &body["orders"][1]["clOrdID"]), (&json!("a"), &json!("b")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_symbol() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_error(BitMEXError::Timeout);
+
+        let bm = BitMEX::with_transport(tr.clone()).with_symbol_cache(SymbolCache::new(["XBTUSD", "ETHUSD"]));
+        let err = block_on(bm.post_order(PostOrderRequest::new("XBTUSDT", Side::Buy, 100))).unwrap_err();
+        assert!(matches!(err, BitMEXError::UnknownSymbol(symbol) if symbol == "XBTUSDT"));
+        let bulk = [PostOrderRequest::new("ETHUSD", Side::Buy, 1), PostOrderRequest::new("ETHUSDT", Side::Buy, 1)];
+        assert!(matches!(block_on(bm.post_order_bulk(&bulk)), Err(BitMEXError::UnknownSymbol(_))));
+        assert!(tr.requests().is_empty());
+
+        assert!(matches!(block_on(bm.post_order(PostOrderRequest::new("XBTUSD", Side::Buy, 100))), Err(BitMEXError::Timeout)));
+        assert_eq!(tr.requests().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_to_cached_tick_size() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_error(BitMEXError::Timeout);
+        tr.push_error(BitMEXError::Timeout);
+
+        let cache = SymbolCache::new(["ETHUSD"]).with_tick_size("XBTUSD", 0.5);
+        let bm = BitMEX::with_transport(tr.clone()).with_symbol_cache(cache);
+        let order = |price| PostOrderRequest {
+            price: Some(price),
+            ..PostOrderRequest::new("XBTUSD", Side::Buy, 100)
+        };
+        assert!(block_on(bm.post_order(order(219.00000000001))).is_err());
+        assert_eq!(tr.requests()[0].body, r#"{"symbol":"XBTUSD","side":"Buy","orderQty":100.0,"price":219.0}"#);
+
+        assert!(matches!(block_on(bm.post_order(order(219.3))), Err(BitMEXError::OffTick { .. })));
+        assert!(matches!(block_on(bm.post_order_bulk(&[order(219.), order(219.3)])), Err(BitMEXError::OffTick { .. })));
+        assert_eq!(tr.requests().len(), 1);
+
+        // Without a tick size the price is sent as is
+        let eth = PostOrderRequest {
+            price: Some(130.07),
+            ..PostOrderRequest::new("ETHUSD", Side::Buy, 1)
+        };
+        assert!(block_on(bm.post_order(eth)).is_err());
+        assert!(tr.requests()[1].body.contains(r#""price":130.07"#));
+        Ok(())
     }
 }
@@ -1,12 +1,12 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::order_book::{GetOrderBookL2Request, GetOrderBookL2Response};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_order_book_l2(&self, req: GetOrderBookL2Request) -> Result<impl Future<Item = Vec<GetOrderBookL2Response>, Error = Error>> {
-        Ok(self.transport.get("/orderBook/L2", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_order_book_l2(&self, req: GetOrderBookL2Request) -> impl Future<Output = Result<Vec<GetOrderBookL2Response>>> {
+        self.transport.get("/orderBook/L2", Some(req))
     }
 }
@@ -1,4 +1,3 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
@@ -6,22 +5,51 @@ use crate::model::position::{
     GetPositionRequest, GetPositionResponse, PostPositionIsolateRequest, PostPositionIsolateResponse, PostPositionLeverageRequest, PostPositionLeverageResponse,
     PostPositionRiskLimitRequest, PostPositionRiskLimitResponse, PostPositionTransferMarginRequest, PostPositionTransferMarginResponse,
 };
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_position(&self, req: GetPositionRequest) -> Result<impl Future<Item = Vec<GetPositionResponse>, Error = Error>> {
-        Ok(self.transport.signed_get("/position", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_position(&self, req: GetPositionRequest) -> impl Future<Output = Result<Vec<GetPositionResponse>>> {
+        self.transport.signed_get("/position", Some(req))
     }
-    pub fn post_position_isolate(&self, req: PostPositionIsolateRequest) -> Result<impl Future<Item = PostPositionIsolateResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/position/isolate", Some(req))?)
+    pub fn post_position_isolate(&self, req: PostPositionIsolateRequest) -> impl Future<Output = Result<PostPositionIsolateResponse>> {
+        self.checked([req.symbol.clone()], || self.transport.signed_post("/position/isolate", Some(req)))
     }
-    pub fn post_position_leverage(&self, req: PostPositionLeverageRequest) -> Result<impl Future<Item = PostPositionLeverageResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/position/leverage", Some(req))?)
+    pub fn post_position_leverage(&self, req: PostPositionLeverageRequest) -> impl Future<Output = Result<PostPositionLeverageResponse>> {
+        self.checked([req.symbol.clone()], || self.transport.signed_post("/position/leverage", Some(req)))
     }
-    pub fn post_position_risk_limit(&self, req: PostPositionRiskLimitRequest) -> Result<impl Future<Item = PostPositionRiskLimitResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/position/riskLimit", Some(req))?)
+    pub fn post_position_risk_limit(&self, req: PostPositionRiskLimitRequest) -> impl Future<Output = Result<PostPositionRiskLimitResponse>> {
+        self.checked([req.symbol.clone()], || self.transport.signed_post("/position/riskLimit", Some(req)))
     }
-    pub fn post_position_transfer_margin(&self, req: PostPositionTransferMarginRequest) -> Result<impl Future<Item = PostPositionTransferMarginResponse, Error = Error>> {
-        Ok(self.transport.signed_post("/position/transferMargin", Some(req))?)
+    pub fn post_position_transfer_margin(&self, req: PostPositionTransferMarginRequest) -> impl Future<Output = Result<PostPositionTransferMarginResponse>> {
+        self.checked([req.symbol.clone()], || self.transport.signed_post("/position/transferMargin", Some(req)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+
+    use crate::error::Result;
+    use crate::model::position::GetPositionRequest;
+    use crate::model::Satoshi;
+    use crate::transport::MockTransport;
+    use crate::BitMEX;
+
+    #[test]
+    fn test_get_position() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(
+            r#"[{"account":2,"symbol":"XBTUSD","currency":"XBt","currentQty":-300,"currentCost":4117233,"riskLimit":20000000000,"realisedPnl":-1841,"unrealisedPnl":-12386,"avgEntryPrice":7286.5,"liquidationPrice":100000000,"markPrice":7337.18,"homeNotional":-0.04088,"leverage":100,"isOpen":true,"timestamp":"2019-01-01T00:00:00.000Z"}]"#,
+        );
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let positions = block_on(bm.get_position(GetPositionRequest::default()))?;
+        let xbt = &positions[0];
+        assert_eq!((xbt.current_qty, xbt.current_cost, xbt.risk_limit), (-300, Satoshi(4117233), Satoshi(20000000000)));
+        assert_eq!((xbt.realised_pnl.to_btc(), xbt.unrealised_pnl), (-0.00001841, Satoshi(-12386)));
+        assert_eq!((xbt.avg_entry_price, xbt.liquidation_price, xbt.mark_price), (Some(7286.5), Some(100000000.), Some(7337.18)));
+        assert!(xbt.is_open && tr.requests()[0].signed);
+        Ok(())
     }
 }
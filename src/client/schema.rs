@@ -0,0 +1,17 @@
+use futures::Future;
+
+use crate::error::Result;
+use crate::model::swagger::{GetSchemaRequest, GetSchemaResponse, GetSchemaWebsocketHelpResponse};
+use crate::transport::TransportTrait;
+use crate::BitMEX;
+
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_schema(&self, model: &str) -> impl Future<Output = Result<GetSchemaResponse>> {
+        let req = GetSchemaRequest { model: Some(model.to_string()) };
+        self.transport.get("/schema", Some(req))
+    }
+
+    pub fn get_websocket_help(&self) -> impl Future<Output = Result<GetSchemaWebsocketHelpResponse>> {
+        self.transport.get::<_, ()>("/schema/websocketHelp", None)
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Duration, Utc};
+use hyper::header::DATE;
+use hyper::Method;
+
+use crate::error::{BitMEXError, Result};
+use crate::transport::TransportTrait;
+use crate::BitMEX;
+
+/// The clock of BitMEX, as far as the `Date` header of a response tells
+#[derive(Clone, Copy, Debug)]
+pub struct ServerTime {
+    pub server_time: DateTime<Utc>,
+    /// `server_time` minus the local time halfway through the request. The header has a resolution
+    /// of one second, so has the offset.
+    pub offset: Duration,
+}
+
+impl<T: TransportTrait> BitMEX<T> {
+    /// BitMEX has no time endpoint, this reads the `Date` header of the cheapest public request instead.
+    /// Useful to check the clock before choosing an `api-expires` window.
+    pub async fn server_time(&self) -> Result<ServerTime> {
+        let query = vec![("count".to_string(), "1".to_string()), ("columns".to_string(), r#"["symbol"]"#.to_string())];
+        let sent = Utc::now();
+        let resp = self.transport.request_raw(Method::GET, "/instrument", query, String::new()).await?;
+        let received = Utc::now();
+
+        let server_time = resp
+            .headers
+            .get(DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+            .ok_or_else(|| BitMEXError::Other("Response without a valid Date header".to_string()))?
+            .with_timezone(&Utc);
+        let local_time = sent + (received - sent) / 2;
+
+        Ok(ServerTime {
+            server_time,
+            offset: server_time - local_time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, TimeZone, Utc};
+    use futures::executor::block_on;
+    use hyper::header::{HeaderValue, DATE};
+
+    use crate::error::Result;
+    use crate::transport::{MockTransport, RawResponse};
+    use crate::BitMEX;
+
+    #[test]
+    fn test_server_time() -> Result<()> {
+        let tr = MockTransport::new();
+        let mut resp = RawResponse::ok("[]");
+        resp.headers.insert(DATE, HeaderValue::from_static("Tue, 01 Jan 2019 00:00:00 GMT"));
+        tr.push_raw(resp);
+        tr.push_response("[]");
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let time = block_on(bm.server_time())?;
+        assert_eq!(time.server_time, Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap());
+        assert!(time.offset < Duration::zero());
+        assert_eq!(tr.requests()[0].endpoint, "/instrument");
+
+        assert!(block_on(bm.server_time()).is_err());
+        Ok(())
+    }
+}
@@ -1,12 +1,12 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::settlement::{GetSettlementRequest, GetSettlementResponse};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_settlement(&self, req: GetSettlementRequest) -> Result<impl Future<Item = GetSettlementResponse, Error = Error>> {
-        Ok(self.transport.signed_get("/settlement", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_settlement(&self, req: GetSettlementRequest) -> impl Future<Output = Result<GetSettlementResponse>> {
+        self.transport.signed_get("/settlement", Some(req))
     }
 }
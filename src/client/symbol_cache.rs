@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use crate::error::{BitMEXError, Result};
+use crate::transport::TransportTrait;
+use crate::BitMEX;
+
+/// The symbols of the instruments open for trading, checked by the order and position methods of a
+/// client set up with `BitMEX::with_symbol_cache`. Catches typos like `XBTUSDT` for `XBTUSD` before
+/// the request is sent.
+///
+/// A cache that knows the tick size of a symbol also rounds the prices of new orders to it, see
+/// `PostOrderRequest::with_tick_size`.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolCache {
+    // Symbol -> tick size
+    symbols: HashMap<String, Option<f64>>,
+}
+
+impl SymbolCache {
+    /// A cache of `symbols` without tick sizes
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(symbols: I) -> Self {
+        SymbolCache {
+            symbols: symbols.into_iter().map(|symbol| (symbol.into(), None)).collect(),
+        }
+    }
+
+    /// The symbols and tick sizes of `/instrument/active`. Instruments listed later are unknown
+    /// until the cache is fetched again.
+    pub async fn fetch<T: TransportTrait>(bm: &BitMEX<T>) -> Result<Self> {
+        let instruments = bm.get_instrument_active().await?;
+        let symbols = instruments.into_iter().filter_map(|instrument| Some((instrument.symbol?, instrument.tick_size)));
+        Ok(SymbolCache { symbols: symbols.collect() })
+    }
+
+    /// Adds `symbol` with its tick size, or sets the tick size of a cached symbol
+    pub fn with_tick_size(mut self, symbol: &str, tick_size: f64) -> Self {
+        self.symbols.insert(symbol.to_string(), Some(tick_size));
+        self
+    }
+
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.symbols.contains_key(symbol)
+    }
+
+    pub fn tick_size(&self, symbol: &str) -> Option<f64> {
+        self.symbols.get(symbol).copied().flatten()
+    }
+
+    /// Fails with `BitMEXError::UnknownSymbol` if `symbol` isn't cached
+    pub fn check(&self, symbol: &str) -> Result<()> {
+        if self.contains(symbol) {
+            Ok(())
+        } else {
+            Err(BitMEXError::UnknownSymbol(symbol.to_string()))
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+
+    use super::SymbolCache;
+    use crate::error::{BitMEXError, Result};
+    use crate::transport::MockTransport;
+    use crate::BitMEX;
+
+    #[test]
+    fn test_fetch_symbol_cache() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(r#"[{"symbol":"XBTUSD","state":"Open","tickSize":0.5,"timestamp":"2019-01-01T00:00:00.000Z"},{"symbol":"ETHUSD","state":"Open","timestamp":"2019-01-01T00:00:00.000Z"}]"#);
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let symbols = block_on(SymbolCache::fetch(&bm))?;
+        assert_eq!(tr.requests()[0].endpoint, "/instrument/active");
+        assert_eq!(symbols.len(), 2);
+        assert!(symbols.contains("XBTUSD") && symbols.check("ETHUSD").is_ok());
+        assert!(matches!(symbols.check("XBTUSDT"), Err(BitMEXError::UnknownSymbol(symbol)) if symbol == "XBTUSDT"));
+        assert_eq!((symbols.tick_size("XBTUSD"), symbols.tick_size("ETHUSD")), (Some(0.5), None));
+        assert_eq!(symbols.with_tick_size("ETHUSD", 0.05).tick_size("ETHUSD"), Some(0.05));
+        Ok(())
+    }
+}
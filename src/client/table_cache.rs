@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::{from_value, Value};
+
+use crate::error::{BitMEXError, Result};
+use crate::model::websocket::{Action, TableMessage};
+
+/// The current rows of a websocket table, maintained from its partial, insert, update and delete
+/// messages. Rows are identified by the `keys` of the partial, a row's key is the value of its key
+/// columns joined with `:`, e.g. `2:XBTUSD:XBt` for a position.
+///
+/// Updates only carry the keys and the changed fields, so the raw rows are kept and every update is
+/// merged into its row before parsing it into `T`.
+#[derive(Clone, Debug)]
+pub struct TableCache<T> {
+    keys: Option<Vec<String>>,
+    fixed_keys: bool,
+    raw: HashMap<String, Value>,
+    rows: HashMap<String, T>,
+}
+
+impl<T> Default for TableCache<T> {
+    fn default() -> Self {
+        TableCache {
+            keys: None,
+            fixed_keys: false,
+            raw: HashMap::new(),
+            rows: HashMap::new(),
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Clone> TableCache<T> {
+    /// A cache taking its keys from the first partial. Deltas before it are a `SequenceGap`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache with keys known in advance, the keys of partials are ignored. Allows seeding the
+    /// cache with REST rows before the partial arrives.
+    pub fn with_keys(keys: &[&str]) -> Self {
+        TableCache {
+            keys: Some(keys.iter().map(ToString::to_string).collect()),
+            fixed_keys: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&T> {
+        self.rows.get(key)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &T> {
+        self.rows.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<T> {
+        self.raw.remove(key);
+        self.rows.remove(key)
+    }
+
+    /// Inserts or replaces a full row, e.g. from a REST snapshot
+    pub fn insert(&mut self, row: Value) -> Result<(String, T)> {
+        let key = self.key_of(&row)?;
+        let parsed: T = from_value(row.clone())?;
+        self.raw.insert(key.clone(), row);
+        self.rows.insert(key.clone(), parsed.clone());
+        Ok((key, parsed))
+    }
+
+    /// Applies a table message and returns the rows it touched in their new state. Deleted rows are
+    /// returned with their last state. An update of a row missing from the cache is a `SequenceGap`,
+    /// a row the update leaves unparseable is kept as it was.
+    pub fn apply(&mut self, msg: &TableMessage<Value>) -> Result<Vec<(String, T)>> {
+        let mut changes = vec![];
+        match msg.action {
+            Action::Partial => {
+                if !self.fixed_keys {
+                    self.keys = msg.keys.clone();
+                }
+                self.raw.clear();
+                self.rows.clear();
+                for row in &msg.data {
+                    changes.push(self.insert(row.clone())?);
+                }
+            }
+            Action::Insert => {
+                self.check_keys(msg)?;
+                for row in &msg.data {
+                    changes.push(self.insert(row.clone())?);
+                }
+            }
+            Action::Update => {
+                self.check_keys(msg)?;
+                for update in &msg.data {
+                    let key = self.key_of(update)?;
+                    let mut row = match self.raw.get(&key) {
+                        Some(row) => row.clone(),
+                        None => return Err(BitMEXError::SequenceGap(msg.table.clone())),
+                    };
+                    merge(&mut row, update);
+                    changes.push(self.insert(row)?);
+                }
+            }
+            Action::Delete => {
+                self.check_keys(msg)?;
+                for row in &msg.data {
+                    let key = self.key_of(row)?;
+                    if let Some(row) = self.remove(&key) {
+                        changes.push((key, row));
+                    }
+                }
+            }
+            Action::Unknown(_) => {}
+        }
+        Ok(changes)
+    }
+
+    fn check_keys(&self, msg: &TableMessage<Value>) -> Result<()> {
+        match self.keys {
+            Some(_) => Ok(()),
+            None => Err(BitMEXError::SequenceGap(msg.table.clone())),
+        }
+    }
+
+    fn key_of(&self, row: &Value) -> Result<String> {
+        let keys = self.keys.as_deref().unwrap_or(&[]);
+        let values = keys
+            .iter()
+            .map(|key| match row.get(key) {
+                Some(Value::String(value)) => Ok(value.clone()),
+                Some(value) if !value.is_null() => Ok(value.to_string()),
+                _ => Err(BitMEXError::Other(format!("Row without {}: {}", key, row))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(values.join(":"))
+    }
+}
+
+fn merge(row: &mut Value, update: &Value) {
+    if let (Value::Object(row), Value::Object(update)) = (row, update) {
+        for (k, v) in update {
+            row.insert(k.clone(), v.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_derive::Deserialize;
+    use serde_json::{from_str, Value};
+
+    use super::TableCache;
+    use crate::error::{BitMEXError, Result};
+    use crate::model::websocket::TableMessage;
+
+    #[test]
+    fn test_table_cache() -> Result<()> {
+        let mut cache = TableCache::<Value>::new();
+        let insert: TableMessage<Value> = from_str(r#"{"table":"position","action":"insert","data":[{"account":2,"symbol":"XBTUSD","currency":"XBt"}]}"#)?;
+        assert!(matches!(cache.apply(&insert).unwrap_err(), BitMEXError::SequenceGap(_)));
+
+        let messages = [
+            r#"{"table":"position","action":"partial","keys":["account","symbol","currency"],"data":[{"account":2,"symbol":"XBTUSD","currency":"XBt","currentQty":100,"leverage":10}]}"#,
+            r#"{"table":"position","action":"insert","data":[{"account":2,"symbol":"ETHUSD","currency":"XBt","currentQty":-5}]}"#,
+            r#"{"table":"position","action":"update","data":[{"account":2,"symbol":"XBTUSD","currency":"XBt","currentQty":200}]}"#,
+            r#"{"table":"position","action":"delete","data":[{"account":2,"symbol":"ETHUSD","currency":"XBt"}]}"#,
+        ];
+        let mut changes = vec![];
+        for msg in messages.iter() {
+            changes.extend(cache.apply(&from_str(msg)?)?);
+        }
+        let keys: Vec<_> = changes.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["2:XBTUSD:XBt", "2:ETHUSD:XBt", "2:XBTUSD:XBt", "2:ETHUSD:XBt"]);
+
+        assert_eq!(cache.len(), 1);
+        let xbt = cache.get("2:XBTUSD:XBt").unwrap();
+        assert_eq!((&xbt["currentQty"], &xbt["leverage"]), (&Value::from(200), &Value::from(10)));
+
+        let update: TableMessage<Value> = from_str(r#"{"table":"position","action":"update","data":[{"account":2,"symbol":"ETHUSD","currency":"XBt","currentQty":-10}]}"#)?;
+        assert!(matches!(cache.apply(&update).unwrap_err(), BitMEXError::SequenceGap(_)));
+        assert!(cache.get("2:ETHUSD:XBt").is_none());
+        Ok(())
+    }
+
+    #[derive(Clone, Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Position {
+        current_qty: i64,
+        leverage: f64,
+    }
+
+    #[test]
+    fn test_unparseable_update() -> Result<()> {
+        let mut cache = TableCache::<Position>::new();
+        let messages = [
+            r#"{"table":"position","action":"partial","keys":["symbol"],"data":[{"symbol":"XBTUSD","currentQty":100,"leverage":10}]}"#,
+            r#"{"table":"position","action":"update","data":[{"symbol":"XBTUSD","currentQty":"many"}]}"#,
+            r#"{"table":"position","action":"update","data":[{"symbol":"XBTUSD","currentQty":200}]}"#,
+        ];
+        cache.apply(&from_str(messages[0])?)?;
+        assert!(matches!(cache.apply(&from_str(messages[1])?).unwrap_err(), BitMEXError::Json(_)));
+        assert_eq!(cache.get("XBTUSD").unwrap().current_qty, 100);
+
+        // The next update still merges into the full row
+        cache.apply(&from_str(messages[2])?)?;
+        let xbt = cache.get("XBTUSD").unwrap();
+        assert_eq!((xbt.current_qty, xbt.leverage), (200, 10.));
+        Ok(())
+    }
+}
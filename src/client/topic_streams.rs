@@ -0,0 +1,202 @@
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures::stream::Stream;
+
+use crate::client::websocket::BitMEXWebsocket;
+use crate::error::Result;
+use crate::model::websocket::Message;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Topic {
+    Trade,
+    Quote,
+    OrderBook,
+    Order,
+    Position,
+    Other,
+}
+
+impl Topic {
+    fn of(msg: &Message) -> Self {
+        match msg {
+            Message::Trade(_) => Topic::Trade,
+            Message::Quote(_) => Topic::Quote,
+            Message::OrderBookL2(_) => Topic::OrderBook,
+            Message::Table(table) if table.table == "order" => Topic::Order,
+            Message::Table(table) if table.table == "position" => Topic::Position,
+            _ => Topic::Other,
+        }
+    }
+}
+
+/// The messages of one websocket split by topic, see `BitMEXWebsocket::split_by_topic`. Each
+/// stream yields the messages of its topic in the order they arrived.
+///
+/// Every stream reads from the shared socket when it has nothing queued, the messages of other
+/// topics are queued for their streams. Messages of a dropped stream are discarded, those of a
+/// stream that is never polled pile up. Errors are returned by the stream that read them, all
+/// streams end with the socket.
+pub struct TopicStreams<S> {
+    /// `trade`
+    pub trades: TopicStream<S>,
+    /// `quote`
+    pub quotes: TopicStream<S>,
+    /// `orderBookL2` and `orderBookL2_25`
+    pub order_book: TopicStream<S>,
+    /// `order`
+    pub orders: TopicStream<S>,
+    /// `position`
+    pub positions: TopicStream<S>,
+    /// Every other table, the bucketed ones included, and the non-table messages like acks and pongs
+    pub other: TopicStream<S>,
+}
+
+impl<S: Stream<Item = Result<Message>> + Unpin> TopicStreams<S> {
+    pub fn new(stream: S) -> Self {
+        let shared = Arc::new(Mutex::new(Demux {
+            stream,
+            queues: HashMap::new(),
+            wakers: HashMap::new(),
+            done: false,
+        }));
+        let stream = |topic| {
+            shared.lock().unwrap().queues.insert(topic, VecDeque::new());
+            TopicStream { topic, shared: shared.clone() }
+        };
+        TopicStreams {
+            trades: stream(Topic::Trade),
+            quotes: stream(Topic::Quote),
+            order_book: stream(Topic::OrderBook),
+            orders: stream(Topic::Order),
+            positions: stream(Topic::Position),
+            other: stream(Topic::Other),
+        }
+    }
+}
+
+impl BitMEXWebsocket {
+    /// Splits the incoming messages into a stream per topic. Subscribe before splitting, the socket
+    /// can't be written to afterwards.
+    pub fn split_by_topic(self) -> TopicStreams<Self> {
+        TopicStreams::new(self)
+    }
+}
+
+struct Demux<S> {
+    stream: S,
+    // Only the streams not dropped yet have a queue
+    queues: HashMap<Topic, VecDeque<Message>>,
+    wakers: HashMap<Topic, Waker>,
+    done: bool,
+}
+
+/// The messages of one topic, see `TopicStreams`
+pub struct TopicStream<S> {
+    topic: Topic,
+    shared: Arc<Mutex<Demux<S>>>,
+}
+
+impl<S: Stream<Item = Result<Message>> + Unpin> Stream for TopicStream<S> {
+    type Item = Result<Message>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut demux = self.shared.lock().unwrap();
+        let demux = &mut *demux;
+        if let Some(msg) = demux.queues.get_mut(&self.topic).and_then(VecDeque::pop_front) {
+            return Poll::Ready(Some(Ok(msg)));
+        }
+        if demux.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            match Pin::new(&mut demux.stream).poll_next(cx) {
+                Poll::Ready(Some(Ok(msg))) => {
+                    let topic = Topic::of(&msg);
+                    if topic == self.topic {
+                        return Poll::Ready(Some(Ok(msg)));
+                    }
+                    if let Some(queue) = demux.queues.get_mut(&topic) {
+                        queue.push_back(msg);
+                        if let Some(waker) = demux.wakers.remove(&topic) {
+                            waker.wake();
+                        }
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => {
+                    demux.done = true;
+                    demux.wakers.drain().for_each(|(_, waker)| waker.wake());
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => {
+                    demux.wakers.insert(self.topic, cx.waker().clone());
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+impl<S> Drop for TopicStream<S> {
+    fn drop(&mut self) {
+        let mut demux = match self.shared.lock() {
+            Ok(demux) => demux,
+            Err(_) => return,
+        };
+        demux.queues.remove(&self.topic);
+        demux.wakers.remove(&self.topic);
+        // The socket may have been waiting to wake this stream, let another one read it
+        if let Some(waker) = demux.wakers.values().next() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+    use serde_json::from_str;
+
+    use super::TopicStreams;
+    use crate::error::{BitMEXError, Result};
+    use crate::model::websocket::Message;
+
+    fn message(json: &str) -> Result<Message> {
+        Ok(from_str(json)?)
+    }
+
+    #[test]
+    fn test_split_by_topic() -> Result<()> {
+        let messages = vec![
+            message(r#"{"success":true,"subscribe":"trade:XBTUSD","request":{"op":"subscribe","args":["trade:XBTUSD"]}}"#)?,
+            message(r#"{"table":"trade","action":"insert","data":[{"timestamp":"2019-01-01T00:00:00.000Z","symbol":"XBTUSD","side":"Buy","size":100,"price":3700.5}]}"#)?,
+            message(r#"{"table":"order","action":"update","data":[{"orderID":"9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e","leavesQty":50}]}"#)?,
+            message(r#"{"table":"quote","action":"insert","data":[{"timestamp":"2019-01-01T00:00:00.000Z","symbol":"XBTUSD","bidSize":100,"bidPrice":3700,"askPrice":3700.5,"askSize":200}]}"#)?,
+            message(r#"{"table":"trade","action":"insert","data":[{"timestamp":"2019-01-01T00:00:01.000Z","symbol":"XBTUSD","side":"Sell","size":50,"price":3700}]}"#)?,
+        ];
+        let mut messages: Vec<_> = messages.into_iter().map(Ok).collect();
+        messages.push(Err(BitMEXError::Timeout));
+        let streams = TopicStreams::new(stream::iter(messages));
+        let TopicStreams {
+            mut trades, mut orders, quotes, other, ..
+        } = streams;
+
+        // Reading the orders queues the trades read on the way
+        assert!(matches!(block_on(orders.next()), Some(Ok(Message::Table(table))) if table.table == "order"));
+        drop(quotes);
+        let trades: Vec<_> = block_on(trades.by_ref().take(2).collect());
+        assert!(trades.iter().all(|msg| matches!(msg, Ok(Message::Trade(_)))));
+
+        // The error goes to the stream that read it, then every stream ends
+        assert!(matches!(block_on(orders.next()), Some(Err(BitMEXError::Timeout))));
+        assert!(block_on(orders.next()).is_none());
+        let other: Vec<_> = block_on(other.collect());
+        assert!(matches!(other[..], [Ok(Message::Success(_))]));
+        Ok(())
+    }
+}
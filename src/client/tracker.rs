@@ -0,0 +1,357 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use futures::{stream, SinkExt, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::client::table_cache::TableCache;
+use crate::client::websocket::BitMEXWebsocket;
+use crate::error::{BitMEXError, Result};
+use crate::model::funding::GetFundingResponse;
+use crate::model::order::{GetOrderRequest, GetOrderResponse};
+use crate::model::position::{GetPositionRequest, GetPositionResponse};
+use crate::model::websocket::{Action, Command, Message, Topic};
+use crate::transport::TransportTrait;
+use crate::BitMEX;
+
+/// Keeps the account's positions up to date: seeded from `GET /position`, then maintained with the
+/// deltas of the `position` websocket topic. Positions are keyed by symbol.
+pub struct PositionTracker<S = BitMEXWebsocket> {
+    stream: S,
+    table: Table<GetPositionResponse>,
+}
+
+impl PositionTracker {
+    pub async fn connect<T: TransportTrait>(bm: &BitMEX<T>) -> Result<Self> {
+        let rows: Vec<Value> = bm.transport.signed_get("/position", Some(GetPositionRequest::default())).await?;
+        let ws = subscribe(bm, Topic::Position).await?;
+        Self::new(rows, ws)
+    }
+}
+
+impl<S> PositionTracker<S>
+where
+    S: Stream<Item = Result<Message>> + Unpin,
+{
+    fn new(snapshot: Vec<Value>, stream: S) -> Result<Self> {
+        let table = Table::new("position", "symbol", snapshot, |_| true)?;
+        Ok(PositionTracker { stream, table })
+    }
+
+    pub fn get_position(&self, symbol: &str) -> Option<&GetPositionResponse> {
+        self.table.cache.get(symbol)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = &GetPositionResponse> {
+        self.table.cache.rows()
+    }
+
+    /// Applies incoming deltas and yields every position they touched, in its new state.
+    /// A deleted position is yielded one last time with its final state.
+    pub fn changed_positions(&mut self) -> impl Stream<Item = Result<(String, GetPositionResponse)>> + '_ {
+        stream::unfold(self, |tracker| async move {
+            let change = tracker.table.next_change(&mut tracker.stream).await?;
+            Some((change, tracker))
+        })
+    }
+}
+
+/// Keeps the account's open orders up to date: seeded from `GET /order` with `{"open": true}`, then
+/// maintained with the deltas of the `order` websocket topic. Orders are keyed by order id.
+pub struct OrderTracker<S = BitMEXWebsocket> {
+    stream: S,
+    table: Table<GetOrderResponse>,
+}
+
+impl OrderTracker {
+    pub async fn connect<T: TransportTrait>(bm: &BitMEX<T>) -> Result<Self> {
+        let req = GetOrderRequest {
+            filter: Some(json!({ "open": true })),
+            ..Default::default()
+        };
+        let rows: Vec<Value> = bm.paged_orders(req).try_collect().await?;
+        let ws = subscribe(bm, Topic::Order).await?;
+        Self::new(rows, ws)
+    }
+}
+
+impl<S> OrderTracker<S>
+where
+    S: Stream<Item = Result<Message>> + Unpin,
+{
+    fn new(snapshot: Vec<Value>, stream: S) -> Result<Self> {
+        let table = Table::new("order", "orderID", snapshot, is_open)?;
+        Ok(OrderTracker { stream, table })
+    }
+
+    pub fn get_order(&self, order_id: &str) -> Option<&GetOrderResponse> {
+        self.table.cache.get(order_id)
+    }
+
+    pub fn open_orders(&self) -> impl Iterator<Item = &GetOrderResponse> {
+        self.table.cache.rows()
+    }
+
+    /// Applies incoming deltas and yields every order they touched, in its new state.
+    /// Orders that got filled, canceled or deleted are yielded one last time and then forgotten, later
+    /// updates of them are ignored.
+    pub fn order_events(&mut self) -> impl Stream<Item = Result<(String, GetOrderResponse)>> + '_ {
+        stream::unfold(self, |tracker| async move {
+            let change = tracker.table.next_change(&mut tracker.stream).await?;
+            Some((change, tracker))
+        })
+    }
+}
+
+/// Keeps the latest funding of every perpetual up to date from the public `funding` websocket topic.
+/// The partial carries the last funding per symbol, every following funding replaces it.
+pub struct FundingTracker<S = BitMEXWebsocket> {
+    stream: S,
+    table: Table<GetFundingResponse>,
+}
+
+impl FundingTracker {
+    pub async fn connect<T>(bm: &BitMEX<T>) -> Result<Self> {
+        let mut ws = bm.websocket().await?;
+        ws.send(Command::Subscribe(vec![Topic::Funding])).await?;
+        Self::new(ws)
+    }
+}
+
+impl<S> FundingTracker<S>
+where
+    S: Stream<Item = Result<Message>> + Unpin,
+{
+    fn new(stream: S) -> Result<Self> {
+        let table = Table::new("funding", "symbol", vec![], |_| true)?;
+        Ok(FundingTracker { stream, table })
+    }
+
+    pub fn get_funding(&self, symbol: &str) -> Option<&GetFundingResponse> {
+        self.table.cache.get(symbol)
+    }
+
+    /// The rate of the last funding of `symbol`. The rate of the next one is the instrument's `fundingRate`.
+    pub fn current_rate(&self, symbol: &str) -> Option<f64> {
+        self.get_funding(symbol).map(|funding| funding.funding_rate)
+    }
+
+    /// The last funding plus the funding interval
+    pub fn next_funding_time(&self, symbol: &str) -> Option<DateTime<Utc>> {
+        // BitMEX sends the interval as a time after 2000-01-01, e.g. 2000-01-01T08:00:00.000Z
+        let epoch = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).single()?;
+        self.get_funding(symbol).map(|funding| *funding.timestamp + (*funding.funding_interval - epoch))
+    }
+
+    /// Applies incoming fundings and yields each one with its symbol
+    pub fn funding_updates(&mut self) -> impl Stream<Item = Result<(String, GetFundingResponse)>> + '_ {
+        stream::unfold(self, |tracker| async move {
+            let change = tracker.table.next_change(&mut tracker.stream).await?;
+            Some((change, tracker))
+        })
+    }
+}
+
+fn is_open(order: &GetOrderResponse) -> bool {
+    use crate::model::order::OrdStatus::*;
+    matches!(order.ord_status, New | PartiallyFilled | PendingNew | PendingReplace | PendingCancel)
+}
+
+async fn subscribe<T: TransportTrait>(bm: &BitMEX<T>, topic: Topic) -> Result<BitMEXWebsocket> {
+    let mut ws = bm.websocket().await?;
+    let expires = (Utc::now() + Duration::seconds(30)).timestamp();
+    ws.send(Command::authenticate(bm, expires)?).await?;
+    ws.send(Command::Subscribe(vec![topic])).await?;
+    Ok(ws)
+}
+
+// How many keys of removed rows are remembered to ignore their late updates
+const MAX_FORGOTTEN: usize = 1000;
+
+/// A private table as seen by a tracker: the cached rows that are still alive, plus the changes not
+/// yielded yet.
+struct Table<R> {
+    name: &'static str,
+    key: &'static str,
+    alive: fn(&R) -> bool,
+    cache: TableCache<R>,
+    pending: VecDeque<(String, R)>,
+    // Keys of the rows deleted or removed once dead. BitMEX still updates them afterwards, e.g. sets
+    // `workingIndicator` of a canceled order to false, these updates are dropped.
+    forgotten: VecDeque<String>,
+}
+
+impl<R: DeserializeOwned + Clone> Table<R> {
+    fn new(name: &'static str, key: &'static str, snapshot: Vec<Value>, alive: fn(&R) -> bool) -> Result<Self> {
+        let mut table = Table {
+            name,
+            key,
+            alive,
+            cache: TableCache::with_keys(&[key]),
+            pending: VecDeque::new(),
+            forgotten: VecDeque::new(),
+        };
+        for row in snapshot {
+            let (key, item) = table.cache.insert(row)?;
+            if !alive(&item) {
+                table.forget(key);
+            }
+        }
+        Ok(table)
+    }
+
+    fn forget(&mut self, key: String) {
+        self.cache.remove(&key);
+        if self.forgotten.len() == MAX_FORGOTTEN {
+            self.forgotten.pop_front();
+        }
+        self.forgotten.push_back(key);
+    }
+
+    fn is_forgotten(&self, row: &Value) -> bool {
+        match row.get(self.key) {
+            Some(Value::String(key)) => self.forgotten.contains(key),
+            _ => false,
+        }
+    }
+
+    async fn next_change<S>(&mut self, stream: &mut S) -> Option<Result<(String, R)>>
+    where
+        S: Stream<Item = Result<Message>> + Unpin,
+    {
+        loop {
+            if let Some(change) = self.pending.pop_front() {
+                return Some(Ok(change));
+            }
+            let msg = match stream.next().await? {
+                Ok(msg) => msg,
+                Err(e) => return Some(Err(e)),
+            };
+            match msg {
+                Message::Table(mut table) if table.table == self.name => {
+                    if let Action::Update | Action::Delete = table.action {
+                        table.data.retain(|row| !self.is_forgotten(row));
+                    }
+                    match self.cache.apply(&table) {
+                        Ok(changes) => {
+                            for (key, item) in changes {
+                                if matches!(table.action, Action::Delete) || !(self.alive)(&item) {
+                                    self.forget(key.clone());
+                                }
+                                self.pending.push_back((key, item));
+                            }
+                        }
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                Message::SequenceGap(ref table) if table == self.name => {
+                    return Some(Err(BitMEXError::SequenceGap(table.clone())));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use futures::{stream, TryStreamExt};
+    use serde_json::{from_str, json};
+
+    use chrono::{TimeZone, Utc};
+
+    use super::{FundingTracker, OrderTracker, PositionTracker};
+    use crate::error::Result;
+    use crate::model::websocket::Message;
+
+    #[test]
+    fn test_position_tracker() -> Result<()> {
+        let messages = vec![
+            r#"{"table":"position","action":"partial","keys":["account","symbol","currency"],"data":[{"account":2,"symbol":"XBTUSD","currency":"XBt","currentQty":100,"leverage":10}]}"#,
+            r#"{"table":"position","action":"update","data":[{"account":2,"symbol":"XBTUSD","currency":"XBt","currentQty":200}]}"#,
+            r#"{"table":"position","action":"insert","data":[{"account":2,"symbol":"ETHUSD","currency":"XBt","currentQty":-5}]}"#,
+            r#"{"table":"trade","action":"insert","data":[{"timestamp":"2019-01-01T00:00:00.000Z","symbol":"XBTUSD"}]}"#,
+            r#"{"table":"position","action":"delete","data":[{"account":2,"symbol":"ETHUSD","currency":"XBt"}]}"#,
+        ];
+        let messages: Vec<Result<Message>> = messages.into_iter().map(|m| Ok(from_str(m)?)).collect();
+
+        let snapshot = vec![json!({"account": 2, "symbol": "XBTUSD", "currency": "XBt", "currentQty": 0, "leverage": 10})];
+        let mut tracker = PositionTracker::new(snapshot, stream::iter(messages))?;
+        assert_eq!(tracker.get_position("XBTUSD").unwrap().current_qty, 0);
+
+        let changes: Vec<_> = block_on(tracker.changed_positions().try_collect())?;
+        let changes: Vec<_> = changes.iter().map(|(symbol, p)| (&**symbol, p.current_qty)).collect();
+        assert_eq!(changes, vec![("XBTUSD", 100), ("XBTUSD", 200), ("ETHUSD", -5), ("ETHUSD", -5)]);
+
+        let xbt = tracker.get_position("XBTUSD").unwrap();
+        assert_eq!((xbt.current_qty, xbt.leverage), (200, 10.));
+        assert!(tracker.get_position("ETHUSD").is_none());
+        assert_eq!(tracker.positions().count(), 1);
+        Ok(())
+    }
+
+    fn order(id: &str, status: &str, leaves_qty: u64) -> String {
+        format!(
+            r#"{{"orderID":"{}","clOrdID":"","clOrdLinkID":"","account":2,"symbol":"XBTUSD","side":"Buy","orderQty":100,"price":9000,"pegPriceType":"","currency":"USD","settlCurrency":"XBt","ordType":"Limit","timeInForce":"GoodTillCancel","execInst":"","contingencyType":"","exDestination":"XBME","ordStatus":"{}","triggered":"","workingIndicator":true,"ordRejReason":"","leavesQty":{},"cumQty":{},"multiLegReportingType":"SingleSecurity","text":"","transactTime":"2019-01-01T00:00:00.000Z","timestamp":"2019-01-01T00:00:00.000Z"}}"#,
+            id,
+            status,
+            leaves_qty,
+            100 - leaves_qty
+        )
+    }
+
+    #[test]
+    fn test_order_tracker() -> Result<()> {
+        let a = "00000000-0000-0000-0000-00000000000a";
+        let b = "00000000-0000-0000-0000-00000000000b";
+        let c = "00000000-0000-0000-0000-00000000000c";
+        let messages = [
+            format!(r#"{{"table":"order","action":"partial","keys":["orderID"],"data":[{},{}]}}"#, order(a, "New", 100), order(b, "New", 100)),
+            format!(r#"{{"table":"order","action":"insert","data":[{}]}}"#, order(c, "New", 100)),
+            format!(r#"{{"table":"order","action":"update","data":[{{"orderID":"{}","ordStatus":"PartiallyFilled","leavesQty":40,"cumQty":60}}]}}"#, a),
+            format!(r#"{{"table":"order","action":"update","data":[{{"orderID":"{}","ordStatus":"Canceled","leavesQty":0}}]}}"#, b),
+            // Late updates of an order that is already closed are ignored
+            format!(r#"{{"table":"order","action":"update","data":[{{"orderID":"{}","workingIndicator":false}}]}}"#, b),
+            format!(r#"{{"table":"order","action":"delete","data":[{{"orderID":"{}"}}]}}"#, c),
+            format!(r#"{{"table":"order","action":"update","data":[{{"orderID":"{}","workingIndicator":false}}]}}"#, c),
+        ];
+        let messages: Vec<Result<Message>> = messages.iter().map(|m| Ok(from_str(m)?)).collect();
+
+        let mut tracker = OrderTracker::new(vec![from_str(&order(a, "New", 100))?], stream::iter(messages))?;
+        assert_eq!(tracker.open_orders().count(), 1);
+
+        let events: Vec<_> = block_on(tracker.order_events().try_collect())?;
+        assert_eq!(events.len(), 6);
+        assert_eq!(events[3].1.leaves_qty, 40);
+        assert_eq!(events[3].1.price, Some(9000.));
+
+        assert_eq!(tracker.open_orders().count(), 1);
+        let open = tracker.get_order(a).unwrap();
+        assert_eq!((open.leaves_qty, open.cum_qty), (40, 60));
+        assert!(tracker.get_order(b).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_funding_tracker() -> Result<()> {
+        let messages = vec![
+            r#"{"table":"funding","action":"partial","keys":["timestamp","symbol"],"data":[{"timestamp":"2019-01-01T04:00:00.000Z","symbol":"XBTUSD","fundingInterval":"2000-01-01T08:00:00.000Z","fundingRate":0.0001,"fundingRateDaily":0.0003},{"timestamp":"2019-01-01T04:00:00.000Z","symbol":"ETHUSD","fundingInterval":"2000-01-01T08:00:00.000Z","fundingRate":-0.0002,"fundingRateDaily":-0.0006}]}"#,
+            r#"{"table":"funding","action":"insert","data":[{"timestamp":"2019-01-01T12:00:00.000Z","symbol":"XBTUSD","fundingInterval":"2000-01-01T08:00:00.000Z","fundingRate":0.00025,"fundingRateDaily":0.00075}]}"#,
+        ];
+        let messages: Vec<Result<Message>> = messages.into_iter().map(|m| Ok(from_str(m)?)).collect();
+
+        let mut tracker = FundingTracker::new(stream::iter(messages))?;
+        assert_eq!(tracker.current_rate("XBTUSD"), None);
+        let updates: Vec<_> = block_on(tracker.funding_updates().try_collect())?;
+        assert_eq!(updates.len(), 3);
+
+        assert_eq!(tracker.current_rate("XBTUSD"), Some(0.00025));
+        assert_eq!(tracker.next_funding_time("XBTUSD"), Some(Utc.with_ymd_and_hms(2019, 1, 1, 20, 0, 0).unwrap()));
+        assert_eq!(tracker.current_rate("ETHUSD"), Some(-0.0002));
+        assert_eq!(tracker.get_funding("ETHUSD").unwrap().funding_rate_daily, -0.0006);
+        assert_eq!(tracker.next_funding_time("XRPUSD"), None);
+        Ok(())
+    }
+}
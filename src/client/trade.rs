@@ -1,15 +1,54 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::trade::{GetTradeBucketedRequest, GetTradeBucketedResponse, GetTradeRequest, GetTradeResponse};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_trade(&self, req: GetTradeRequest) -> Result<impl Future<Item = GetTradeResponse, Error = Error>> {
-        Ok(self.transport.get("/trade", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_trade(&self, req: GetTradeRequest) -> impl Future<Output = Result<GetTradeResponse>> {
+        self.transport.get("/trade", Some(req))
     }
-    pub fn get_trade_bucketed(&self, req: GetTradeBucketedRequest) -> Result<impl Future<Item = GetTradeBucketedResponse, Error = Error>> {
-        Ok(self.transport.get("/trade/bucketed", Some(req))?)
+    pub fn get_trade_bucketed(&self, req: GetTradeBucketedRequest) -> impl Future<Output = Result<GetTradeBucketedResponse>> {
+        self.transport.get("/trade/bucketed", Some(req))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use futures::executor::block_on;
+
+    use crate::error::Result;
+    use crate::model::trade::GetTradeRequest;
+    use crate::model::{Side, TickDirection};
+    use crate::transport::MockTransport;
+    use crate::BitMEX;
+
+    #[test]
+    fn test_get_trade() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(
+            r#"[{"timestamp":"2019-01-01T00:00:00.123Z","symbol":"XBTUSD","side":"Sell","size":250,"price":3700.5,"tickDirection":"MinusTick","trdMatchID":"9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e","grossValue":6756000,"homeNotional":0.06756,"foreignNotional":250}]"#,
+        );
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let start_time = Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap();
+        let trades = block_on(bm.get_trade(GetTradeRequest {
+            symbol: Some("XBTUSD".to_string()),
+            count: 1,
+            reverse: Some(true),
+            start_time: Some(start_time),
+            ..Default::default()
+        }))?;
+        let trade = &trades[0];
+        assert!(matches!(trade.side, Some(Side::Sell)));
+        assert_eq!((trade.size, trade.price, trade.gross_value), (Some(250), Some(3700.5), Some(6756000)));
+        assert_eq!(trade.tick_direction, Some(TickDirection::MinusTick));
+
+        let req = &tr.requests()[0];
+        assert_eq!(req.endpoint, "/trade");
+        assert!(req.query.contains(&("startTime".to_string(), "2019-01-01T00:00:00Z".to_string())));
+        Ok(())
     }
 }
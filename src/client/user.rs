@@ -1,35 +1,125 @@
-use failure::Error;
-use futures::Future;
+use futures::{Future, TryFutureExt};
 
-use crate::error::Result;
+use crate::error::{BitMEXError, Result};
 use crate::model::user::{
-    GetUserAffiliateStatusResponse, GetUserCommissionResponse, GetUserDepositAddressRequest, GetUserDepositAddressResponse, GetUserResponse, GetUserWalletHistoryRequest,
+    GetUserAffiliateStatusResponse, GetUserCheckReferralCodeRequest, GetUserCheckReferralCodeResponse, GetUserCommissionResponse, GetUserDepositAddressRequest, GetUserDepositAddressResponse, GetUserMinWithdrawalFeeRequest, GetUserMinWithdrawalFeeResponse, GetUserResponse, GetUserWalletHistoryRequest,
     GetUserWalletHistoryResponse, GetUserWalletRequest, GetUserWalletResponse, GetUserWalletSummaryRequest, GetUserWalletSummaryResponse,
 };
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_user(&self) -> Result<impl Future<Item = GetUserResponse, Error = Error>> {
-        Ok(self.transport.signed_get::<_, ()>("/user", None)?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_user(&self) -> impl Future<Output = Result<GetUserResponse>> {
+        self.transport.signed_get::<_, ()>("/user", None)
     }
-    pub fn get_user_affiliate_status(&self) -> Result<impl Future<Item = GetUserAffiliateStatusResponse, Error = Error>> {
-        Ok(self.transport.signed_get::<_, ()>("/user/affiliateStatus", None)?)
+    pub fn get_user_affiliate_status(&self) -> impl Future<Output = Result<GetUserAffiliateStatusResponse>> {
+        self.transport.signed_get::<_, ()>("/user/affiliateStatus", None)
     }
 
-    pub fn get_user_commission(&self) -> Result<impl Future<Item = GetUserCommissionResponse, Error = Error>> {
-        Ok(self.transport.signed_get::<_, ()>("/user/commission", None)?)
+    /// Resolves to `BitMEXError::InvalidReferralCode` if BitMEX doesn't know the code
+    pub fn check_referral_code(&self, referral_code: &str) -> impl Future<Output = Result<GetUserCheckReferralCodeResponse>> {
+        let req = GetUserCheckReferralCodeRequest {
+            referral_code: Some(referral_code.to_string()),
+        };
+        self.transport.get("/user/checkReferralCode", Some(req)).map_err(|e| match e {
+            // Unknown codes are answered with a plain 404, other errors (e.g. rate limits) are passed through
+            BitMEXError::Api { status: 404, .. } => BitMEXError::InvalidReferralCode,
+            e => e,
+        })
     }
-    pub fn get_user_deposit_address(&self, req: GetUserDepositAddressRequest) -> Result<impl Future<Item = GetUserDepositAddressResponse, Error = Error>> {
-        Ok(self.transport.signed_get("/user/depositAddress", Some(req))?)
+
+    pub fn get_user_commission(&self) -> impl Future<Output = Result<GetUserCommissionResponse>> {
+        self.transport.signed_get::<_, ()>("/user/commission", None)
+    }
+    pub fn get_user_deposit_address(&self, req: GetUserDepositAddressRequest) -> impl Future<Output = Result<GetUserDepositAddressResponse>> {
+        self.transport.signed_get("/user/depositAddress", Some(req))
     }
 
-    pub fn get_user_wallet(&self, req: GetUserWalletRequest) -> Result<impl Future<Item = GetUserWalletResponse, Error = Error>> {
-        Ok(self.transport.signed_get("/user/wallet", Some(req))?)
+    pub fn get_user_wallet(&self, req: GetUserWalletRequest) -> impl Future<Output = Result<GetUserWalletResponse>> {
+        self.transport.signed_get("/user/wallet", Some(req))
+    }
+    pub fn get_user_wallet_history(&self, req: GetUserWalletHistoryRequest) -> impl Future<Output = Result<GetUserWalletHistoryResponse>> {
+        self.transport.signed_get("/user/walletHistory", Some(req))
+    }
+    pub fn get_user_wallet_summary(&self, req: GetUserWalletSummaryRequest) -> impl Future<Output = Result<GetUserWalletSummaryResponse>> {
+        self.transport.signed_get("/user/walletSummary", Some(req))
+    }
+    /// The fee to pass to a withdrawal, it follows the network fee
+    pub fn get_min_withdrawal_fee(&self, currency: &str) -> impl Future<Output = Result<GetUserMinWithdrawalFeeResponse>> {
+        let req = GetUserMinWithdrawalFeeRequest {
+            currency: Some(currency.to_string()),
+        };
+        self.transport.get("/user/minWithdrawalFee", Some(req))
     }
-    pub fn get_user_wallet_history(&self, req: GetUserWalletHistoryRequest) -> Result<impl Future<Item = GetUserWalletHistoryResponse, Error = Error>> {
-        Ok(self.transport.signed_get("/user/walletHistory", Some(req))?)
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use hyper::StatusCode;
+
+    use crate::error::{BitMEXError, Result};
+    use crate::model::user::GetUserWalletSummaryRequest;
+    use crate::model::Satoshi;
+    use crate::transport::MockTransport;
+    use crate::BitMEX;
+
+    #[test]
+    fn test_get_user_affiliate_status() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(r#"{"account":2,"currency":"XBt","prevPayout":0,"prevTurnover":0,"prevComm":0,"prevTimestamp":null,"execTurnover":0,"execComm":0,"totalReferrals":3,"totalTurnover":0,"totalComm":0,"payoutPcnt":0.2,"pendingPayout":0,"timestamp":"2019-01-01T00:00:00.000Z","referrerAccount":null,"referralDiscount":0.1,"affiliatePayout":15000,"usdValue":0.55}"#);
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let status = block_on(bm.get_user_affiliate_status())?;
+        assert_eq!((status.affiliate_payout, status.usd_value, status.referrer_account), (Some(15000.), Some(0.55), None));
+        assert!(tr.requests()[0].signed);
+        Ok(())
     }
-    pub fn get_user_wallet_summary(&self, req: GetUserWalletSummaryRequest) -> Result<impl Future<Item = GetUserWalletSummaryResponse, Error = Error>> {
-        Ok(self.transport.signed_get("/user/walletSummary", Some(req))?)
+
+    #[test]
+    fn test_check_referral_code() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response("0.1");
+        tr.push_status(StatusCode::NOT_FOUND, r#"{"error":{"message":"Not Found","name":"HTTPError"}}"#);
+
+        let bm = BitMEX::with_transport(tr.clone());
+        assert_eq!(block_on(bm.check_referral_code("ABCDEF"))?, 0.1);
+        assert!(!tr.requests()[0].signed);
+        assert_eq!(tr.requests()[0].query, vec![("referralCode".to_string(), "ABCDEF".to_string())]);
+
+        let err = block_on(bm.check_referral_code("NOPE")).unwrap_err();
+        assert!(matches!(err, BitMEXError::InvalidReferralCode));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_user_wallet_summary() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(
+            r#"[{"account":2,"currency":"XBt","transactType":"Deposit","symbol":null,"amount":1000000,"pendingDebit":0,"realisedPnl":0,"walletBalance":1000000,"unrealisedPnl":0,"marginBalance":1000000},
+                {"account":2,"currency":"XBt","transactType":"RealisedPNL","symbol":"XBTUSD","amount":-1500,"pendingDebit":0,"realisedPnl":-1500,"walletBalance":-1500,"unrealisedPnl":0,"marginBalance":-1500},
+                {"account":2,"currency":"XBt","transactType":"Total","symbol":null,"amount":998500,"pendingDebit":0,"realisedPnl":-1500,"walletBalance":998500,"unrealisedPnl":250,"marginBalance":998750}]"#,
+        );
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let summary = block_on(bm.get_user_wallet_summary(GetUserWalletSummaryRequest { currency: Some("XBt".to_string()) }))?;
+        let types: Vec<_> = summary.iter().map(|row| row.transact_type.as_deref().unwrap()).collect();
+        assert_eq!(types, vec!["Deposit", "RealisedPNL", "Total"]);
+        assert_eq!((summary[2].wallet_balance, summary[2].margin_balance), (Some(Satoshi(998500)), Some(Satoshi(998750))));
+        assert!(tr.requests()[0].signed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_min_withdrawal_fee() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(r#"{"currency":"XBt","fee":20000,"minFee":10000}"#);
+
+        let bm = BitMEX::with_transport(tr.clone());
+        let fee = block_on(bm.get_min_withdrawal_fee("XBt"))?;
+        assert_eq!((&*fee.currency, fee.fee, fee.min_fee), ("XBt", Satoshi(20000), Satoshi(10000)));
+        assert_eq!(tr.requests()[0].endpoint, "/user/minWithdrawalFee");
+        assert_eq!(tr.requests()[0].query, vec![("currency".to_string(), "XBt".to_string())]);
+        Ok(())
     }
 }
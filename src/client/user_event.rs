@@ -1,12 +1,16 @@
-use failure::Error;
 use futures::Future;
 
 use crate::error::Result;
 use crate::model::user_event::{GetUserEventRequest, GetUserEventResponse};
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
-impl BitMEX {
-    pub fn get_user_event(&self, req: GetUserEventRequest) -> Result<impl Future<Item = GetUserEventResponse, Error = Error>> {
-        Ok(self.transport.signed_get("/userEvent", Some(req))?)
+impl<T: TransportTrait> BitMEX<T> {
+    pub fn get_user_event(&self, req: GetUserEventRequest) -> impl Future<Output = Result<GetUserEventResponse>> {
+        self.transport.signed_get("/userEvent", Some(req))
+    }
+
+    pub fn get_user_events(&self, count: Option<i64>, start_id: Option<i64>) -> impl Future<Output = Result<GetUserEventResponse>> {
+        self.get_user_event(GetUserEventRequest { count, start_id })
     }
 }
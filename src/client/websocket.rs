@@ -1,73 +1,573 @@
-use failure::Error;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
 use futures::sink::Sink;
 use futures::stream::Stream;
-use futures::{Future, Poll, StartSend};
+use futures::{Future, SinkExt, StreamExt, TryFutureExt};
 use log::trace;
-use serde_json::{from_str, to_string};
+use serde_json::{from_str, from_value, json, to_string, Value};
 use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Message};
+use tokio_tungstenite::tungstenite::Error as WsError;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
-use tungstenite::protocol::Message;
-use url::Url;
 
-use crate::consts::WS_URL;
-use crate::model::websocket::{Command, Message as BitMEXWsMessage};
+use crate::error::{BitMEXError, Result};
+use crate::model::websocket::{Command, Message as BitMEXWsMessage, Topic};
 use crate::BitMEX;
 
-#[allow(dead_code)]
 type WSStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
-impl BitMEX {
-    pub fn websocket(&self) -> impl Future<Item = BitMEXWebsocket, Error = Error> {
-        connect_async(Url::parse(&WS_URL).unwrap()).map(|(stream, _)| stream).from_err().map(BitMEXWebsocket::new)
+impl<T> BitMEX<T> {
+    /// The websocket url of the network the client talks to, e.g. `wss://testnet.bitmex.com/realtime`
+    pub fn ws_url(&self) -> &str {
+        &self.ws_url
+    }
+
+    pub fn websocket(&self) -> impl Future<Output = Result<BitMEXWebsocket>> + Send + 'static {
+        connect_async(self.ws_url.to_string()).map_ok(|(stream, _)| BitMEXWebsocket::new(stream)).err_into()
+    }
+
+    /// Connect to the websocket, subscribe to `topics` like `subscribe_confirmed` and return the
+    /// incoming messages only. Useful when nothing else needs to be sent, e.g. for public topics.
+    pub fn ws_stream(&self, topics: &[(Topic, Option<&str>)]) -> impl Stream<Item = Result<BitMEXWsMessage>> + Send + 'static {
+        let topics: Vec<_> = topics.iter().map(|(topic, symbol)| (topic.clone(), symbol.map(ToString::to_string))).collect();
+        let connecting = self.websocket();
+        async move {
+            let mut ws = connecting.await?;
+            let topics: Vec<_> = topics.iter().map(|(topic, symbol)| (topic.clone(), symbol.as_deref())).collect();
+            ws.subscribe_confirmed(&topics).await?;
+            Ok(ws)
+        }
+        .try_flatten_stream()
     }
 }
 
 pub struct BitMEXWebsocket {
     inner: WSStream,
+    partials: PartialTracker,
+    subscriptions: HashSet<String>,
+    // Messages read while waiting for subscription acks, returned by the stream first
+    buffered: VecDeque<BitMEXWsMessage>,
 }
 
 impl BitMEXWebsocket {
     fn new(ws: WSStream) -> Self {
-        Self { inner: ws }
+        Self {
+            inner: ws,
+            partials: PartialTracker::default(),
+            subscriptions: HashSet::new(),
+            buffered: VecDeque::new(),
+        }
+    }
+
+    /// Subscribe to all `topics` in a single `subscribe` op, each qualified with its symbol if given.
+    /// Topics this socket already subscribed to are skipped, nothing is sent if none is left.
+    pub async fn subscribe(&mut self, topics: &[(Topic, Option<&str>)]) -> Result<()> {
+        let args = self.new_subscriptions(topics);
+        self.send_op("subscribe", args).await
+    }
+
+    /// Like `subscribe`, but resolves only once BitMEX acknowledged every topic. Fails with
+    /// `BitMEXError::SubscriptionFailed` if one was rejected, those topics count as unsubscribed.
+    /// Other messages arriving meanwhile are kept for the stream, the acks are consumed.
+    pub async fn subscribe_confirmed(&mut self, topics: &[(Topic, Option<&str>)]) -> Result<()> {
+        let args = self.new_subscriptions(topics);
+        self.send_op("subscribe", args.clone()).await?;
+
+        let mut pending: HashSet<String> = args.into_iter().collect();
+        while !pending.is_empty() {
+            let text = match self.inner.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(WsError::ConnectionClosed.into()),
+            };
+            let value: Value = if text.as_str() == "pong" { Value::Null } else { from_str(&text)? };
+            let ack = value.get("subscribe").and_then(Value::as_str).filter(|arg| pending.contains(*arg));
+            if let Some(arg) = ack {
+                let arg = arg.to_string();
+                pending.remove(&arg);
+                if value.get("success").and_then(Value::as_bool) != Some(true) {
+                    return Err(self.subscription_failed(vec![arg], "Not acknowledged".into()));
+                }
+                continue;
+            }
+
+            // A rejection names the whole op, e.g. {"status":400,"error":"Unknown symbol","request":{"op":"subscribe","args":[..]}}
+            if let (Some(error), Some(request)) = (value.get("error").and_then(Value::as_str), value.get("request")) {
+                let rejected: Vec<String> = request["args"].as_array().map(Vec::as_slice).unwrap_or(&[]).iter().filter_map(Value::as_str).map(ToString::to_string).collect();
+                if request["op"] == "subscribe" && rejected.iter().any(|arg| pending.contains(arg)) {
+                    return Err(self.subscription_failed(rejected, error.to_string()));
+                }
+            }
+
+            let message = self.parse_text(text.as_str())?;
+            self.buffered.push_back(message);
+        }
+        Ok(())
+    }
+
+    fn new_subscriptions(&mut self, topics: &[(Topic, Option<&str>)]) -> Vec<String> {
+        topics
+            .iter()
+            .map(|(topic, symbol)| topic.with_symbol(*symbol))
+            .filter(|arg| self.subscriptions.insert(arg.clone()))
+            .collect()
+    }
+
+    fn subscription_failed(&mut self, args: Vec<String>, message: String) -> BitMEXError {
+        args.iter().for_each(|arg| {
+            self.subscriptions.remove(arg);
+        });
+        BitMEXError::SubscriptionFailed { args, message }
+    }
+
+    /// Unsubscribe from all `topics` in a single `unsubscribe` op, skipping those not subscribed to
+    pub async fn unsubscribe(&mut self, topics: &[(Topic, Option<&str>)]) -> Result<()> {
+        let args: Vec<_> = topics
+            .iter()
+            .map(|(topic, symbol)| topic.with_symbol(*symbol))
+            .filter(|arg| self.subscriptions.remove(arg))
+            .collect();
+        args.iter().for_each(|arg| self.partials.forget(arg));
+        self.send_op("unsubscribe", args).await
+    }
+
+    /// The subscription arguments sent so far, e.g. `trade:XBTUSD`
+    pub fn subscriptions(&self) -> impl Iterator<Item = &str> {
+        self.subscriptions.iter().map(String::as_str)
+    }
+
+    /// Unsubscribes from everything and closes the socket with a normal close frame, so BitMEX
+    /// doesn't log an abnormal closure against the api key.
+    pub async fn close(mut self) -> Result<()> {
+        let args = self.subscriptions.drain().collect();
+        self.send_op("unsubscribe", args).await?;
+        let frame = CloseFrame {
+            code: CloseCode::Normal,
+            reason: "".into(),
+        };
+        self.inner.close(Some(frame)).await.map_err(Into::into)
+    }
+
+    async fn send_op(&mut self, op: &str, args: Vec<String>) -> Result<()> {
+        if args.is_empty() {
+            return Ok(());
+        }
+        let command = to_string(&json!({ "op": op, "args": args }))?;
+        trace!("Sending '{}' through websocket", command);
+        self.inner.send(Message::Text(command.into())).await.map_err(Into::into)
+    }
+
+    // Control frames are handled by tungstenite, so only text frames carry BitMEX messages
+    fn parse_message(&mut self, msg: Message) -> Result<Option<BitMEXWsMessage>> {
+        match msg {
+            Message::Text(message) => self.parse_text(message.as_str()).map(Some),
+            others => {
+                trace!("Skipping non-text websocket frame: {:?}", others);
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse_text(&mut self, text: &str) -> Result<BitMEXWsMessage> {
+        if text == "pong" {
+            return Ok(BitMEXWsMessage::Pong);
+        }
+        trace!("Received message from BitMEX: '{}'", text);
+        let value: Value = from_str(text)?;
+        match self.partials.check(&value) {
+            Some(table) => Ok(BitMEXWsMessage::SequenceGap(table)),
+            None => Ok(from_value(value)?),
+        }
+    }
+}
+
+/// BitMEX has no sequence numbers, but every subscription starts with a `partial` image of the table
+/// (per symbol when filtered). An insert/update/delete for a table or symbol without one means
+/// that we missed data and anything built from the deltas is stale.
+#[derive(Debug, Default)]
+struct PartialTracker {
+    received: HashMap<String, HashSet<Option<String>>>, // table -> filtered symbols, None for unfiltered
+}
+
+impl PartialTracker {
+    // Drops the partial of a subscription argument on unsubscribe, so that a resubscription waits
+    // for a new one. `trade` drops the whole table, `trade:XBTUSD` only XBTUSD.
+    fn forget(&mut self, arg: &str) {
+        match arg.split_once(':') {
+            Some((table, symbol)) => {
+                if let Some(symbols) = self.received.get_mut(table) {
+                    symbols.remove(&Some(symbol.to_string()));
+                    if symbols.is_empty() {
+                        self.received.remove(table);
+                    }
+                }
+            }
+            None => {
+                self.received.remove(arg);
+            }
+        }
+    }
+
+    // Returns the table name if the message is a delta arriving without its partial
+    fn check(&mut self, value: &Value) -> Option<String> {
+        let table = value.get("table")?.as_str()?;
+        let action = value.get("action")?.as_str()?;
+
+        if action == "partial" {
+            let symbol = value.pointer("/filter/symbol").and_then(Value::as_str).map(ToString::to_string);
+            self.received.entry(table.to_string()).or_default().insert(symbol);
+            return None;
+        }
+
+        let symbols = match self.received.get(table) {
+            Some(symbols) => symbols,
+            None => return Some(table.to_string()),
+        };
+        if symbols.contains(&None) {
+            return None;
+        }
+        let rows = value.get("data").and_then(Value::as_array).map(Vec::as_slice).unwrap_or(&[]);
+        let covered = rows.iter().all(|row| match row.get("symbol").and_then(Value::as_str) {
+            Some(symbol) => symbols.contains(&Some(symbol.to_string())),
+            None => true,
+        });
+        if covered {
+            None
+        } else {
+            Some(table.to_string())
+        }
     }
 }
 
-impl Sink for BitMEXWebsocket {
-    type SinkItem = Command;
-    type SinkError = Error;
+impl Sink<Command> for BitMEXWebsocket {
+    type Error = BitMEXError;
 
-    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.inner).poll_ready(cx).map_err(Into::into)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Command) -> Result<()> {
+        match &item {
+            Command::Subscribe(topics) => self.subscriptions.extend(topics.iter().map(|t| t.with_symbol(None))),
+            Command::Unsubscribe(topics) => topics.iter().for_each(|t| {
+                let arg = t.with_symbol(None);
+                self.subscriptions.remove(&arg);
+                self.partials.forget(&arg);
+            }),
+            _ => {}
+        }
         let command = match &item {
             &Command::Ping => "ping".to_string(),
             command => to_string(command)?,
         };
         trace!("Sending '{}' through websocket", command);
-        Ok(self.inner.start_send(Message::Text(command))?.map(|_| item))
+        Pin::new(&mut self.inner).start_send(Message::Text(command.into())).map_err(Into::into)
     }
 
-    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
-        Ok(self.inner.poll_complete()?)
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx).map_err(Into::into)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx).map_err(Into::into)
     }
 }
 
 impl Stream for BitMEXWebsocket {
-    type Item = BitMEXWsMessage;
-    type Error = Error;
+    type Item = Result<BitMEXWsMessage>;
 
-    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        Ok(self.inner.poll().map(|a| a.map(|i| i.map(parse_message)))?)
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(msg) = self.buffered.pop_front() {
+            return Poll::Ready(Some(Ok(msg)));
+        }
+        loop {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(msg))) => match self.parse_message(msg) {
+                    Ok(Some(msg)) => return Poll::Ready(Some(Ok(msg))),
+                    Ok(None) => continue,
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                },
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
     }
 }
 
-fn parse_message(msg: Message) -> BitMEXWsMessage {
-    match msg {
-        Message::Text(message) => match message.as_str() {
-            "pong" => BitMEXWsMessage::Pong,
-            others => match from_str(others) {
-                Ok(r) => r,
-                Err(_) => unreachable!("Received message from BitMEX: '{}'", others),
-            },
-        },
-        _ => unreachable!(),
+#[cfg(test)]
+mod test {
+    use futures::{SinkExt, StreamExt};
+    use serde_json::json;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use tokio_tungstenite::tungstenite::protocol::Message;
+    use tokio_tungstenite::{accept_async, connect_async};
+
+    use super::{BitMEXWebsocket, PartialTracker};
+    use crate::error::{BitMEXError, Result};
+    use crate::model::websocket::{Message as BitMEXWsMessage, Topic};
+    use crate::BitMEX;
+
+    fn assert_send_static<T: Send + 'static>() {}
+
+    #[test]
+    fn test_websocket_is_send() {
+        assert_send_static::<BitMEXWebsocket>();
+    }
+
+    #[test]
+    fn test_parse_message() -> Result<()> {
+        let rt = Runtime::new()?;
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let url = format!("ws://{}", listener.local_addr()?);
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                accept_async(stream).await.unwrap()
+            });
+
+            let (stream, _) = connect_async(url).await?;
+            let mut ws = BitMEXWebsocket::new(stream);
+            match ws.parse_text("pong")? {
+                BitMEXWsMessage::Pong => {}
+                other => panic!("Unexpected message {:?}", other),
+            }
+            assert!(ws.parse_message(Message::Ping(Vec::new().into()))?.is_none());
+            assert!(ws.parse_text("not json").is_err());
+            drop(server.await.unwrap());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_delta_before_partial() {
+        let mut tracker = PartialTracker::default();
+        let update = json!({"table": "orderBookL2", "action": "update", "data": [{"symbol": "XBTUSD", "id": 8799386750u64, "side": "Sell", "size": 10}]});
+        assert_eq!(tracker.check(&update), Some("orderBookL2".to_string()));
+
+        let partial = json!({"table": "orderBookL2", "action": "partial", "filter": {"symbol": "XBTUSD"}, "data": []});
+        assert_eq!(tracker.check(&partial), None);
+        assert_eq!(tracker.check(&update), None);
+
+        let other_symbol = json!({"table": "orderBookL2", "action": "update", "data": [{"symbol": "ETHUSD", "id": 29699996001u64, "side": "Buy", "size": 10}]});
+        assert_eq!(tracker.check(&other_symbol), Some("orderBookL2".to_string()));
+    }
+
+    #[test]
+    fn test_unfiltered_partial() {
+        let mut tracker = PartialTracker::default();
+        let partial = json!({"table": "trade", "action": "partial", "filter": {}, "data": []});
+        let insert = json!({"table": "trade", "action": "insert", "data": [{"symbol": "ETHUSD"}]});
+        assert_eq!(tracker.check(&partial), None);
+        assert_eq!(tracker.check(&insert), None);
+        assert_eq!(tracker.check(&json!({"success": true, "subscribe": "trade"})), None);
+    }
+
+    #[test]
+    fn test_resubscribe() -> Result<()> {
+        let rt = Runtime::new()?;
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let url = format!("ws://{}", listener.local_addr()?);
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                let partial = r#"{"table":"trade","action":"partial","filter":{"symbol":"XBTUSD"},"data":[]}"#;
+                let insert = r#"{"table":"trade","action":"insert","data":[{"timestamp":"2019-01-01T00:00:00.000Z","symbol":"XBTUSD"}]}"#;
+                // subscribe, unsubscribe, subscribe
+                let replies = [vec![partial, insert], vec![], vec![insert, partial, insert]];
+                for replies in replies.iter() {
+                    ws.next().await.unwrap().unwrap();
+                    for reply in replies {
+                        ws.send(Message::Text((*reply).into())).await.unwrap();
+                    }
+                }
+                ws
+            });
+
+            let (stream, _) = connect_async(url).await?;
+            let mut ws = BitMEXWebsocket::new(stream);
+            let topics = [(Topic::Trade(None), Some("XBTUSD"))];
+            ws.subscribe(&topics).await?;
+            for _ in 0..2 {
+                assert!(matches!(ws.next().await, Some(Ok(BitMEXWsMessage::Trade(_)))));
+            }
+            ws.unsubscribe(&topics).await?;
+            ws.subscribe(&topics).await?;
+
+            // The partial of the first subscription doesn't cover the deltas of the second
+            match ws.next().await {
+                Some(Ok(BitMEXWsMessage::SequenceGap(table))) => assert_eq!(table, "trade"),
+                other => panic!("Unexpected message {:?}", other),
+            }
+            for _ in 0..2 {
+                assert!(matches!(ws.next().await, Some(Ok(BitMEXWsMessage::Trade(_)))));
+            }
+            drop(server.await.unwrap());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_forget_partial() {
+        let mut tracker = PartialTracker::default();
+        let insert = |symbol| json!({"table": "trade", "action": "insert", "data": [{"symbol": symbol}]});
+        for symbol in ["XBTUSD", "ETHUSD"].iter() {
+            tracker.check(&json!({"table": "trade", "action": "partial", "filter": {"symbol": symbol}, "data": []}));
+        }
+        tracker.forget("trade:XBTUSD");
+        assert_eq!(tracker.check(&insert("XBTUSD")), Some("trade".to_string()));
+        assert_eq!(tracker.check(&insert("ETHUSD")), None);
+
+        tracker.check(&json!({"table": "trade", "action": "partial", "filter": {}, "data": []}));
+        tracker.forget("trade");
+        assert_eq!(tracker.check(&insert("ETHUSD")), Some("trade".to_string()));
+    }
+
+    #[test]
+    fn test_subscribe_symbols() -> Result<()> {
+        let rt = Runtime::new()?;
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let url = format!("ws://{}", listener.local_addr()?);
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let ws = accept_async(stream).await.unwrap();
+                ws.take(3).map(|msg| msg.unwrap().into_text().unwrap().to_string()).collect::<Vec<_>>().await
+            });
+
+            let (stream, _) = connect_async(url).await?;
+            let mut ws = BitMEXWebsocket::new(stream);
+            ws.subscribe(&[(Topic::Trade(None), Some("XBTUSD")), (Topic::Trade(None), Some("ETHUSD")), (Topic::Trade(None), Some("XBTUSD"))]).await?;
+            ws.subscribe(&[(Topic::Trade(None), Some("ETHUSD"))]).await?;
+            ws.subscribe(&[(Topic::Trade(None), Some("XBTUSD")), (Topic::Instrument, Some("XBTUSD"))]).await?;
+            ws.unsubscribe(&[(Topic::Trade(None), Some("ETHUSD")), (Topic::Quote, None)]).await?;
+            assert_eq!(ws.subscriptions().count(), 2);
+
+            let sent = server.await.unwrap();
+            assert_eq!(
+                sent,
+                vec![
+                    r#"{"args":["trade:XBTUSD","trade:ETHUSD"],"op":"subscribe"}"#,
+                    r#"{"args":["instrument:XBTUSD"],"op":"subscribe"}"#,
+                    r#"{"args":["trade:ETHUSD"],"op":"unsubscribe"}"#,
+                ]
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_close() -> Result<()> {
+        let rt = Runtime::new()?;
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let url = format!("ws://{}", listener.local_addr()?);
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let ws = accept_async(stream).await.unwrap();
+                ws.take(3).map(|msg| msg.unwrap()).collect::<Vec<_>>().await
+            });
+
+            let (stream, _) = connect_async(url).await?;
+            let mut ws = BitMEXWebsocket::new(stream);
+            ws.subscribe(&[(Topic::Trade(None), Some("XBTUSD"))]).await?;
+            ws.close().await?;
+
+            let received = server.await.unwrap();
+            assert_eq!(received[1].clone().into_text().unwrap().as_str(), r#"{"args":["trade:XBTUSD"],"op":"unsubscribe"}"#);
+            match &received[2] {
+                Message::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Normal),
+                other => panic!("Unexpected frame {:?}", other),
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_subscribe_confirmed() -> Result<()> {
+        let rt = Runtime::new()?;
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let url = format!("ws://{}", listener.local_addr()?);
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                let replies = [
+                    vec![
+                        r#"{"success":true,"subscribe":"trade:XBTUSD","request":{"op":"subscribe","args":["trade:XBTUSD","instrument:XBTUSD"]}}"#,
+                        r#"{"table":"trade","action":"partial","filter":{"symbol":"XBTUSD"},"data":[]}"#,
+                        r#"{"success":true,"subscribe":"instrument:XBTUSD","request":{"op":"subscribe","args":["trade:XBTUSD","instrument:XBTUSD"]}}"#,
+                    ],
+                    vec![r#"{"status":400,"error":"Unknown symbol: XBTUSDX","meta":{},"request":{"op":"subscribe","args":["trade:XBTUSDX"]}}"#],
+                ];
+                for replies in replies.iter() {
+                    ws.next().await.unwrap().unwrap();
+                    for reply in replies {
+                        ws.send(Message::Text((*reply).into())).await.unwrap();
+                    }
+                }
+                ws
+            });
+
+            let (stream, _) = connect_async(url).await?;
+            let mut ws = BitMEXWebsocket::new(stream);
+            ws.subscribe_confirmed(&[(Topic::Trade(None), Some("XBTUSD")), (Topic::Instrument, Some("XBTUSD"))]).await?;
+            // Nothing left to confirm
+            ws.subscribe_confirmed(&[(Topic::Trade(None), Some("XBTUSD"))]).await?;
+
+            match ws.subscribe_confirmed(&[(Topic::Trade(None), Some("XBTUSDX"))]).await.unwrap_err() {
+                BitMEXError::SubscriptionFailed { args, message } => assert_eq!((args, message.as_str()), (vec!["trade:XBTUSDX".to_string()], "Unknown symbol: XBTUSDX")),
+                e => panic!("Unexpected error {}", e),
+            }
+            assert_eq!(ws.subscriptions().count(), 2);
+
+            // The partial read while waiting for the acks
+            match ws.next().await {
+                Some(Ok(BitMEXWsMessage::Trade(msg))) => assert_eq!(msg.table, "trade"),
+                other => panic!("Unexpected message {:?}", other),
+            }
+            drop(server.await.unwrap());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_ws_stream() -> Result<()> {
+        let rt = Runtime::new()?;
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let mut bm = BitMEX::new();
+            bm.ws_url = format!("ws://{}", listener.local_addr()?).into();
+            let server = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                let subscribe = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                for reply in [
+                    r#"{"success":true,"subscribe":"trade:XBTUSD","request":{"op":"subscribe","args":["trade:XBTUSD"]}}"#,
+                    r#"{"table":"trade","action":"partial","filter":{"symbol":"XBTUSD"},"data":[]}"#,
+                ]
+                .iter()
+                {
+                    ws.send(Message::Text((*reply).into())).await.unwrap();
+                }
+                (ws, subscribe)
+            });
+
+            let mut stream = Box::pin(bm.ws_stream(&[(Topic::Trade(None), Some("XBTUSD"))]));
+            match stream.next().await {
+                Some(Ok(BitMEXWsMessage::Trade(msg))) => assert_eq!(msg.table, "trade"),
+                other => panic!("Unexpected message {:?}", other),
+            }
+            let (_ws, subscribe) = server.await.unwrap();
+            assert_eq!(subscribe.as_str(), r#"{"args":["trade:XBTUSD"],"op":"subscribe"}"#);
+            Ok(())
+        })
     }
 }
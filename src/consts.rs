@@ -1,24 +1,84 @@
 use std::env::var;
+use std::str::FromStr;
 
 use lazy_static::lazy_static;
 use log::warn;
+use url::Url;
+
+use crate::error::BitMEXError;
+
+/// The BitMEX deployment to talk to, chosen at runtime. Without one, `BITMEX_TESTNET` decides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    pub fn rest_url(self) -> &'static str {
+        match self {
+            Network::Mainnet => "https://www.bitmex.com/api/v1",
+            Network::Testnet => "https://testnet.bitmex.com/api/v1",
+        }
+    }
+
+    pub fn ws_url(self) -> &'static str {
+        match self {
+            Network::Mainnet => "wss://www.bitmex.com/realtime",
+            Network::Testnet => "wss://testnet.bitmex.com/realtime",
+        }
+    }
+}
+
+/// Parses `mainnet` or `testnet`, ignoring case
+impl FromStr for Network {
+    type Err = BitMEXError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            _ => Err(BitMEXError::InvalidConfig(format!("Unknown network {}, expected mainnet or testnet", s))),
+        }
+    }
+}
+
+/// The websocket url of the deployment `rest_url` points to: the `Network`'s if it is a network's
+/// REST url, otherwise `/realtime` on the same host, e.g. for a gateway. `None` for an invalid url.
+pub fn ws_url_for(rest_url: &str) -> Option<String> {
+    if let Some(network) = [Network::Mainnet, Network::Testnet].iter().find(|network| network.rest_url() == rest_url) {
+        return Some(network.ws_url().to_string());
+    }
+    let mut url = Url::parse(rest_url).ok()?;
+    let scheme = if url.scheme() == "http" { "ws" } else { "wss" };
+    url.set_scheme(scheme).ok()?;
+    url.set_path("/realtime");
+    url.set_query(None);
+    Some(url.to_string())
+}
 
 // dotenv is a must run in every test otherwise the url will be mis-loaded
 lazy_static! {
-    pub static ref WS_URL: &'static str = {
-        if var("BITMEX_TESTNET").unwrap_or_else(|_| "0".to_string()) == "0" {
-            "wss://www.bitmex.com/realtime"
-        } else {
-            warn!("Your are using BitMEX testnet Restful API");
-            "wss://testnet.bitmex.com/realtime"
-        }
-    };
     pub static ref REST_URL: &'static str = {
         if var("BITMEX_TESTNET").unwrap_or_else(|_| "0".to_string()) == "0" {
-            "https://www.bitmex.com/api/v1"
+            Network::Mainnet.rest_url()
         } else {
             warn!("Your are using BitMEX testnet Websocket");
-            "https://testnet.bitmex.com/api/v1"
+            Network::Testnet.rest_url()
         }
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ws_url_for, Network};
+
+    #[test]
+    fn test_ws_url_for() {
+        assert_eq!(ws_url_for(Network::Testnet.rest_url()).as_deref(), Some(Network::Testnet.ws_url()));
+        assert_eq!(ws_url_for(Network::Mainnet.rest_url()).as_deref(), Some(Network::Mainnet.ws_url()));
+        assert_eq!(ws_url_for("https://gateway.example.com/api/v1").as_deref(), Some("wss://gateway.example.com/realtime"));
+        assert_eq!(ws_url_for("http://127.0.0.1:8080/api/v1?x=1").as_deref(), Some("ws://127.0.0.1:8080/realtime"));
+        assert_eq!(ws_url_for("api/v1"), None);
+    }
+}
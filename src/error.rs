@@ -1,10 +1,15 @@
 use std::result::Result as StdResult;
+use std::time::Duration;
 
-use failure::{Error, Fail};
+use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde_derive::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio_tungstenite::tungstenite;
 
-pub type Result<T> = ::std::result::Result<T, Error>;
+use crate::client::book::BookViolation;
+
+pub type Result<T> = ::std::result::Result<T, BitMEXError>;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(untagged)]
@@ -22,15 +27,183 @@ impl<T: DeserializeOwned> BitMEXResponse<T> {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Fail)]
-#[fail(display = "BitMEX error: {}", message)]
+/// The `error` object of a failed request, as sent by BitMEX
+#[derive(Deserialize, Serialize, Debug, Clone, Error)]
+#[error("BitMEX error: {message}")]
 pub struct BitMEXResponseError {
     pub message: String,
     pub name: String,
 }
 
-#[derive(Debug, Fail, Serialize, Deserialize, Clone)]
+impl BitMEXResponseError {
+    pub fn kind(&self) -> ApiErrorKind {
+        ApiErrorKind::from_name(&self.name)
+    }
+}
+
+/// The error names of BitMEX worth telling apart, see `BitMEXError::Api`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    /// The matching engine is overloaded, the request was not processed
+    Overloaded,
+    RateLimitViolation,
+    /// Missing, invalid or expired credentials
+    AuthenticationError,
+    /// The parameters were rejected
+    ValidationError,
+    /// Any other name, e.g. the generic `HTTPError`
+    Other,
+}
+
+impl ApiErrorKind {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Overloaded" => ApiErrorKind::Overloaded,
+            "RateLimitViolation" | "RateLimitError" => ApiErrorKind::RateLimitViolation,
+            "AuthenticationError" => ApiErrorKind::AuthenticationError,
+            "ValidationError" => ApiErrorKind::ValidationError,
+            _ => ApiErrorKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
 pub enum BitMEXError {
-    #[fail(display = "No Api key set for private api")]
+    /// The request never got a response, e.g. DNS or connection failures
+    #[error("HTTP transport error: {0}")]
+    Transport(#[source] hyper_util::client::legacy::Error),
+    /// The connection broke while the response body was read
+    #[error("Failed to read the response body: {0}")]
+    Body(#[source] hyper::Error),
+    /// A compressed response body that doesn't decompress, see the `gzip` feature
+    #[error("Failed to decompress the response body: {0}")]
+    Decompress(String),
+    #[error("Websocket error: {0}")]
+    Websocket(#[source] Box<tungstenite::Error>),
+    #[error("Request timed out")]
+    Timeout,
+    /// BitMEX rejected the request. `kind` is parsed from `name`, match on it instead of the string.
+    #[error("BitMEX error {status} ({name}): {message}")]
+    Api {
+        status: u16,
+        kind: ApiErrorKind,
+        name: String,
+        message: String,
+    },
+    /// A response that isn't JSON, e.g. an error page of a proxy. Holds the body text.
+    #[error("Unexpected non-JSON response: {0}")]
+    PlainTextError(String),
+    /// The response is not what the endpoint is documented to return
+    #[error("Cannot deserialize the response: {error}, body: {body}")]
+    Deserialize {
+        #[source]
+        error: serde_json::Error,
+        body: String,
+    },
+    /// Deltas of a websocket table arrived without its partial, the table needs a resubscription
+    #[error("Deltas of table {0} arrived without a partial")]
+    SequenceGap(String),
+    /// A book update for a level missing from the `OrderBook`, a delta was missed. Resubscribe to
+    /// the book to get a fresh partial.
+    #[error("Order book of {symbol} is out of sync, level {id} is missing")]
+    BookDesync { symbol: String, id: i64 },
+    /// `OrderBook::validate` found the book inconsistent
+    #[error("Order book is inconsistent: {0:?}")]
+    InvalidBook(Vec<BookViolation>),
+    /// BitMEX rejected a websocket subscription, e.g. for an unknown symbol
+    #[error("Subscription to {args:?} failed: {message}")]
+    SubscriptionFailed { args: Vec<String>, message: String },
+    #[error("No Api key set for private api")]
     NoApiKeySet,
+    /// HTTP 429, `retry_after` is taken from the `Retry-After` header and `reset_at` from
+    /// `X-RateLimit-Reset`, the time the quota is refilled
+    #[error("Rate limited by BitMEX, retry after {retry_after:?}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        reset_at: Option<DateTime<Utc>>,
+    },
+    /// HTTP 401, the api key or signature was rejected. The message tells an unknown key, a wrong
+    /// signature and an expired request apart, see `is_expired_signature`.
+    #[error("Unauthorized: {message}")]
+    Unauthorized { message: String },
+    /// HTTP 403, the api key lacks the permission or the account is restricted
+    #[error("Forbidden: {message}")]
+    Forbidden { message: String },
+    /// HTTP 503, usually the engine being overloaded. The request was not processed.
+    #[error("BitMEX is unavailable: {message}")]
+    ServiceUnavailable { message: String },
+    /// HTTP 503 during scheduled maintenance, told apart from an overload by the message.
+    /// `retry_after` is taken from the `Retry-After` header.
+    #[error("BitMEX is down for maintenance: {message}")]
+    Maintenance { message: String, retry_after: Option<Duration> },
+    /// Rejected without being sent, see `CircuitBreaker`
+    #[error("Circuit breaker is open, BitMEX seems to be down")]
+    CircuitOpen,
+    /// A `BitMEXBuilder` setting was rejected
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    /// A price of an order that isn't a multiple of the instrument's tick size, the request was not
+    /// sent. See `PostOrderRequest::with_tick_size`.
+    #[error("Price {price} is not a multiple of the tick size {tick_size}")]
+    OffTick { price: f64, tick_size: f64 },
+    /// The symbol is missing from the `SymbolCache`, the request was not sent
+    #[error("Unknown symbol: {0}")]
+    UnknownSymbol(String),
+    #[error("Invalid referral code")]
+    InvalidReferralCode,
+    #[error("Invalid url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("Invalid request: {0}")]
+    Http(#[from] hyper::http::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Anything else, e.g. from custom transports
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<tungstenite::Error> for BitMEXError {
+    fn from(e: tungstenite::Error) -> Self {
+        BitMEXError::Websocket(Box::new(e))
+    }
+}
+
+impl BitMEXError {
+    /// Whether the same request may succeed when sent again, i.e. network failures, rate limits and
+    /// an overloaded, unavailable or maintained engine. Other API errors and malformed responses need a human instead.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            BitMEXError::Transport(_)
+                | BitMEXError::Body(_)
+                | BitMEXError::Websocket(_)
+                | BitMEXError::Timeout
+                | BitMEXError::RateLimited { .. }
+                | BitMEXError::ServiceUnavailable { .. }
+                | BitMEXError::Maintenance { .. }
+                | BitMEXError::Api {
+                    kind: ApiErrorKind::Overloaded | ApiErrorKind::RateLimitViolation,
+                    ..
+                }
+        )
+    }
+
+    /// Whether BitMEX rejected a signed request because its `api-expires` was in the past. Usually
+    /// the local clock lags behind, `BitMEX::server_time` tells by how much.
+    pub fn is_expired_signature(&self) -> bool {
+        match self {
+            BitMEXError::Unauthorized { message } => message.contains("expired"),
+            _ => false,
+        }
+    }
+
+    /// The kind of an `Api` error, `None` for any other error
+    pub fn api_error_kind(&self) -> Option<ApiErrorKind> {
+        match self {
+            BitMEXError::Api { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
 }
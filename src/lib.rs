@@ -2,19 +2,35 @@ mod client;
 mod consts;
 mod error;
 pub mod model;
+pub mod signing;
 mod transport;
+pub mod util;
 
-use failure::Error;
-use futures::{Future, IntoFuture};
-
+pub use crate::client::book::{BookLevel, BookViolation, OrderBook, OrderBookStream, TopOfBook};
+pub use crate::client::server_time::ServerTime;
+pub use crate::client::symbol_cache::SymbolCache;
+pub use crate::client::table_cache::TableCache;
+pub use crate::client::topic_streams::{TopicStream, TopicStreams};
 pub use crate::client::websocket::BitMEXWebsocket;
-pub use crate::client::BitMEX;
-pub use crate::error::Result;
+pub use crate::client::tracker::{FundingTracker, OrderTracker, PositionTracker};
+pub use crate::client::{BitMEX, BitMEXBuilder};
+pub use crate::consts::Network;
+pub use crate::signing::ApiSecret;
+#[cfg(feature = "blocking")]
+pub use crate::client::blocking::Blocking;
+pub use crate::error::{ApiErrorKind, BitMEXError, BitMEXResponseError, Result};
+#[cfg(feature = "testing")]
+pub use crate::transport::{MockRequest, MockTransport};
+#[cfg(feature = "tracing")]
+pub use crate::transport::TracingTransport;
+#[cfg(feature = "record")]
+pub use crate::transport::{Fixture, RecordingTransport, ReplayTransport};
+pub use crate::transport::{BoxFuture, CircuitBreaker, CircuitState, ObservedTransport, RateGovernor, RawResponse, RequestObserver, RetryConfig, RetryTransport, ThrottledTransport, Transport, TransportBuilder, TransportTrait};
 
 pub const API_VERSION: &str = "1.2.0";
 pub const SWAGGER_URL: &str = "https://www.bitmex.com/api/explorer/swagger.json";
 
-pub fn check_version() -> impl Future<Item = bool, Error = Error> {
-    let resp = transport::Transport::new().get_swagger().into_future().flatten();
-    resp.map(|desc| desc.info.version == API_VERSION)
+pub async fn check_version() -> Result<bool> {
+    let desc = transport::Transport::new().get_swagger().await?;
+    Ok(desc.info.version == API_VERSION)
 }
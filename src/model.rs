@@ -1,6 +1,7 @@
 pub mod announcement;
 pub mod api_key;
 pub mod chat;
+mod common;
 pub mod definitions;
 pub mod execution;
 pub mod funding;
@@ -21,5 +22,6 @@ pub mod user;
 pub mod user_event;
 pub mod websocket;
 
+pub use self::common::BitMEXDateTime;
 use self::public::GeneralRequest;
-pub use self::public::{BinSize, ContingencyType, ExecInst, OrdType, PegPriceType, Side, TimeInForce, Vararg};
+pub use self::public::{BinSize, Columns, ContingencyType, ExecInst, OrdStatus, OrdType, Pagination, PegPriceType, Satoshi, Side, TickDirection, TimeInForce, UnknownVariantError, Vararg};
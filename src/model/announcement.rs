@@ -1,18 +1,21 @@
-use chrono::{DateTime, Utc};
+use super::{BitMEXDateTime, Columns};
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct GetAnnouncementRequest {
-    pub columns: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Columns>,
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct GetAnnouncementResponse {
     pub id: Option<usize>,
     pub link: Option<String>,
     pub title: Option<String>,
     pub content: Option<String>,
-    pub date: Option<DateTime<Utc>>,
+    pub date: Option<BitMEXDateTime>,
 }
 
 pub type GetAnnouncementUrgentResponse = GetAnnouncementResponse;
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -12,7 +12,7 @@ pub struct GeneralApiKeyResponse {
     pub permissions: Vec<String>,
     pub enabled: bool,
     pub user_id: usize,
-    pub created: DateTime<Utc>,
+    pub created: BitMEXDateTime,
 }
 
 pub type GetApiKeyResponse = GeneralApiKeyResponse;
@@ -20,10 +20,14 @@ pub type GetApiKeyResponse = GeneralApiKeyResponse;
 #[derive(Serialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PostApiKeyRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cidr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub permissions: Option<ApiKeyPermission>,
     pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub token: Option<String>,
 }
 
@@ -48,11 +52,13 @@ impl ToString for ApiKeyPermission {
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Success {
     pub success: bool,
 }
 
 #[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct DeleteApiKeyRequest {
     #[serde(rename = "apiKeyID")]
     pub api_key_id: String,
@@ -1,13 +1,14 @@
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GetChatRequest {
     pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start: Option<usize>,
     pub reverse: bool,
-    #[serde(rename = "channelID")]
+    #[serde(rename = "channelID", skip_serializing_if = "Option::is_none")]
     pub channel_id: Option<usize>,
 }
 
@@ -26,7 +27,7 @@ impl Default for GetChatRequest {
 #[serde(rename_all = "camelCase")]
 pub struct GetChatResponse {
     pub id: usize,
-    pub date: DateTime<Utc>,
+    pub date: BitMEXDateTime,
     pub user: String,
     pub message: String,
     pub html: String,
@@ -56,7 +57,7 @@ impl Default for PostChatRequest {
 #[serde(rename_all = "camelCase")]
 pub struct PostChatResponse {
     pub id: usize,
-    pub date: DateTime<Utc>,
+    pub date: BitMEXDateTime,
     pub user: String,
     pub message: String,
     pub html: String,
@@ -66,12 +67,14 @@ pub struct PostChatResponse {
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct GetChatChannelsResponse {
     pub id: usize,
     pub name: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct GetChatConnectedResponse {
     pub users: usize,
     pub bots: usize,
@@ -0,0 +1,144 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+// Integers above this are milliseconds, below seconds. 10^11 seconds lie in the year 5138.
+const MAX_SECONDS: i64 = 100_000_000_000;
+
+/// A timestamp sent by BitMEX, dereferences to the `DateTime<Utc>`.
+///
+/// Parses RFC 3339 with any precision and offset (`2018-01-08T00:00:00.000Z`,
+/// `2018-01-08T00:00:00+00:00`), timestamps without an offset, which are UTC, bare dates and Unix
+/// timestamps in seconds or milliseconds. Serializes as RFC 3339 with milliseconds, like BitMEX.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitMEXDateTime(pub DateTime<Utc>);
+
+impl BitMEXDateTime {
+    pub fn into_inner(self) -> DateTime<Utc> {
+        self.0
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+            return Some(BitMEXDateTime(time.with_timezone(&Utc)));
+        }
+        ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+            .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok().and_then(|date| date.and_hms_opt(0, 0, 0)))
+            .map(|time| BitMEXDateTime(Utc.from_utc_datetime(&time)))
+    }
+
+    fn from_unix(timestamp: i64) -> Option<Self> {
+        let time = if timestamp.abs() < MAX_SECONDS {
+            Utc.timestamp_opt(timestamp, 0).single()
+        } else {
+            Utc.timestamp_millis_opt(timestamp).single()
+        };
+        time.map(BitMEXDateTime)
+    }
+}
+
+impl Deref for BitMEXDateTime {
+    type Target = DateTime<Utc>;
+
+    fn deref(&self) -> &DateTime<Utc> {
+        &self.0
+    }
+}
+
+impl From<DateTime<Utc>> for BitMEXDateTime {
+    fn from(time: DateTime<Utc>) -> Self {
+        BitMEXDateTime(time)
+    }
+}
+
+impl From<BitMEXDateTime> for DateTime<Utc> {
+    fn from(time: BitMEXDateTime) -> Self {
+        time.0
+    }
+}
+
+impl PartialEq<DateTime<Utc>> for BitMEXDateTime {
+    fn eq(&self, other: &DateTime<Utc>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for BitMEXDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for BitMEXDateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for BitMEXDateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BitMEXDateTimeVisitor)
+    }
+}
+
+struct BitMEXDateTimeVisitor;
+
+impl<'de> Visitor<'de> for BitMEXDateTimeVisitor {
+    type Value = BitMEXDateTime;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an ISO 8601 timestamp or a Unix timestamp")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<BitMEXDateTime, E> {
+        BitMEXDateTime::parse(s).ok_or_else(|| E::invalid_value(de::Unexpected::Str(s), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, timestamp: i64) -> Result<BitMEXDateTime, E> {
+        BitMEXDateTime::from_unix(timestamp).ok_or_else(|| E::invalid_value(de::Unexpected::Signed(timestamp), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, timestamp: u64) -> Result<BitMEXDateTime, E> {
+        let signed = i64::try_from(timestamp).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(timestamp), &self))?;
+        self.visit_i64(signed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use serde_json::{from_str, to_string};
+
+    use super::BitMEXDateTime;
+
+    #[test]
+    fn test_bitmex_date_time() {
+        let time = Utc.with_ymd_and_hms(2018, 1, 8, 0, 0, 0).unwrap();
+        for s in [
+            r#""2018-01-08T00:00:00.000Z""#,
+            r#""2018-01-08T00:00:00Z""#,
+            r#""2018-01-08T00:00:00+00:00""#,
+            r#""2018-01-08T01:00:00.000+01:00""#,
+            r#""2018-01-08T00:00:00.000""#,
+            r#""2018-01-08""#,
+            "1515369600",
+            "1515369600000",
+        ] {
+            assert_eq!(from_str::<BitMEXDateTime>(s).unwrap(), time, "{}", s);
+        }
+
+        let precise: BitMEXDateTime = from_str(r#""2018-01-08T00:00:00.123456Z""#).unwrap();
+        assert_eq!(precise.timestamp_subsec_micros(), 123456);
+        assert_eq!(to_string(&precise).unwrap(), r#""2018-01-08T00:00:00.123Z""#);
+
+        assert!(from_str::<BitMEXDateTime>(r#""yesterday""#).is_err());
+        assert!(from_str::<BitMEXDateTime>("true").is_err());
+    }
+}
@@ -1,12 +1,12 @@
 #![allow(unused)]
 
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_json::Value;
 use uuid::Uuid;
 use serde_derive::{Deserialize, Serialize};
 
 
-pub use super::public::{BinSize, ContingencyType, ExecInst, OrdType, PegPriceType, Side, TimeInForce};
+pub use super::public::{BinSize, ContingencyType, ExecInst, OrdType, PegPriceType, Satoshi, Side, TickDirection, TimeInForce};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,7 +16,7 @@ pub struct Announcement {
     link: Option<String>,
     title: Option<String>,
     content: Option<String>,
-    date: Option<DateTime<Utc>>,
+    date: Option<BitMEXDateTime>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -43,7 +43,7 @@ pub struct APIKey {
     permissions: Option<Vec<XAny>>,
     enabled: Option<bool>,
     user_id: i32,
-    created: Option<DateTime<Utc>>,
+    created: Option<BitMEXDateTime>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -51,7 +51,7 @@ pub struct APIKey {
 /// Trollbox Data
 pub struct Chat {
     id: Option<i32>,
-    date: DateTime<Utc>,
+    date: BitMEXDateTime,
     user: String,
     message: String,
     html: String,
@@ -130,128 +130,129 @@ pub struct Execution {
     exec_comm: Option<i64>,
     home_notional: Option<f64>,
     foreign_notional: Option<f64>,
-    transact_time: Option<DateTime<Utc>>,
-    timestamp: Option<DateTime<Utc>>,
+    transact_time: Option<BitMEXDateTime>,
+    timestamp: Option<BitMEXDateTime>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Swap Funding History
 pub struct Funding {
-    timestamp: DateTime<Utc>,
-    symbol: String,
-    funding_interval: Option<DateTime<Utc>>,
-    funding_rate: Option<f64>,
-    funding_rate_daily: Option<f64>,
+    pub timestamp: BitMEXDateTime,
+    pub symbol: String,
+    pub funding_interval: Option<BitMEXDateTime>,
+    pub funding_rate: Option<f64>,
+    pub funding_rate_daily: Option<f64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Tradeable Contracts, Indices, and History
 pub struct Instrument {
-    symbol: String,
-    root_symbol: Option<String>,
-    state: Option<String>,
-    typ: Option<String>,
-    listing: Option<DateTime<Utc>>,
-    front: Option<DateTime<Utc>>,
-    expiry: Option<DateTime<Utc>>,
-    settle: Option<DateTime<Utc>>,
-    relist_interval: Option<DateTime<Utc>>,
-    inverse_leg: Option<String>,
-    sell_leg: Option<String>,
-    buy_leg: Option<String>,
-    option_strike_pcnt: Option<f64>,
-    option_strike_round: Option<f64>,
-    option_strike_price: Option<f64>,
-    option_multiplier: Option<f64>,
-    position_currency: Option<String>,
-    underlying: Option<String>,
-    quote_currency: Option<String>,
-    underlying_symbol: Option<String>,
-    reference: Option<String>,
-    reference_symbol: Option<String>,
-    calc_interval: Option<DateTime<Utc>>,
-    publish_interval: Option<DateTime<Utc>>,
-    publish_time: Option<DateTime<Utc>>,
-    max_order_qty: Option<i64>,
-    max_price: Option<f64>,
-    lot_size: Option<i64>,
-    tick_size: Option<f64>,
-    multiplier: Option<i64>,
-    settl_currency: Option<String>,
-    underlying_to_position_multiplier: Option<i64>,
-    underlying_to_settle_multiplier: Option<i64>,
-    quote_to_settle_multiplier: Option<i64>,
-    is_quanto: Option<bool>,
-    is_inverse: Option<bool>,
-    init_margin: Option<f64>,
-    maint_margin: Option<f64>,
-    risk_limit: Option<i64>,
-    risk_step: Option<i64>,
-    limit: Option<f64>,
-    capped: Option<bool>,
-    taxed: Option<bool>,
-    deleverage: Option<bool>,
-    maker_fee: Option<f64>,
-    taker_fee: Option<f64>,
-    settlement_fee: Option<f64>,
-    insurance_fee: Option<f64>,
-    funding_base_symbol: Option<String>,
-    funding_quote_symbol: Option<String>,
-    funding_premium_symbol: Option<String>,
-    funding_timestamp: Option<DateTime<Utc>>,
-    funding_interval: Option<DateTime<Utc>>,
-    funding_rate: Option<f64>,
-    indicative_funding_rate: Option<f64>,
-    rebalance_timestamp: Option<DateTime<Utc>>,
-    rebalance_interval: Option<DateTime<Utc>>,
-    opening_timestamp: Option<DateTime<Utc>>,
-    closing_timestamp: Option<DateTime<Utc>>,
-    session_interval: Option<DateTime<Utc>>,
-    prev_close_price: Option<f64>,
-    limit_down_price: Option<f64>,
-    limit_up_price: Option<f64>,
-    bankrupt_limit_down_price: Option<f64>,
-    bankrupt_limit_up_price: Option<f64>,
-    prev_total_volume: Option<i64>,
-    total_volume: Option<i64>,
-    volume: Option<i64>,
-    volume24h: Option<i64>,
-    prev_total_turnover: Option<i64>,
-    total_turnover: Option<i64>,
-    turnover: Option<i64>,
-    turnover24h: Option<i64>,
-    home_notional24h: Option<f64>,
-    foreign_notional24h: Option<f64>,
-    prev_price24h: Option<f64>,
-    vwap: Option<f64>,
-    high_price: Option<f64>,
-    low_price: Option<f64>,
-    last_price: Option<f64>,
-    last_price_protected: Option<f64>,
-    last_tick_direction: Option<String>,
-    last_change_pcnt: Option<f64>,
-    bid_price: Option<f64>,
-    mid_price: Option<f64>,
-    ask_price: Option<f64>,
-    impact_bid_price: Option<f64>,
-    impact_mid_price: Option<f64>,
-    impact_ask_price: Option<f64>,
-    has_liquidity: Option<bool>,
-    open_interest: Option<i64>,
-    open_value: Option<i64>,
-    fair_method: Option<String>,
-    fair_basis_rate: Option<f64>,
-    fair_basis: Option<f64>,
-    fair_price: Option<f64>,
-    mark_method: Option<String>,
-    mark_price: Option<f64>,
-    indicative_tax_rate: Option<f64>,
-    indicative_settle_price: Option<f64>,
-    option_underlying_price: Option<f64>,
-    settled_price: Option<f64>,
-    timestamp: Option<DateTime<Utc>>,
+    pub symbol: String,
+    pub root_symbol: Option<String>,
+    pub state: Option<String>,
+    pub typ: Option<String>,
+    pub listing: Option<BitMEXDateTime>,
+    pub front: Option<BitMEXDateTime>,
+    pub expiry: Option<BitMEXDateTime>,
+    pub settle: Option<BitMEXDateTime>,
+    pub relist_interval: Option<BitMEXDateTime>,
+    pub inverse_leg: Option<String>,
+    pub sell_leg: Option<String>,
+    pub buy_leg: Option<String>,
+    pub option_strike_pcnt: Option<f64>,
+    pub option_strike_round: Option<f64>,
+    pub option_strike_price: Option<f64>,
+    pub option_multiplier: Option<f64>,
+    pub position_currency: Option<String>,
+    pub underlying: Option<String>,
+    pub quote_currency: Option<String>,
+    pub underlying_symbol: Option<String>,
+    pub reference: Option<String>,
+    pub reference_symbol: Option<String>,
+    pub calc_interval: Option<BitMEXDateTime>,
+    pub publish_interval: Option<BitMEXDateTime>,
+    pub publish_time: Option<BitMEXDateTime>,
+    pub max_order_qty: Option<i64>,
+    pub max_price: Option<f64>,
+    pub lot_size: Option<i64>,
+    pub tick_size: Option<f64>,
+    pub multiplier: Option<i64>,
+    pub settl_currency: Option<String>,
+    pub underlying_to_position_multiplier: Option<i64>,
+    pub underlying_to_settle_multiplier: Option<i64>,
+    pub quote_to_settle_multiplier: Option<i64>,
+    pub is_quanto: Option<bool>,
+    pub is_inverse: Option<bool>,
+    pub init_margin: Option<f64>,
+    pub maint_margin: Option<f64>,
+    pub risk_limit: Option<i64>,
+    pub risk_step: Option<i64>,
+    pub limit: Option<f64>,
+    pub capped: Option<bool>,
+    pub taxed: Option<bool>,
+    pub deleverage: Option<bool>,
+    pub maker_fee: Option<f64>,
+    pub taker_fee: Option<f64>,
+    pub settlement_fee: Option<f64>,
+    pub insurance_fee: Option<f64>,
+    pub funding_base_symbol: Option<String>,
+    pub funding_quote_symbol: Option<String>,
+    pub funding_premium_symbol: Option<String>,
+    pub funding_timestamp: Option<BitMEXDateTime>,
+    pub funding_interval: Option<BitMEXDateTime>,
+    pub funding_rate: Option<f64>,
+    pub indicative_funding_rate: Option<f64>,
+    pub rebalance_timestamp: Option<BitMEXDateTime>,
+    pub rebalance_interval: Option<BitMEXDateTime>,
+    pub opening_timestamp: Option<BitMEXDateTime>,
+    pub closing_timestamp: Option<BitMEXDateTime>,
+    pub session_interval: Option<BitMEXDateTime>,
+    pub prev_close_price: Option<f64>,
+    pub limit_down_price: Option<f64>,
+    pub limit_up_price: Option<f64>,
+    pub bankrupt_limit_down_price: Option<f64>,
+    pub bankrupt_limit_up_price: Option<f64>,
+    pub prev_total_volume: Option<i64>,
+    pub total_volume: Option<i64>,
+    pub volume: Option<i64>,
+    pub volume24h: Option<i64>,
+    pub prev_total_turnover: Option<i64>,
+    pub total_turnover: Option<i64>,
+    pub turnover: Option<i64>,
+    pub turnover24h: Option<i64>,
+    pub home_notional24h: Option<f64>,
+    pub foreign_notional24h: Option<f64>,
+    pub prev_price24h: Option<f64>,
+    pub vwap: Option<f64>,
+    pub high_price: Option<f64>,
+    pub low_price: Option<f64>,
+    pub last_price: Option<f64>,
+    pub last_price_protected: Option<f64>,
+    pub last_tick_direction: Option<TickDirection>,
+    pub last_change_pcnt: Option<f64>,
+    pub bid_price: Option<f64>,
+    pub mid_price: Option<f64>,
+    pub ask_price: Option<f64>,
+    pub impact_bid_price: Option<f64>,
+    pub impact_mid_price: Option<f64>,
+    pub impact_ask_price: Option<f64>,
+    pub has_liquidity: Option<bool>,
+    pub open_interest: Option<i64>,
+    pub open_value: Option<i64>,
+    pub fair_method: Option<String>,
+    pub fair_basis_rate: Option<f64>,
+    pub fair_basis: Option<f64>,
+    pub fair_price: Option<f64>,
+    pub mark_method: Option<String>,
+    pub mark_price: Option<f64>,
+    pub indicative_tax_rate: Option<f64>,
+    pub indicative_settle_price: Option<f64>,
+    pub option_underlying_price: Option<f64>,
+    pub settled_price_adjustment_rate: Option<f64>,
+    pub settled_price: Option<f64>,
+    pub timestamp: Option<BitMEXDateTime>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -266,13 +267,13 @@ pub struct InstrumentInterval {
 #[serde(rename_all = "camelCase")]
 /// empty
 pub struct IndexComposite {
-    timestamp: DateTime<Utc>,
+    timestamp: BitMEXDateTime,
     symbol: Option<String>,
     index_symbol: Option<String>,
     reference: Option<String>,
     last_price: Option<f64>,
     weight: Option<f64>,
-    logged: Option<DateTime<Utc>>,
+    logged: Option<BitMEXDateTime>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -280,7 +281,7 @@ pub struct IndexComposite {
 /// Insurance Fund Data
 pub struct Insurance {
     currency: String,
-    timestamp: DateTime<Utc>,
+    timestamp: BitMEXDateTime,
     wallet_balance: Option<i64>,
 }
 
@@ -310,7 +311,7 @@ pub struct Liquidation {
 /// Account Notifications
 pub struct GlobalNotification {
     id: Option<i32>,
-    date: DateTime<Utc>,
+    date: BitMEXDateTime,
     title: String,
     body: String,
     ttl: i32,
@@ -326,168 +327,168 @@ pub struct GlobalNotification {
 /// Placement, Cancellation, Amending, and History
 pub struct Order {
     #[serde(rename = "orderID")]
-    order_id: Uuid,
+    pub order_id: Uuid,
     #[serde(rename = "clOrdID")]
-    cl_ord_id: Option<String>,
+    pub cl_ord_id: Option<String>,
     #[serde(rename = "clOrdLinkID")]
-    cl_ord_link_id: Option<String>,
-    account: Option<i64>,
-    symbol: Option<String>,
-    side: Option<Side>,
-    simple_order_qty: Option<f64>,
-    order_qty: Option<i64>,
-    price: Option<f64>,
-    display_qty: Option<i64>,
-    stop_px: Option<f64>,
-    peg_offset_value: Option<f64>,
-    peg_price_type: Option<PegPriceType>,
-    currency: Option<String>,
-    settl_currency: Option<String>,
-    ord_type: Option<OrdType>,
-    time_in_force: Option<TimeInForce>,
-    exec_inst: Option<ExecInst>,
-    contingency_type: Option<ContingencyType>,
-    ex_destination: Option<String>,
-    ord_status: Option<String>,
-    triggered: Option<String>,
-    working_indicator: Option<bool>,
-    ord_rej_reason: Option<String>,
-    simple_leaves_qty: Option<f64>,
-    leaves_qty: Option<i64>,
-    simple_cum_qty: Option<f64>,
-    cum_qty: Option<i64>,
-    avg_px: Option<f64>,
-    multi_leg_reporting_type: Option<String>,
-    text: Option<String>,
-    transact_time: Option<DateTime<Utc>>,
-    timestamp: Option<DateTime<Utc>>,
+    pub cl_ord_link_id: Option<String>,
+    pub account: Option<i64>,
+    pub symbol: Option<String>,
+    pub side: Option<Side>,
+    pub simple_order_qty: Option<f64>,
+    pub order_qty: Option<i64>,
+    pub price: Option<f64>,
+    pub display_qty: Option<i64>,
+    pub stop_px: Option<f64>,
+    pub peg_offset_value: Option<f64>,
+    pub peg_price_type: Option<PegPriceType>,
+    pub currency: Option<String>,
+    pub settl_currency: Option<String>,
+    pub ord_type: Option<OrdType>,
+    pub time_in_force: Option<TimeInForce>,
+    pub exec_inst: Option<ExecInst>,
+    pub contingency_type: Option<ContingencyType>,
+    pub ex_destination: Option<String>,
+    pub ord_status: Option<String>,
+    pub triggered: Option<String>,
+    pub working_indicator: Option<bool>,
+    pub ord_rej_reason: Option<String>,
+    pub simple_leaves_qty: Option<f64>,
+    pub leaves_qty: Option<i64>,
+    pub simple_cum_qty: Option<f64>,
+    pub cum_qty: Option<i64>,
+    pub avg_px: Option<f64>,
+    pub multi_leg_reporting_type: Option<String>,
+    pub text: Option<String>,
+    pub transact_time: Option<BitMEXDateTime>,
+    pub timestamp: Option<BitMEXDateTime>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// empty
 pub struct OrderBookL2 {
-    symbol: String,
-    id: i64,
-    side: Side,
-    size: Option<i64>,
-    price: Option<f64>,
+    pub symbol: String,
+    pub id: i64,
+    pub side: Side,
+    pub size: Option<i64>,
+    pub price: Option<f64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Summary of Open and Closed Positions
 pub struct Position {
-    account: i64,
-    symbol: String,
-    currency: String,
-    underlying: Option<String>,
-    quote_currency: Option<String>,
-    commission: Option<f64>,
-    init_margin_req: Option<f64>,
-    maint_margin_req: Option<f64>,
-    risk_limit: Option<i64>,
-    leverage: Option<f64>,
-    cross_margin: Option<bool>,
-    deleverage_percentile: Option<f64>,
-    rebalanced_pnl: Option<i64>,
-    prev_realised_pnl: Option<i64>,
-    prev_unrealised_pnl: Option<i64>,
-    prev_close_price: Option<f64>,
-    opening_timestamp: Option<DateTime<Utc>>,
-    opening_qty: Option<i64>,
-    opening_cost: Option<i64>,
-    opening_comm: Option<i64>,
-    open_order_buy_qty: Option<i64>,
-    open_order_buy_cost: Option<i64>,
-    open_order_buy_premium: Option<i64>,
-    open_order_sell_qty: Option<i64>,
-    open_order_sell_cost: Option<i64>,
-    open_order_sell_premium: Option<i64>,
-    exec_buy_qty: Option<i64>,
-    exec_buy_cost: Option<i64>,
-    exec_sell_qty: Option<i64>,
-    exec_sell_cost: Option<i64>,
-    exec_qty: Option<i64>,
-    exec_cost: Option<i64>,
-    exec_comm: Option<i64>,
-    current_timestamp: Option<DateTime<Utc>>,
-    current_qty: Option<i64>,
-    current_cost: Option<i64>,
-    current_comm: Option<i64>,
-    realised_cost: Option<i64>,
-    unrealised_cost: Option<i64>,
-    gross_open_cost: Option<i64>,
-    gross_open_premium: Option<i64>,
-    gross_exec_cost: Option<i64>,
-    is_open: Option<bool>,
-    mark_price: Option<f64>,
-    mark_value: Option<i64>,
-    risk_value: Option<i64>,
-    home_notional: Option<f64>,
-    foreign_notional: Option<f64>,
-    pos_state: Option<String>,
-    pos_cost: Option<i64>,
-    pos_cost2: Option<i64>,
-    pos_cross: Option<i64>,
-    pos_init: Option<i64>,
-    pos_comm: Option<i64>,
-    pos_loss: Option<i64>,
-    pos_margin: Option<i64>,
-    pos_maint: Option<i64>,
-    pos_allowance: Option<i64>,
-    taxable_margin: Option<i64>,
-    init_margin: Option<i64>,
-    maint_margin: Option<i64>,
-    session_margin: Option<i64>,
-    target_excess_margin: Option<i64>,
-    var_margin: Option<i64>,
-    realised_gross_pnl: Option<i64>,
-    realised_tax: Option<i64>,
-    realised_pnl: Option<i64>,
-    unrealised_gross_pnl: Option<i64>,
-    long_bankrupt: Option<i64>,
-    short_bankrupt: Option<i64>,
-    tax_base: Option<i64>,
-    indicative_tax_rate: Option<f64>,
-    indicative_tax: Option<i64>,
-    unrealised_tax: Option<i64>,
-    unrealised_pnl: Option<i64>,
-    unrealised_pnl_pcnt: Option<f64>,
-    unrealised_roe_pcnt: Option<f64>,
-    simple_qty: Option<f64>,
-    simple_cost: Option<f64>,
-    simple_value: Option<f64>,
-    simple_pnl: Option<f64>,
-    simple_pnl_pcnt: Option<f64>,
-    avg_cost_price: Option<f64>,
-    avg_entry_price: Option<f64>,
-    break_even_price: Option<f64>,
-    margin_call_price: Option<f64>,
-    liquidation_price: Option<f64>,
-    bankrupt_price: Option<f64>,
-    timestamp: Option<DateTime<Utc>>,
-    last_price: Option<f64>,
-    last_value: Option<i64>,
+    pub account: i64,
+    pub symbol: String,
+    pub currency: String,
+    pub underlying: Option<String>,
+    pub quote_currency: Option<String>,
+    pub commission: Option<f64>,
+    pub init_margin_req: Option<f64>,
+    pub maint_margin_req: Option<f64>,
+    pub risk_limit: Option<i64>,
+    pub leverage: Option<f64>,
+    pub cross_margin: Option<bool>,
+    pub deleverage_percentile: Option<f64>,
+    pub rebalanced_pnl: Option<i64>,
+    pub prev_realised_pnl: Option<i64>,
+    pub prev_unrealised_pnl: Option<i64>,
+    pub prev_close_price: Option<f64>,
+    pub opening_timestamp: Option<BitMEXDateTime>,
+    pub opening_qty: Option<i64>,
+    pub opening_cost: Option<i64>,
+    pub opening_comm: Option<i64>,
+    pub open_order_buy_qty: Option<i64>,
+    pub open_order_buy_cost: Option<i64>,
+    pub open_order_buy_premium: Option<i64>,
+    pub open_order_sell_qty: Option<i64>,
+    pub open_order_sell_cost: Option<i64>,
+    pub open_order_sell_premium: Option<i64>,
+    pub exec_buy_qty: Option<i64>,
+    pub exec_buy_cost: Option<i64>,
+    pub exec_sell_qty: Option<i64>,
+    pub exec_sell_cost: Option<i64>,
+    pub exec_qty: Option<i64>,
+    pub exec_cost: Option<i64>,
+    pub exec_comm: Option<i64>,
+    pub current_timestamp: Option<BitMEXDateTime>,
+    pub current_qty: Option<i64>,
+    pub current_cost: Option<i64>,
+    pub current_comm: Option<i64>,
+    pub realised_cost: Option<i64>,
+    pub unrealised_cost: Option<i64>,
+    pub gross_open_cost: Option<i64>,
+    pub gross_open_premium: Option<i64>,
+    pub gross_exec_cost: Option<i64>,
+    pub is_open: Option<bool>,
+    pub mark_price: Option<f64>,
+    pub mark_value: Option<i64>,
+    pub risk_value: Option<i64>,
+    pub home_notional: Option<f64>,
+    pub foreign_notional: Option<f64>,
+    pub pos_state: Option<String>,
+    pub pos_cost: Option<i64>,
+    pub pos_cost2: Option<i64>,
+    pub pos_cross: Option<i64>,
+    pub pos_init: Option<i64>,
+    pub pos_comm: Option<i64>,
+    pub pos_loss: Option<i64>,
+    pub pos_margin: Option<i64>,
+    pub pos_maint: Option<i64>,
+    pub pos_allowance: Option<i64>,
+    pub taxable_margin: Option<i64>,
+    pub init_margin: Option<i64>,
+    pub maint_margin: Option<i64>,
+    pub session_margin: Option<i64>,
+    pub target_excess_margin: Option<i64>,
+    pub var_margin: Option<i64>,
+    pub realised_gross_pnl: Option<i64>,
+    pub realised_tax: Option<i64>,
+    pub realised_pnl: Option<i64>,
+    pub unrealised_gross_pnl: Option<i64>,
+    pub long_bankrupt: Option<i64>,
+    pub short_bankrupt: Option<i64>,
+    pub tax_base: Option<i64>,
+    pub indicative_tax_rate: Option<f64>,
+    pub indicative_tax: Option<i64>,
+    pub unrealised_tax: Option<i64>,
+    pub unrealised_pnl: Option<i64>,
+    pub unrealised_pnl_pcnt: Option<f64>,
+    pub unrealised_roe_pcnt: Option<f64>,
+    pub simple_qty: Option<f64>,
+    pub simple_cost: Option<f64>,
+    pub simple_value: Option<f64>,
+    pub simple_pnl: Option<f64>,
+    pub simple_pnl_pcnt: Option<f64>,
+    pub avg_cost_price: Option<f64>,
+    pub avg_entry_price: Option<f64>,
+    pub break_even_price: Option<f64>,
+    pub margin_call_price: Option<f64>,
+    pub liquidation_price: Option<f64>,
+    pub bankrupt_price: Option<f64>,
+    pub timestamp: Option<BitMEXDateTime>,
+    pub last_price: Option<f64>,
+    pub last_value: Option<i64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Best Bid/Offer Snapshots & Historical Bins
 pub struct Quote {
-    timestamp: DateTime<Utc>,
-    symbol: String,
-    bid_size: Option<i64>,
-    bid_price: Option<f64>,
-    ask_price: Option<f64>,
-    ask_size: Option<i64>,
+    pub timestamp: BitMEXDateTime,
+    pub symbol: String,
+    pub bid_size: Option<i64>,
+    pub bid_price: Option<f64>,
+    pub ask_price: Option<f64>,
+    pub ask_size: Option<i64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Historical Settlement Data
 pub struct Settlement {
-    timestamp: DateTime<Utc>,
+    timestamp: BitMEXDateTime,
     symbol: String,
     settlement_type: Option<String>,
     settled_price: Option<f64>,
@@ -514,7 +515,7 @@ pub struct Stats {
 #[serde(rename_all = "camelCase")]
 /// empty
 pub struct StatsHistory {
-    date: DateTime<Utc>,
+    date: BitMEXDateTime,
     root_symbol: String,
     currency: Option<String>,
     volume: Option<i64>,
@@ -537,36 +538,36 @@ pub struct StatsUSD {
 #[serde(rename_all = "camelCase")]
 /// Individual & Bucketed Trades
 pub struct Trade {
-    timestamp: DateTime<Utc>,
-    symbol: String,
-    side: Option<Side>,
-    size: Option<i64>,
-    price: Option<f64>,
-    tick_direction: Option<String>,
+    pub timestamp: BitMEXDateTime,
+    pub symbol: String,
+    pub side: Option<Side>,
+    pub size: Option<i64>,
+    pub price: Option<f64>,
+    pub tick_direction: Option<TickDirection>,
     #[serde(rename = "trdMatchID")]
-    trd_match_id: Option<Uuid>,
-    gross_value: Option<i64>,
-    home_notional: Option<f64>,
-    foreign_notional: Option<f64>,
+    pub trd_match_id: Option<Uuid>,
+    pub gross_value: Option<i64>,
+    pub home_notional: Option<f64>,
+    pub foreign_notional: Option<f64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// empty
 pub struct TradeBin {
-    timestamp: DateTime<Utc>,
-    symbol: String,
-    open: Option<f64>,
-    high: Option<f64>,
-    low: Option<f64>,
-    close: Option<f64>,
-    trades: Option<i64>,
-    volume: Option<i64>,
-    vwap: Option<f64>,
-    last_size: Option<i64>,
-    turnover: Option<i64>,
-    home_notional: Option<f64>,
-    foreign_notional: Option<f64>,
+    pub timestamp: BitMEXDateTime,
+    pub symbol: String,
+    pub open: Option<f64>,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    pub close: Option<f64>,
+    pub trades: Option<i64>,
+    pub volume: Option<i64>,
+    pub vwap: Option<f64>,
+    pub last_size: Option<i64>,
+    pub turnover: Option<i64>,
+    pub home_notional: Option<f64>,
+    pub foreign_notional: Option<f64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -575,26 +576,26 @@ pub struct TradeBin {
 pub struct Wallet {
     pub account: i64,
     pub currency: String,
-    pub prev_deposited: Option<i64>,
-    pub prev_withdrawn: Option<i64>,
-    pub prev_transfer_in: Option<i64>,
-    pub prev_transfer_out: Option<i64>,
-    pub prev_amount: Option<i64>,
-    pub prev_timestamp: Option<DateTime<Utc>>,
-    pub delta_deposited: Option<i64>,
-    pub delta_withdrawn: Option<i64>,
-    pub delta_transfer_in: Option<i64>,
-    pub delta_transfer_out: Option<i64>,
-    pub delta_amount: Option<i64>,
-    pub deposited: Option<i64>,
-    pub withdrawn: Option<i64>,
-    pub transfer_in: Option<i64>,
-    pub transfer_out: Option<i64>,
-    pub amount: Option<i64>,
-    pub pending_credit: Option<i64>,
-    pub pending_debit: Option<i64>,
-    pub confirmed_debit: Option<i64>,
-    pub timestamp: Option<DateTime<Utc>>,
+    pub prev_deposited: Option<Satoshi>,
+    pub prev_withdrawn: Option<Satoshi>,
+    pub prev_transfer_in: Option<Satoshi>,
+    pub prev_transfer_out: Option<Satoshi>,
+    pub prev_amount: Option<Satoshi>,
+    pub prev_timestamp: Option<BitMEXDateTime>,
+    pub delta_deposited: Option<Satoshi>,
+    pub delta_withdrawn: Option<Satoshi>,
+    pub delta_transfer_in: Option<Satoshi>,
+    pub delta_transfer_out: Option<Satoshi>,
+    pub delta_amount: Option<Satoshi>,
+    pub deposited: Option<Satoshi>,
+    pub withdrawn: Option<Satoshi>,
+    pub transfer_in: Option<Satoshi>,
+    pub transfer_out: Option<Satoshi>,
+    pub amount: Option<Satoshi>,
+    pub pending_credit: Option<Satoshi>,
+    pub pending_debit: Option<Satoshi>,
+    pub confirmed_debit: Option<Satoshi>,
+    pub timestamp: Option<BitMEXDateTime>,
     pub addr: Option<String>,
     pub script: Option<String>,
     pub withdrawal_lock: Option<Vec<String>>,
@@ -615,8 +616,8 @@ pub struct Transaction {
     address: Option<String>,
     tx: Option<String>,
     text: Option<String>,
-    transact_time: Option<DateTime<Utc>>,
-    timestamp: Option<DateTime<Utc>>,
+    transact_time: Option<BitMEXDateTime>,
+    timestamp: Option<BitMEXDateTime>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -625,7 +626,7 @@ pub struct Transaction {
 pub struct AccessToken {
     id: String,
     ttl: Option<f64>,
-    created: Option<DateTime<Utc>>,
+    created: Option<BitMEXDateTime>,
     user_id: Option<f64>,
 }
 
@@ -638,7 +639,7 @@ pub struct Affiliate {
     prev_payout: Option<i64>,
     prev_turnover: Option<i64>,
     prev_comm: Option<i64>,
-    prev_timestamp: Option<DateTime<Utc>>,
+    prev_timestamp: Option<BitMEXDateTime>,
     exec_turnover: Option<i64>,
     exec_comm: Option<i64>,
     total_referrals: Option<i64>,
@@ -646,7 +647,7 @@ pub struct Affiliate {
     total_comm: Option<i64>,
     payout_pcnt: Option<f64>,
     pending_payout: Option<i64>,
-    timestamp: Option<DateTime<Utc>>,
+    timestamp: Option<BitMEXDateTime>,
     referrer_account: Option<f64>,
     referral_discount: Option<f64>,
     affiliate_payout: Option<f64>,
@@ -663,8 +664,8 @@ pub struct User {
     username: String,
     email: String,
     phone: Option<String>,
-    created: Option<DateTime<Utc>>,
-    last_updated: Option<DateTime<Utc>>,
+    created: Option<BitMEXDateTime>,
+    last_updated: Option<BitMEXDateTime>,
     preferences: Option<UserPreferences>,
     restricted_engine_fields: Option<Value>,
     tfa_enabled: Option<String>,
@@ -691,47 +692,47 @@ pub struct UserCommission {
 #[serde(rename_all = "camelCase")]
 /// empty
 pub struct Margin {
-    account: i64,
-    currency: String,
-    risk_limit: Option<i64>,
-    prev_state: Option<String>,
-    state: Option<String>,
-    action: Option<String>,
-    amount: Option<i64>,
-    pending_credit: Option<i64>,
-    pending_debit: Option<i64>,
-    confirmed_debit: Option<i64>,
-    prev_realised_pnl: Option<i64>,
-    prev_unrealised_pnl: Option<i64>,
-    gross_comm: Option<i64>,
-    gross_open_cost: Option<i64>,
-    gross_open_premium: Option<i64>,
-    gross_exec_cost: Option<i64>,
-    gross_mark_value: Option<i64>,
-    risk_value: Option<i64>,
-    taxable_margin: Option<i64>,
-    init_margin: Option<i64>,
-    maint_margin: Option<i64>,
-    session_margin: Option<i64>,
-    target_excess_margin: Option<i64>,
-    var_margin: Option<i64>,
-    realised_pnl: Option<i64>,
-    unrealised_pnl: Option<i64>,
-    indicative_tax: Option<i64>,
-    unrealised_profit: Option<i64>,
-    synthetic_margin: Option<i64>,
-    wallet_balance: Option<i64>,
-    margin_balance: Option<i64>,
-    margin_balance_pcnt: Option<f64>,
-    margin_leverage: Option<f64>,
-    margin_used_pcnt: Option<f64>,
-    excess_margin: Option<i64>,
-    excess_margin_pcnt: Option<f64>,
-    available_margin: Option<i64>,
-    withdrawable_margin: Option<i64>,
-    timestamp: Option<DateTime<Utc>>,
-    gross_last_value: Option<i64>,
-    commission: Option<f64>,
+    pub account: i64,
+    pub currency: String,
+    pub risk_limit: Option<Satoshi>,
+    pub prev_state: Option<String>,
+    pub state: Option<String>,
+    pub action: Option<String>,
+    pub amount: Option<Satoshi>,
+    pub pending_credit: Option<Satoshi>,
+    pub pending_debit: Option<Satoshi>,
+    pub confirmed_debit: Option<Satoshi>,
+    pub prev_realised_pnl: Option<Satoshi>,
+    pub prev_unrealised_pnl: Option<Satoshi>,
+    pub gross_comm: Option<Satoshi>,
+    pub gross_open_cost: Option<Satoshi>,
+    pub gross_open_premium: Option<Satoshi>,
+    pub gross_exec_cost: Option<Satoshi>,
+    pub gross_mark_value: Option<Satoshi>,
+    pub risk_value: Option<Satoshi>,
+    pub taxable_margin: Option<Satoshi>,
+    pub init_margin: Option<Satoshi>,
+    pub maint_margin: Option<Satoshi>,
+    pub session_margin: Option<Satoshi>,
+    pub target_excess_margin: Option<Satoshi>,
+    pub var_margin: Option<Satoshi>,
+    pub realised_pnl: Option<Satoshi>,
+    pub unrealised_pnl: Option<Satoshi>,
+    pub indicative_tax: Option<Satoshi>,
+    pub unrealised_profit: Option<Satoshi>,
+    pub synthetic_margin: Option<Satoshi>,
+    pub wallet_balance: Option<Satoshi>,
+    pub margin_balance: Option<Satoshi>,
+    pub margin_balance_pcnt: Option<f64>,
+    pub margin_leverage: Option<f64>,
+    pub margin_used_pcnt: Option<f64>,
+    pub excess_margin: Option<Satoshi>,
+    pub excess_margin_pcnt: Option<f64>,
+    pub available_margin: Option<Satoshi>,
+    pub withdrawable_margin: Option<Satoshi>,
+    pub timestamp: Option<BitMEXDateTime>,
+    pub gross_last_value: Option<Satoshi>,
+    pub commission: Option<f64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -748,17 +749,17 @@ pub struct CommunicationToken {
 #[serde(rename_all = "camelCase")]
 /// User Events for auditing
 pub struct UserEvent {
-    id: Option<f64>,
-    r#type: String,
-    status: String,
-    user_id: f64,
-    created_by_id: f64,
-    ip: Option<String>,
-    geoip_country: Option<String>,
-    geoip_region: Option<String>,
-    geoip_sub_region: Option<String>,
-    event_meta: Option<Value>,
-    created: DateTime<Utc>,
+    pub id: Option<i64>,
+    pub r#type: String,
+    pub status: String,
+    pub user_id: i64,
+    pub created_by_id: Option<i64>,
+    pub ip: Option<String>,
+    pub geoip_country: Option<String>,
+    pub geoip_region: Option<String>,
+    pub geoip_sub_region: Option<String>,
+    pub event_meta: Option<Value>,
+    pub created: BitMEXDateTime,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -767,7 +768,7 @@ pub struct UserEvent {
 pub struct UserPreferences {
     alert_on_liquidations: Option<bool>,
     animations_enabled: Option<bool>,
-    announcements_last_seen: Option<DateTime<Utc>>,
+    announcements_last_seen: Option<BitMEXDateTime>,
     #[serde(rename = "chatChannelID")]
     chat_channel_id: Option<f64>,
     color_theme: Option<String>,
@@ -2,7 +2,7 @@ use super::GeneralRequest;
 pub use super::Side;
 
 use serde_derive::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 
 pub type GetExecutionRequest = GeneralRequest;
 
@@ -59,8 +59,8 @@ pub struct GetExecutionResponse {
     pub exec_comm: f64,
     pub home_notional: f64,
     pub foreign_notional: f64,
-    pub transact_time: DateTime<Utc>,
-    pub timestamp: DateTime<Utc>,
+    pub transact_time: BitMEXDateTime,
+    pub timestamp: BitMEXDateTime,
 }
 
 pub type GetExecutionTradeHistoryRequest = GeneralRequest;
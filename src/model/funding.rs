@@ -1,5 +1,5 @@
 use super::GeneralRequest;
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_derive::Deserialize;
 
 pub type GetFundingRequest = GeneralRequest;
@@ -7,9 +7,9 @@ pub type GetFundingRequest = GeneralRequest;
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetFundingResponse {
-    pub timestamp: DateTime<Utc>,
+    pub timestamp: BitMEXDateTime,
     pub symbol: String,
-    pub funding_interval: DateTime<Utc>,
+    pub funding_interval: BitMEXDateTime,
     pub funding_rate: f64,
     pub funding_rate_daily: f64,
 }
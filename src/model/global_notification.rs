@@ -1,11 +1,11 @@
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_derive::Deserialize;
 
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetGlobalNotificationResponse {
     pub id: usize,
-    pub date: DateTime<Utc>,
+    pub date: BitMEXDateTime,
     pub title: String,
     pub body: String,
     pub ttl: usize,
@@ -1,5 +1,5 @@
-use super::GeneralRequest;
-use chrono::{DateTime, Utc};
+use super::{GeneralRequest, TickDirection};
+use super::BitMEXDateTime;
 use serde_derive::Deserialize;
 
 pub type GetInstrumentRequest = GeneralRequest;
@@ -103,7 +103,7 @@ pub struct GetInstrumentResponse {
     pub low_price: Option<f64>,
     pub last_price: Option<f64>,
     pub last_price_protected: Option<f64>,
-    pub last_tick_direction: Option<String>,
+    pub last_tick_direction: Option<TickDirection>,
     pub last_change_pcnt: Option<f64>,
     pub bid_price: Option<f64>,
     pub mid_price: Option<f64>,
@@ -123,8 +123,37 @@ pub struct GetInstrumentResponse {
     pub indicative_tax_rate: Option<f64>,
     pub indicative_settle_price: Option<f64>,
     pub option_underlying_price: Option<f64>,
+    pub settled_price_adjustment_rate: Option<f64>,
     pub settled_price: Option<f64>,
-    pub timestamp: DateTime<Utc>,
+    pub timestamp: BitMEXDateTime,
+}
+
+/// The fields of an instrument that orders have to respect, small enough to cache for every
+/// symbol. Built from a `GetInstrumentResponse`, missing fields leave the order unconstrained:
+/// no tick, a lot of 1, no maximum, a multiplier of 0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinOrderInfo {
+    pub symbol: String,
+    pub tick_size: f64,
+    pub lot_size: i64,
+    pub max_order_qty: i64,
+    pub max_price: f64,
+    pub is_inverse: bool,
+    pub multiplier: i64,
+}
+
+impl From<&GetInstrumentResponse> for MinOrderInfo {
+    fn from(instrument: &GetInstrumentResponse) -> Self {
+        MinOrderInfo {
+            symbol: instrument.symbol.clone().unwrap_or_default(),
+            tick_size: instrument.tick_size.unwrap_or(0.),
+            lot_size: instrument.lot_size.map_or(1, |lot_size| lot_size as i64),
+            max_order_qty: instrument.max_order_qty.map_or(i64::MAX, |max| max as i64),
+            max_price: instrument.max_price.unwrap_or(f64::INFINITY),
+            is_inverse: instrument.is_inverse.unwrap_or(false),
+            multiplier: instrument.multiplier.map_or(0, |multiplier| multiplier as i64),
+        }
+    }
 }
 
 pub type GetInstrumentActiveResponse = GetInstrumentResponse;
@@ -132,6 +161,7 @@ pub type GetInstrumentActiveAndIndicesResponse = GetInstrumentResponse;
 pub type GetInstrumentIndicesResponse = GetInstrumentResponse;
 
 #[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetInstrumentActiveIntervalsResponse {
     pub intervals: Vec<String>,
     pub symbols: Vec<String>,
@@ -142,11 +172,42 @@ pub type GetInstrumentCompositeIndexRequest = GetInstrumentRequest;
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetInstrumentCompositeIndexResponse {
-    pub timestamp: DateTime<Utc>,
+    pub timestamp: BitMEXDateTime,
     pub symbol: String,
     pub index_symbol: String,
     pub reference: String,
-    pub last_price: f64,
-    pub weight: f64,
-    pub logged: DateTime<Utc>,
+    pub last_price: Option<f64>,
+    pub weight: Option<f64>,
+    pub logged: BitMEXDateTime,
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::from_str;
+
+    use super::GetInstrumentResponse;
+
+    // Trimmed from /instrument, every field that isn't sent for the contract type is null
+    const PERPETUAL: &str = r#"{"symbol":"XBTUSD","rootSymbol":"XBT","state":"Open","typ":"FFWCSX","listing":"2016-05-13T12:00:00.000Z","front":"2016-05-13T12:00:00.000Z","expiry":null,"settle":null,"relistInterval":null,"inverseLeg":"","sellLeg":"","buyLeg":"","optionStrikePcnt":null,"optionStrikeRound":null,"optionStrikePrice":null,"optionMultiplier":null,"positionCurrency":"USD","underlying":"XBT","quoteCurrency":"USD","underlyingSymbol":"XBT=","reference":"BMEX","referenceSymbol":".BXBT","calcInterval":null,"publishInterval":null,"publishTime":null,"maxOrderQty":10000000,"maxPrice":1000000,"lotSize":1,"tickSize":0.5,"multiplier":-100000000,"settlCurrency":"XBt","underlyingToPositionMultiplier":null,"underlyingToSettleMultiplier":-100000000,"quoteToSettleMultiplier":null,"isQuanto":false,"isInverse":true,"initMargin":0.01,"maintMargin":0.005,"riskLimit":20000000000,"riskStep":10000000000,"limit":null,"capped":false,"taxed":true,"deleverage":true,"makerFee":-0.00025,"takerFee":0.00075,"settlementFee":0,"insuranceFee":0,"fundingBaseSymbol":".XBTBON8H","fundingQuoteSymbol":".USDBON8H","fundingPremiumSymbol":".XBTUSDPI8H","fundingTimestamp":"2019-01-01T04:00:00.000Z","fundingInterval":"2000-01-01T08:00:00.000Z","fundingRate":0.0001,"indicativeFundingRate":0.0001,"rebalanceTimestamp":null,"rebalanceInterval":null,"openingTimestamp":"2019-01-01T00:00:00.000Z","closingTimestamp":"2019-01-01T01:00:00.000Z","sessionInterval":"2000-01-01T01:00:00.000Z","prevClosePrice":3695.2,"limitDownPrice":null,"limitUpPrice":null,"bankruptLimitDownPrice":null,"bankruptLimitUpPrice":null,"prevTotalVolume":1234567890123,"totalVolume":1234567990123,"volume":100000000,"volume24h":2000000000,"prevTotalTurnover":17200000000000000,"totalTurnover":17200002700000000,"turnover":2700000000000,"turnover24h":54000000000000,"homeNotional24h":540000,"foreignNotional24h":2000000000,"prevPrice24h":3650,"vwap":3701.3,"highPrice":3750,"lowPrice":3600,"lastPrice":3700.5,"lastPriceProtected":3700.5,"lastTickDirection":"PlusTick","lastChangePcnt":0.0138,"bidPrice":3700,"midPrice":3700.25,"askPrice":3700.5,"impactBidPrice":3699.8,"impactMidPrice":3700.25,"impactAskPrice":3700.7,"hasLiquidity":true,"openInterest":100000000,"openValue":2700000000000,"fairMethod":"FundingRate","fairBasisRate":0.1095,"fairBasis":0.04,"fairPrice":3700.3,"markMethod":"FairPrice","markPrice":3700.3,"indicativeTaxRate":0,"indicativeSettlePrice":3700.26,"optionUnderlyingPrice":null,"settledPriceAdjustmentRate":null,"settledPrice":null,"timestamp":"2019-01-01T00:00:00.000Z"}"#;
+    const FUTURE: &str = r#"{"symbol":"XBTZ19","rootSymbol":"XBT","state":"Open","typ":"FFCCSX","listing":"2019-06-14T12:00:00.000Z","front":"2019-11-29T12:00:00.000Z","expiry":"2019-12-27T12:00:00.000Z","settle":"2019-12-27T12:00:00.000Z","relistInterval":null,"inverseLeg":"","sellLeg":"","buyLeg":"","optionStrikePcnt":null,"optionStrikeRound":null,"optionStrikePrice":null,"optionMultiplier":null,"positionCurrency":"USD","underlying":"XBT","quoteCurrency":"USD","underlyingSymbol":"XBT=","reference":"BMEX","referenceSymbol":".BXBT30M","calcInterval":null,"publishInterval":null,"publishTime":null,"maxOrderQty":10000000,"maxPrice":1000000,"lotSize":1,"tickSize":0.5,"multiplier":-100000000,"settlCurrency":"XBt","underlyingToPositionMultiplier":null,"underlyingToSettleMultiplier":-100000000,"quoteToSettleMultiplier":null,"isQuanto":false,"isInverse":true,"initMargin":0.01,"maintMargin":0.005,"riskLimit":20000000000,"riskStep":10000000000,"limit":null,"capped":false,"taxed":true,"deleverage":true,"makerFee":-0.00025,"takerFee":0.00075,"settlementFee":0.0005,"insuranceFee":0,"fundingBaseSymbol":"","fundingQuoteSymbol":"","fundingPremiumSymbol":"","fundingTimestamp":null,"fundingInterval":null,"fundingRate":null,"indicativeFundingRate":null,"rebalanceTimestamp":null,"rebalanceInterval":null,"openingTimestamp":"2019-11-01T00:00:00.000Z","closingTimestamp":"2019-11-01T01:00:00.000Z","sessionInterval":"2000-01-01T01:00:00.000Z","prevClosePrice":9180.5,"limitDownPrice":null,"limitUpPrice":null,"bankruptLimitDownPrice":null,"bankruptLimitUpPrice":null,"prevTotalVolume":3000000000,"totalVolume":3000100000,"volume":100000,"volume24h":80000000,"prevTotalTurnover":40000000000000,"totalTurnover":40001000000000,"turnover":1000000000,"turnover24h":870000000000,"homeNotional24h":8700,"foreignNotional24h":80000000,"prevPrice24h":9150,"vwap":9187.2,"highPrice":9250,"lowPrice":9100,"lastPrice":9190,"lastPriceProtected":9190,"lastTickDirection":"ZeroMinusTick","lastChangePcnt":0.0044,"bidPrice":9189.5,"midPrice":9189.75,"askPrice":9190,"impactBidPrice":9188.9,"impactMidPrice":9189.75,"impactAskPrice":9190.4,"hasLiquidity":true,"openInterest":120000000,"openValue":1300000000000,"fairMethod":"ImpactMidPrice","fairBasisRate":0.0663,"fairBasis":42.1,"fairPrice":9189.6,"markMethod":"FairPrice","markPrice":9189.6,"indicativeTaxRate":0,"indicativeSettlePrice":9147.5,"optionUnderlyingPrice":null,"settledPriceAdjustmentRate":null,"settledPrice":null,"timestamp":"2019-11-01T00:00:00.000Z"}"#;
+    const INDEX: &str = r#"{"symbol":".BXBT","rootSymbol":"XBT","state":"Unlisted","typ":"MRCXXX","listing":null,"front":null,"expiry":null,"settle":null,"relistInterval":null,"inverseLeg":"","sellLeg":"","buyLeg":"","optionStrikePcnt":null,"optionStrikeRound":null,"optionStrikePrice":null,"optionMultiplier":null,"positionCurrency":"","underlying":"XBT","quoteCurrency":"USD","underlyingSymbol":"XBT=","reference":"BMI","referenceSymbol":".BXBT","calcInterval":null,"publishInterval":"2000-01-01T00:01:00.000Z","publishTime":null,"maxOrderQty":null,"maxPrice":null,"lotSize":null,"tickSize":0.01,"multiplier":null,"settlCurrency":"","underlyingToPositionMultiplier":null,"underlyingToSettleMultiplier":null,"quoteToSettleMultiplier":null,"isQuanto":false,"isInverse":false,"initMargin":null,"maintMargin":null,"riskLimit":null,"riskStep":null,"limit":null,"capped":false,"taxed":false,"deleverage":false,"makerFee":null,"takerFee":null,"settlementFee":null,"insuranceFee":null,"fundingBaseSymbol":"","fundingQuoteSymbol":"","fundingPremiumSymbol":"","fundingTimestamp":null,"fundingInterval":null,"fundingRate":null,"indicativeFundingRate":null,"rebalanceTimestamp":null,"rebalanceInterval":null,"openingTimestamp":null,"closingTimestamp":null,"sessionInterval":null,"prevClosePrice":3695.34,"limitDownPrice":null,"limitUpPrice":null,"bankruptLimitDownPrice":null,"bankruptLimitUpPrice":null,"prevTotalVolume":null,"totalVolume":null,"volume":null,"volume24h":null,"prevTotalTurnover":null,"totalTurnover":null,"turnover":null,"turnover24h":null,"homeNotional24h":null,"foreignNotional24h":null,"prevPrice24h":3649.81,"vwap":null,"highPrice":null,"lowPrice":null,"lastPrice":3700.26,"lastPriceProtected":null,"lastTickDirection":"MinusTick","lastChangePcnt":0.0138,"bidPrice":null,"midPrice":null,"askPrice":null,"impactBidPrice":null,"impactMidPrice":null,"impactAskPrice":null,"hasLiquidity":false,"openInterest":null,"openValue":null,"fairMethod":"","fairBasisRate":null,"fairBasis":null,"fairPrice":null,"markMethod":"LastPrice","markPrice":3700.26,"indicativeTaxRate":null,"indicativeSettlePrice":null,"optionUnderlyingPrice":null,"settledPriceAdjustmentRate":null,"settledPrice":null,"timestamp":"2019-01-01T00:00:00.000Z"}"#;
+
+    #[test]
+    fn test_parse_instruments() {
+        let perpetual: GetInstrumentResponse = from_str(PERPETUAL).unwrap();
+        assert_eq!((perpetual.funding_rate, perpetual.expiry), (Some(0.0001), None));
+        assert_eq!(perpetual.total_turnover, Some(17_200_002_700_000_000.));
+
+        let future: GetInstrumentResponse = from_str(FUTURE).unwrap();
+        assert_eq!((future.funding_rate, future.indicative_settle_price), (None, Some(9147.5)));
+        assert_eq!(future.expiry.as_deref(), Some("2019-12-27T12:00:00.000Z"));
+
+        let index: GetInstrumentResponse = from_str(INDEX).unwrap();
+        assert_eq!((index.lot_size, index.multiplier, index.volume), (None, None, None));
+        assert_eq!((index.mark_price, index.tick_size), (Some(3700.26), Some(0.01)));
+
+        // Fields left out entirely are read as null too
+        let bare: GetInstrumentResponse = from_str(r#"{"symbol":".BXBT","timestamp":"2019-01-01T00:00:00.000Z"}"#).unwrap();
+        assert_eq!((bare.mark_price, bare.open_interest), (None, None));
+    }
 }
@@ -1,5 +1,5 @@
 use super::GeneralRequest;
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_derive::Deserialize;
 
 pub type GetInsuranceRequest = GeneralRequest;
@@ -8,6 +8,6 @@ pub type GetInsuranceRequest = GeneralRequest;
 #[serde(rename_all = "camelCase")]
 pub struct GetInsuranceResponse {
     pub currency: String,
-    pub timestamp: DateTime<Utc>,
+    pub timestamp: BitMEXDateTime,
     pub wallet_balance: f64,
 }
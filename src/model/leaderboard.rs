@@ -15,6 +15,7 @@ impl Default for RankingType {
 }
 
 #[derive(Clone, Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetLeaderboardRequest {
     pub method: RankingType,
 }
@@ -22,12 +23,34 @@ pub struct GetLeaderboardRequest {
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetLeaderboardResponse {
+    /// Empty or a pseudonym for users that opted for anonymity
     pub name: String,
-    pub is_real_name: bool,
-    pub profit: f64,
+    pub is_real_name: Option<bool>,
+    pub profit: Option<f64>,
+    /// Only present when ranking by ROE
+    pub pnl: Option<f64>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetLeaderboardNameResponse {
     pub name: String,
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::from_str;
+
+    use super::GetLeaderboardResponse;
+
+    #[test]
+    fn test_parse_leaderboard() {
+        let rows: Vec<GetLeaderboardResponse> =
+            from_str(r#"[{"name":"Raging-Meadow-Sun","isRealName":false,"profit":1342178529},{"name":"","profit":12.5},{"name":"Quiet-Fog","pnl":0.42}]"#).unwrap();
+        assert_eq!(rows[0].is_real_name, Some(false));
+        assert_eq!(rows[1].is_real_name, None);
+        assert_eq!(rows[1].profit, Some(12.5));
+        assert_eq!(rows[2].pnl, Some(0.42));
+        assert_eq!(rows[2].profit, None);
+    }
+}
@@ -1,51 +1,78 @@
 use serde_derive::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_json::Value;
 use uuid::Uuid;
 
+use super::public::{deserialize_empty_as_none, deserialize_exec_inst, serialize_exec_inst};
 use super::GeneralRequest;
-pub use super::{BinSize, ContingencyType, ExecInst, OrdType, PegPriceType, Side, TimeInForce, Vararg};
+use crate::error::{BitMEXError, Result};
+use crate::util::{is_tick_multiple, round_to_tick};
+pub use super::{BinSize, ContingencyType, ExecInst, OrdStatus, OrdType, PegPriceType, Side, TimeInForce, Vararg};
 
 
+/// An order as BitMEX reports it. Quantities are in contracts. Fields BitMEX leaves empty or omits
+/// are defaulted; websocket updates only carry the changed fields, merge them into the full row
+/// first, e.g. with a `TableCache`.
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GeneralOrderResponse {
     #[serde(rename = "orderID")]
     pub order_id: Uuid,
-    #[serde(rename = "clOrdID")]
+    #[serde(rename = "clOrdID", default)]
     pub cl_ord_id: String,
-    #[serde(rename = "clOrdLinkID")]
+    #[serde(rename = "clOrdLinkID", default)]
     pub cl_ord_link_id: String,
-    pub account: u64,
+    pub account: i64,
     pub symbol: String,
     pub side: Side,
+    #[serde(default)]
     pub simple_order_qty: Option<f64>,
-    pub order_qty: f64,
+    pub order_qty: i64,
+    #[serde(default)]
     pub price: Option<f64>,
-    pub display_qty: Option<f64>,
+    #[serde(default)]
+    pub display_qty: Option<i64>,
+    #[serde(default)]
     pub stop_px: Option<f64>,
+    #[serde(default)]
     pub peg_offset_value: Option<f64>,
-    pub peg_price_type: String,
+    #[serde(default, deserialize_with = "deserialize_empty_as_none")]
+    pub peg_price_type: Option<PegPriceType>,
+    #[serde(default)]
     pub currency: String,
+    #[serde(default)]
     pub settl_currency: String,
     pub ord_type: OrdType,
     pub time_in_force: TimeInForce,
-    pub exec_inst: String,
-    pub contingency_type: String,
+    #[serde(default, deserialize_with = "deserialize_exec_inst")]
+    pub exec_inst: Vec<ExecInst>,
+    #[serde(default, deserialize_with = "deserialize_empty_as_none")]
+    pub contingency_type: Option<ContingencyType>,
+    #[serde(default)]
     pub ex_destination: String,
-    pub ord_status: String,
+    pub ord_status: OrdStatus,
+    #[serde(default)]
     pub triggered: String,
+    #[serde(default)]
     pub working_indicator: bool,
+    #[serde(default)]
     pub ord_rej_reason: String,
+    #[serde(default)]
     pub simple_leaves_qty: Option<f64>,
-    pub leaves_qty: f64,
+    #[serde(default)]
+    pub leaves_qty: i64,
+    #[serde(default)]
     pub simple_cum_qty: Option<f64>,
-    pub cum_qty: f64,
+    #[serde(default)]
+    pub cum_qty: i64,
+    #[serde(default)]
     pub avg_px: Option<f64>,
+    #[serde(default)]
     pub multi_leg_reporting_type: String,
+    #[serde(default)]
     pub text: String,
-    pub transact_time: DateTime<Utc>,
-    pub timestamp: DateTime<Utc>,
+    pub transact_time: BitMEXDateTime,
+    pub timestamp: BitMEXDateTime,
 }
 
 pub type GetOrderRequest = GeneralRequest;
@@ -55,19 +82,27 @@ pub type GetOrderResponse = GeneralOrderResponse;
 #[serde(rename_all = "camelCase")]
 pub struct PutOrderRequest {
     pub symbol: String,
-    #[serde(rename = "orderID")]
+    #[serde(rename = "orderID", skip_serializing_if = "Option::is_none")]
     pub order_id: Option<Uuid>,
-    #[serde(rename = "clOrdID")]
+    #[serde(rename = "clOrdID", skip_serializing_if = "Option::is_none")]
     pub cl_ord_id: Option<String>,
-    #[serde(rename = "origClOrdID")]
+    #[serde(rename = "origClOrdID", skip_serializing_if = "Option::is_none")]
     pub orig_cl_ord_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub simple_order_qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub order_qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub simple_leaves_qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub leaves_qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_px: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub peg_offset_value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
 }
 
@@ -77,57 +112,209 @@ pub type PutOrderResponse = GeneralOrderResponse;
 #[serde(rename_all = "camelCase")]
 pub struct PostOrderRequest {
     pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub side: Option<Side>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub simple_order_qty: Option<f64>,
+    /// Without a `side`, BitMEX buys a positive and sells a negative quantity. With a `side` the
+    /// quantity has to be positive, see `PostOrderRequest::new`.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub order_qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub display_qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_px: Option<f64>,
-    #[serde(rename = "clOrdID")]
+    #[serde(rename = "clOrdID", skip_serializing_if = "Option::is_none")]
     pub cl_ord_id: Option<String>,
-    #[serde(rename = "clOrdLinkID")]
+    #[serde(rename = "clOrdLinkID", skip_serializing_if = "Option::is_none")]
     pub cl_ord_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub peg_offset_value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub peg_price_type: Option<PegPriceType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ord_type: Option<OrdType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub time_in_force: Option<TimeInForce>,
+    #[serde(serialize_with = "serialize_exec_inst", skip_serializing_if = "Option::is_none")]
     pub exec_inst: Option<Vec<ExecInst>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub contingency_type: Option<ContingencyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
 }
 
+impl PostOrderRequest {
+    /// An order for `order_qty` contracts, sent as `side` with a positive `orderQty`. Set the price,
+    /// type and the other fields on the returned request.
+    pub fn new(symbol: &str, side: Side, order_qty: u64) -> Self {
+        PostOrderRequest {
+            symbol: symbol.into(),
+            side: Some(side),
+            order_qty: Some(order_qty as f64),
+            ..Default::default()
+        }
+    }
+
+    /// Rounds `price`, `stop_px` and `peg_offset_value` to exact multiples of `tick_size`, the
+    /// instrument's `tickSize`, so that float error like `219.00000000001` doesn't get the order
+    /// rejected. Fails with `BitMEXError::OffTick` for a price that is between two ticks.
+    pub fn with_tick_size(mut self, tick_size: f64) -> Result<Self> {
+        for price in [&mut self.price, &mut self.stop_px, &mut self.peg_offset_value].iter_mut().filter_map(|price| price.as_mut()) {
+            if !is_tick_multiple(*price, tick_size) {
+                return Err(BitMEXError::OffTick { price: *price, tick_size });
+            }
+            *price = round_to_tick(*price, tick_size);
+        }
+        Ok(self)
+    }
+
+    /// Fills in a random `clOrdID` unless one is already set. Resubmitting the returned request
+    /// reuses the id, so BitMEX rejects a duplicate submission instead of placing the order twice.
+    pub fn with_generated_cl_ord_id(mut self) -> Self {
+        if self.cl_ord_id.is_none() {
+            self.cl_ord_id = Some(Uuid::new_v4().to_string());
+        }
+        self
+    }
+
+    /// Like `with_generated_cl_ord_id`, with the id prefixed by `namespace`, e.g. `my_bot/`, to tell
+    /// the orders of several bots on one account apart. The id is a v4 UUID without hyphens, cut
+    /// so that the `clOrdID` fits BitMEX's 36 characters. Fails with `BitMEXError::InvalidConfig`
+    /// for a namespace over 20 characters, which would leave too little of the UUID to be unique.
+    pub fn with_auto_cl_ord_id(mut self, namespace: &str) -> Result<Self> {
+        check_cl_ord_id_namespace(namespace)?;
+        if self.cl_ord_id.is_none() {
+            let mut id = format!("{}{}", namespace, Uuid::new_v4().to_simple());
+            if let Some((end, _)) = id.char_indices().nth(MAX_CL_ORD_ID_LEN) {
+                id.truncate(end);
+            }
+            self.cl_ord_id = Some(id);
+        }
+        Ok(self)
+    }
+}
+
+// The longest `clOrdID` BitMEX accepts
+const MAX_CL_ORD_ID_LEN: usize = 36;
+// The longest namespace of a generated `clOrdID`, leaving at least 16 random hex digits
+const MAX_CL_ORD_ID_NAMESPACE_LEN: usize = 20;
+
+pub(crate) fn check_cl_ord_id_namespace(namespace: &str) -> Result<()> {
+    if namespace.chars().count() > MAX_CL_ORD_ID_NAMESPACE_LEN {
+        return Err(BitMEXError::InvalidConfig(format!("clOrdID namespace {} is longer than {} characters", namespace, MAX_CL_ORD_ID_NAMESPACE_LEN)));
+    }
+    Ok(())
+}
+
 pub type PostOrderResponse = GeneralOrderResponse;
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteOrderRequest {
-    #[serde(rename = "orderID")]
+    #[serde(rename = "orderID", skip_serializing_if = "Option::is_none")]
     pub order_id: Option<Vararg<Uuid>>,
-    #[serde(rename = "clOrdID")]
+    #[serde(rename = "clOrdID", skip_serializing_if = "Option::is_none")]
     pub cl_ord_id: Option<Vararg<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
 }
 
+impl From<OrderId> for DeleteOrderRequest {
+    fn from(id: OrderId) -> Self {
+        match id {
+            OrderId::OrderID(id) => DeleteOrderRequest {
+                order_id: Some(Vararg::Single(id)),
+                ..Default::default()
+            },
+            OrderId::ClOrdID(id) => DeleteOrderRequest {
+                cl_ord_id: Some(Vararg::Single(id)),
+                ..Default::default()
+            },
+        }
+    }
+}
+
 pub type DeleteOrderResponse = GeneralOrderResponse;
 
+/// Cancels a single order, sent as JSON body of `DELETE /order`
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrderRequest {
+    #[serde(rename = "orderID", skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    #[serde(rename = "clOrdID", skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl From<OrderId> for CancelOrderRequest {
+    fn from(id: OrderId) -> Self {
+        match id {
+            OrderId::OrderID(id) => CancelOrderRequest {
+                order_id: Some(id.to_string()),
+                ..Default::default()
+            },
+            OrderId::ClOrdID(id) => CancelOrderRequest {
+                cl_ord_id: Some(id),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+pub type CancelOrderResponse = GeneralOrderResponse;
+
+/// Cancels every order matching `symbol` and `filter` (e.g. `{"side": "Buy"}`), sent as JSON body
+/// of `DELETE /order/all`
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrdersByFilterRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+pub type CancelOrdersByFilterResponse = GeneralOrderResponse;
+
+/// Identifies a single order either by the id BitMEX assigned or by the client supplied one
+#[derive(Clone, Debug)]
+pub enum OrderId {
+    OrderID(Uuid),
+    ClOrdID(String),
+}
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DeleteOrderAllRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub test: Option<String>,
 }
 
 pub type DeleteOrderAllResponse = GeneralOrderResponse;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PostOrderCancelAllAfterRequest {
     pub timeout: u64,
 }
 
 pub type PostOrderCancelAllAfterResponse = ();
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PostOrderClosePositionRequest {
     pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub price: Option<f64>,
 }
 
@@ -137,3 +324,123 @@ pub type PostOrderClosePositionResponse = GeneralOrderResponse;
 // // "(\w+)": 0  $1: f64
 // // "(\w+)": true  $1: bool
 // // "(\w+)": "2018-09-02T18:57:54.593Z"  $1: String
+
+#[cfg(test)]
+mod test {
+    use serde_json::{from_str, json, to_value};
+    use uuid::Uuid;
+
+    use super::{DeleteOrderRequest, ExecInst, GeneralOrderResponse, OrdStatus, OrderId, PegPriceType, PostOrderRequest, Side};
+    use crate::error::BitMEXError;
+
+    #[test]
+    fn test_parse_order() {
+        let order: GeneralOrderResponse = from_str(
+            r#"{"orderID":"9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e","clOrdID":"","clOrdLinkID":"","account":2,"symbol":"XBTUSD","side":"Sell","simpleOrderQty":null,"orderQty":100,"price":null,"displayQty":null,"stopPx":3500,"pegOffsetValue":-50,"pegPriceType":"TrailingStopPeg","currency":"USD","settlCurrency":"XBt","ordType":"Stop","timeInForce":"ImmediateOrCancel","execInst":"LastPrice,ReduceOnly","contingencyType":"","exDestination":"XBME","ordStatus":"New","triggered":"","workingIndicator":false,"ordRejReason":"","simpleLeavesQty":null,"leavesQty":100,"simpleCumQty":null,"cumQty":0,"avgPx":null,"multiLegReportingType":"SingleSecurity","text":"Submitted via API.","transactTime":"2019-01-01T00:00:00.000Z","timestamp":"2019-01-01T00:00:00.000Z"}"#,
+        )
+        .unwrap();
+        assert!(matches!(order.peg_price_type, Some(PegPriceType::TrailingStopPeg)));
+        assert!(matches!(order.exec_inst[..], [ExecInst::LastPrice, ExecInst::ReduceOnly]));
+        assert!(order.contingency_type.is_none());
+        assert_eq!((order.ord_status, order.leaves_qty, order.stop_px), (OrdStatus::New, 100, Some(3500.)));
+
+        // Trimmed by `columns`
+        let order: GeneralOrderResponse = from_str(
+            r#"{"orderID":"9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e","account":2,"symbol":"XBTUSD","side":"Buy","orderQty":1,"ordType":"Market","timeInForce":"ImmediateOrCancel","ordStatus":"Filled","transactTime":"2019-01-01T00:00:00.000Z","timestamp":"2019-01-01T00:00:00.000Z"}"#,
+        )
+        .unwrap();
+        assert!(order.exec_inst.is_empty() && order.peg_price_type.is_none());
+    }
+
+    #[test]
+    fn test_delete_order_request_from_order_id() {
+        let id = Uuid::parse_str("9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e").unwrap();
+        let req = DeleteOrderRequest::from(OrderId::OrderID(id));
+        assert_eq!(to_value(&req).unwrap(), json!({"orderID": "9f0c3b6e-1a5d-4d6a-8a8e-3f4f5b6c7d8e"}));
+
+        let req = DeleteOrderRequest::from(OrderId::ClOrdID("my-order".into()));
+        assert_eq!(to_value(&req).unwrap(), json!({"clOrdID": "my-order"}));
+    }
+
+    #[test]
+    fn test_exec_inst_is_comma_separated() {
+        let req = PostOrderRequest {
+            exec_inst: Some(vec![ExecInst::ParticipateDoNotInitiate, ExecInst::ReduceOnly]),
+            ..Default::default()
+        };
+        assert_eq!(to_value(&req).unwrap()["execInst"], json!("ParticipateDoNotInitiate,ReduceOnly"));
+        assert!(to_value(PostOrderRequest::default()).unwrap()["execInst"].is_null());
+    }
+
+    #[test]
+    fn test_new_post_order_request() {
+        let req = to_value(PostOrderRequest::new("XBTUSD", Side::Buy, 100)).unwrap();
+        assert_eq!((&req["side"], &req["orderQty"]), (&json!("Buy"), &json!(100.)));
+        let req = to_value(PostOrderRequest::new("XBTUSD", Side::Sell, 100)).unwrap();
+        assert_eq!((&req["side"], &req["orderQty"]), (&json!("Sell"), &json!(100.)));
+    }
+
+    #[test]
+    fn test_with_tick_size() {
+        let order = PostOrderRequest {
+            price: Some(2190. * 0.1),
+            stop_px: Some(218.99999999999),
+            ..PostOrderRequest::new("XBTUSD", Side::Buy, 100)
+        };
+        let order = order.with_tick_size(0.5).unwrap();
+        assert_eq!((order.price, order.stop_px, order.peg_offset_value), (Some(219.), Some(219.), None));
+        assert_eq!(to_value(&order).unwrap()["price"], json!(219.0));
+
+        let off_tick = PostOrderRequest {
+            price: Some(219.3),
+            ..order
+        };
+        match off_tick.with_tick_size(0.5) {
+            Err(BitMEXError::OffTick { price, tick_size }) => assert_eq!((price, tick_size), (219.3, 0.5)),
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unset_fields_are_omitted() {
+        let req = PostOrderRequest::new("XBTUSD", Side::Buy, 100);
+        assert_eq!(to_value(req).unwrap(), json!({"symbol": "XBTUSD", "side": "Buy", "orderQty": 100.}));
+        assert_eq!(to_value(PostOrderRequest::default()).unwrap(), json!({"symbol": ""}));
+    }
+
+    #[test]
+    fn test_generated_cl_ord_id() {
+        let req = PostOrderRequest::default().with_generated_cl_ord_id();
+        let id = req.cl_ord_id.clone().unwrap();
+        assert!(Uuid::parse_str(&id).is_ok());
+        assert_eq!(req.with_generated_cl_ord_id().cl_ord_id, Some(id));
+
+        let req = PostOrderRequest {
+            cl_ord_id: Some("my-order".into()),
+            ..Default::default()
+        };
+        assert_eq!(req.with_generated_cl_ord_id().cl_ord_id, Some("my-order".into()));
+    }
+
+    #[test]
+    fn test_auto_cl_ord_id() {
+        let id = PostOrderRequest::default().with_auto_cl_ord_id("my_bot/").unwrap().cl_ord_id.unwrap();
+        assert_eq!(id.len(), 36);
+        assert!(id.starts_with("my_bot/") && id[7..].chars().all(|c| c.is_ascii_hexdigit()));
+
+        let other = PostOrderRequest::default().with_auto_cl_ord_id("my_bot/").unwrap().cl_ord_id.unwrap();
+        assert_ne!(id, other);
+        assert_eq!(PostOrderRequest::default().with_auto_cl_ord_id("").unwrap().cl_ord_id.unwrap().len(), 32);
+        let req = PostOrderRequest {
+            cl_ord_id: Some("my-order".into()),
+            ..Default::default()
+        };
+        assert_eq!(req.clone().with_auto_cl_ord_id("my_bot/").unwrap().cl_ord_id, Some("my-order".into()));
+
+        let namespace = "a".repeat(20);
+        let id = PostOrderRequest::default().with_auto_cl_ord_id(&namespace).unwrap().cl_ord_id.unwrap();
+        assert_eq!(id.len(), 36);
+        assert!(matches!(PostOrderRequest::default().with_auto_cl_ord_id(&"a".repeat(21)), Err(BitMEXError::InvalidConfig(_))));
+        assert!(matches!(req.with_auto_cl_ord_id("my_very_long_bot_name/"), Err(BitMEXError::InvalidConfig(_))));
+    }
+}
@@ -3,8 +3,10 @@ use serde_derive::{Deserialize, Serialize};
 pub use super::Side;
 
 #[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetOrderBookL2Request {
     pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub depth: Option<u64>,
 }
 
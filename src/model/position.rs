@@ -1,12 +1,15 @@
-use std::collections::BTreeMap;
-
 use serde_derive::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
+use serde_json::Value;
 
-#[derive(Clone, Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+use super::{Columns, Satoshi};
+
+// Fields missing from the response (e.g. trimmed by `columns`) are defaulted. Quantities are in
+// contracts, monetary fields in satoshis; prices, ratios and notionals are floats.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct GeneralPositionResponse {
-    pub account: u64,
+    pub account: i64,
     pub symbol: String,
     pub currency: String,
     pub underlying: String,
@@ -14,73 +17,73 @@ pub struct GeneralPositionResponse {
     pub commission: f64,
     pub init_margin_req: f64,
     pub maint_margin_req: f64,
-    pub risk_limit: f64,
+    pub risk_limit: Satoshi,
     pub leverage: f64,
     pub cross_margin: bool,
     pub deleverage_percentile: Option<f64>,
-    pub rebalanced_pnl: f64,
-    pub prev_realised_pnl: f64,
-    pub prev_unrealised_pnl: f64,
+    pub rebalanced_pnl: Satoshi,
+    pub prev_realised_pnl: Satoshi,
+    pub prev_unrealised_pnl: Satoshi,
     pub prev_close_price: f64,
-    pub opening_timestamp: DateTime<Utc>,
-    pub opening_qty: f64,
-    pub opening_cost: f64,
-    pub opening_comm: f64,
-    pub open_order_buy_qty: f64,
-    pub open_order_buy_cost: f64,
-    pub open_order_buy_premium: f64,
-    pub open_order_sell_qty: f64,
-    pub open_order_sell_cost: f64,
-    pub open_order_sell_premium: f64,
-    pub exec_buy_qty: f64,
-    pub exec_buy_cost: f64,
-    pub exec_sell_qty: f64,
-    pub exec_sell_cost: f64,
-    pub exec_qty: f64,
-    pub exec_cost: f64,
-    pub exec_comm: f64,
-    pub current_timestamp: DateTime<Utc>,
-    pub current_qty: f64,
-    pub current_cost: f64,
-    pub current_comm: f64,
-    pub realised_cost: f64,
-    pub unrealised_cost: f64,
-    pub gross_open_cost: f64,
-    pub gross_open_premium: f64,
-    pub gross_exec_cost: f64,
+    pub opening_timestamp: BitMEXDateTime,
+    pub opening_qty: i64,
+    pub opening_cost: Satoshi,
+    pub opening_comm: Satoshi,
+    pub open_order_buy_qty: i64,
+    pub open_order_buy_cost: Satoshi,
+    pub open_order_buy_premium: Satoshi,
+    pub open_order_sell_qty: i64,
+    pub open_order_sell_cost: Satoshi,
+    pub open_order_sell_premium: Satoshi,
+    pub exec_buy_qty: i64,
+    pub exec_buy_cost: Satoshi,
+    pub exec_sell_qty: i64,
+    pub exec_sell_cost: Satoshi,
+    pub exec_qty: i64,
+    pub exec_cost: Satoshi,
+    pub exec_comm: Satoshi,
+    pub current_timestamp: BitMEXDateTime,
+    pub current_qty: i64,
+    pub current_cost: Satoshi,
+    pub current_comm: Satoshi,
+    pub realised_cost: Satoshi,
+    pub unrealised_cost: Satoshi,
+    pub gross_open_cost: Satoshi,
+    pub gross_open_premium: Satoshi,
+    pub gross_exec_cost: Satoshi,
     pub is_open: bool,
     pub mark_price: Option<f64>,
-    pub mark_value: f64,
-    pub risk_value: f64,
+    pub mark_value: Satoshi,
+    pub risk_value: Satoshi,
     pub home_notional: f64,
     pub foreign_notional: f64,
     pub pos_state: String,
-    pub pos_cost: f64,
-    pub pos_cost2: f64,
-    pub pos_cross: f64,
-    pub pos_init: f64,
-    pub pos_comm: f64,
-    pub pos_loss: f64,
-    pub pos_margin: f64,
-    pub pos_maint: f64,
-    pub pos_allowance: f64,
-    pub taxable_margin: f64,
-    pub init_margin: f64,
-    pub maint_margin: f64,
-    pub session_margin: f64,
-    pub target_excess_margin: f64,
-    pub var_margin: f64,
-    pub realised_gross_pnl: f64,
-    pub realised_tax: f64,
-    pub realised_pnl: f64,
-    pub unrealised_gross_pnl: f64,
-    pub long_bankrupt: f64,
-    pub short_bankrupt: f64,
-    pub tax_base: f64,
+    pub pos_cost: Satoshi,
+    pub pos_cost2: Satoshi,
+    pub pos_cross: Satoshi,
+    pub pos_init: Satoshi,
+    pub pos_comm: Satoshi,
+    pub pos_loss: Satoshi,
+    pub pos_margin: Satoshi,
+    pub pos_maint: Satoshi,
+    pub pos_allowance: Satoshi,
+    pub taxable_margin: Satoshi,
+    pub init_margin: Satoshi,
+    pub maint_margin: Satoshi,
+    pub session_margin: Satoshi,
+    pub target_excess_margin: Satoshi,
+    pub var_margin: Satoshi,
+    pub realised_gross_pnl: Satoshi,
+    pub realised_tax: Satoshi,
+    pub realised_pnl: Satoshi,
+    pub unrealised_gross_pnl: Satoshi,
+    pub long_bankrupt: Satoshi,
+    pub short_bankrupt: Satoshi,
+    pub tax_base: Satoshi,
     pub indicative_tax_rate: f64,
-    pub indicative_tax: f64,
-    pub unrealised_tax: f64,
-    pub unrealised_pnl: f64,
+    pub indicative_tax: Satoshi,
+    pub unrealised_tax: Satoshi,
+    pub unrealised_pnl: Satoshi,
     pub unrealised_pnl_pcnt: f64,
     pub unrealised_roe_pcnt: f64,
     pub simple_qty: Option<f64>,
@@ -94,21 +97,57 @@ pub struct GeneralPositionResponse {
     pub margin_call_price: Option<f64>,
     pub liquidation_price: Option<f64>,
     pub bankrupt_price: Option<f64>,
-    pub timestamp: DateTime<Utc>,
+    pub timestamp: BitMEXDateTime,
     pub last_price: Option<f64>,
-    pub last_value: f64,
+    pub last_value: Satoshi,
+}
+
+impl GeneralPositionResponse {
+    pub fn is_long(&self) -> bool {
+        self.current_qty > 0
+    }
+
+    pub fn is_short(&self) -> bool {
+        self.current_qty < 0
+    }
+
+    /// How far the mark price may move against the position before it is liquidated, in percent of
+    /// the mark price. `None` for a flat position or without a mark or liquidation price.
+    pub fn liquidation_distance_pct(&self) -> Option<f64> {
+        let (mark, liquidation) = (self.mark_price?, self.liquidation_price?);
+        let distance = match self.current_qty {
+            0 => return None,
+            qty if qty > 0 => mark - liquidation,
+            _ => liquidation - mark,
+        };
+        Some(distance / mark * 100.)
+    }
+
+    /// The unrealised PnL as a fraction of the initial margin, e.g. `0.25` for 25%. Unlike
+    /// `unrealised_roe_pcnt` not rounded by BitMEX. `None` for a flat position.
+    pub fn unrealised_roe(&self) -> Option<f64> {
+        if self.current_qty == 0 || self.pos_init.0 == 0 {
+            return None;
+        }
+        Some(self.unrealised_pnl.0 as f64 / self.pos_init.0 as f64)
+    }
 }
 
 #[derive(Clone, Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetPositionRequest {
-    pub filter: Option<BTreeMap<String, String>>,
-    pub columns: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Columns>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub count: Option<u64>,
 }
 
 pub type GetPositionResponse = GeneralPositionResponse;
 
 #[derive(Clone, Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PostPositionIsolateRequest {
     pub symbol: String,
     pub enabled: bool,
@@ -117,6 +156,7 @@ pub struct PostPositionIsolateRequest {
 pub type PostPositionIsolateResponse = GeneralPositionResponse;
 
 #[derive(Clone, Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PostPositionLeverageRequest {
     pub symbol: String,
     pub leverage: f64,
@@ -128,15 +168,16 @@ pub type PostPositionLeverageResponse = GeneralPositionResponse;
 #[serde(rename_all = "camelCase")]
 pub struct PostPositionRiskLimitRequest {
     pub symbol: String,
-    pub risk_limit: f64,
+    pub risk_limit: i64, // in satoshis
 }
 
 pub type PostPositionRiskLimitResponse = GeneralPositionResponse;
 
 #[derive(Clone, Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PostPositionTransferMarginRequest {
     pub symbol: String,
-    pub amount: f64,
+    pub amount: i64, // in satoshis, negative to withdraw margin from an isolated position
 }
 
 pub type PostPositionTransferMarginResponse = GeneralPositionResponse;
@@ -145,3 +186,45 @@ pub type PostPositionTransferMarginResponse = GeneralPositionResponse;
 // // "(\w+)": 0  $1: f64
 // // "(\w+)": true  $1: bool
 // // "(\w+)": "2018-09-02T18:57:54.593Z"  $1: String
+
+#[cfg(test)]
+mod test {
+    use super::GeneralPositionResponse;
+    use crate::model::Satoshi;
+
+    // XBTUSD at 10x, entered at 8000 and marked at 8200
+    fn position(current_qty: i64, liquidation_price: f64, unrealised_pnl: i64, pos_init: i64) -> GeneralPositionResponse {
+        GeneralPositionResponse {
+            symbol: "XBTUSD".into(),
+            leverage: 10.,
+            current_qty,
+            avg_entry_price: Some(8000.),
+            mark_price: Some(8200.),
+            liquidation_price: Some(liquidation_price),
+            unrealised_pnl: Satoshi(unrealised_pnl),
+            pos_init: Satoshi(pos_init),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_risk_metrics() {
+        let long = position(1000, 7300., 304_878, 1_250_000);
+        assert!(long.is_long() && !long.is_short());
+        assert!((long.liquidation_distance_pct().unwrap() - 10.9756).abs() < 1e-4);
+        assert!((long.unrealised_roe().unwrap() - 0.2439).abs() < 1e-4);
+
+        let short = position(-500, 8800., -152_439, 625_000);
+        assert!(short.is_short() && !short.is_long());
+        assert!((short.liquidation_distance_pct().unwrap() - 7.3171).abs() < 1e-4);
+        assert!((short.unrealised_roe().unwrap() + 0.2439).abs() < 1e-4);
+
+        let flat = position(0, 0., 0, 0);
+        assert!(!flat.is_long() && !flat.is_short());
+        assert_eq!(flat.liquidation_distance_pct(), None);
+        assert_eq!(flat.unrealised_roe(), None);
+
+        let unmarked = GeneralPositionResponse { mark_price: None, ..long };
+        assert_eq!(unmarked.liquidation_distance_pct(), None);
+    }
+}
@@ -1,29 +1,102 @@
 
-use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
 
 use chrono::{DateTime, Utc};
+use serde::de::{self, Deserialize as _, Deserializer, IntoDeserializer};
+use serde::Serializer;
 use serde_derive::{Deserialize, Serialize};
+use serde_json::{to_value, Value};
+use thiserror::Error;
 
+use crate::transport::ToUrlQuery;
 
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GeneralRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol: Option<String>,
-    pub filter: Option<BTreeMap<String, String>>,
-    pub columns: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Columns>,
     pub count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub reverse: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub end_time: Option<DateTime<Utc>>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+/// The paging parameters shared by the GET endpoints, unset ones are left to BitMEX
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    pub count: Option<u64>,
+    pub start: Option<u64>,
+    pub reverse: Option<bool>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl Pagination {
+    pub fn to_query(&self) -> Vec<(String, String)> {
+        self.to_url_query()
+    }
+}
+
+impl GeneralRequest {
+    /// Overrides the paging fields set in `pagination`
+    pub fn with_pagination(mut self, pagination: Pagination) -> Self {
+        if let Some(count) = pagination.count {
+            self.count = count;
+        }
+        self.start = pagination.start.or(self.start);
+        self.reverse = pagination.reverse.or(self.reverse);
+        self.start_time = pagination.start_time.or(self.start_time);
+        self.end_time = pagination.end_time.or(self.end_time);
+        self
+    }
+}
+
+/// The fields a GET request returns, e.g. `Columns::of(["symbol", "lastPrice"])`. Sent as a JSON
+/// array (`["symbol","lastPrice"]`). Only instrument and position responses tolerate every field
+/// being trimmed, other responses still need their non optional fields selected.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Columns(pub Vec<&'static str>);
+
+impl Columns {
+    pub fn of(names: impl IntoIterator<Item = &'static str>) -> Self {
+        Columns(names.into_iter().collect())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Side {
     Buy,
     Sell,
     #[serde(rename = "")]
     Empty, // BitMEX sometimes has empty side due to unknown reason
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+/// How a trade price compares to the previous trade, a `Zero*` tick repeats the price of the last
+/// trade that moved it
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum TickDirection {
+    PlusTick,
+    ZeroPlusTick,
+    MinusTick,
+    ZeroMinusTick,
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -44,16 +117,43 @@ impl Default for BinSize {
     }
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+/// An amount in satoshis (`XBt`), the unit BitMEX reports XBT wallet, margin and position values in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Satoshi(pub i64);
+
+impl Satoshi {
+    pub const PER_BTC: i64 = 100_000_000;
+
+    pub fn to_btc(self) -> f64 {
+        self.0 as f64 / Self::PER_BTC as f64
+    }
+
+    /// Rounds to the nearest satoshi
+    pub fn from_btc(btc: f64) -> Self {
+        Satoshi((btc * Self::PER_BTC as f64).round() as i64)
+    }
+}
+
+impl From<i64> for Satoshi {
+    fn from(satoshis: i64) -> Self {
+        Satoshi(satoshis)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum PegPriceType {
     LastPeg,
     MidPricePeg,
     MarketPeg,
     PrimaryPeg,
     TrailingStopPeg,
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum OrdType {
     Market,
     Limit,
@@ -63,17 +163,44 @@ pub enum OrdType {
     LimitIfTouched,
     MarketWithLeftOverAsLimit,
     Pegged,
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OrdStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    DoneForDay,
+    Canceled,
+    PendingCancel,
+    Stopped,
+    Rejected,
+    Suspended,
+    PendingNew,
+    Calculated,
+    Expired,
+    AcceptedForBidding,
+    PendingReplace,
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum TimeInForce {
     Day,
     GoodTillCancel,
     ImmediateOrCancel,
     FillOrKill,
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum ExecInst {
     ParticipateDoNotInitiate,
     AllOrNone,
@@ -83,14 +210,121 @@ pub enum ExecInst {
     Close,
     ReduceOnly,
     Fixed,
+    LastWithinMark,
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+impl ExecInst {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ExecInst::ParticipateDoNotInitiate => "ParticipateDoNotInitiate",
+            ExecInst::AllOrNone => "AllOrNone",
+            ExecInst::MarkPrice => "MarkPrice",
+            ExecInst::IndexPrice => "IndexPrice",
+            ExecInst::LastPrice => "LastPrice",
+            ExecInst::Close => "Close",
+            ExecInst::ReduceOnly => "ReduceOnly",
+            ExecInst::Fixed => "Fixed",
+            ExecInst::LastWithinMark => "LastWithinMark",
+            ExecInst::Unknown(inst) => inst,
+        }
+    }
+}
+
+// BitMEX takes the instructions as one comma separated string, e.g. "ParticipateDoNotInitiate,ReduceOnly"
+pub(crate) fn serialize_exec_inst<S: Serializer>(insts: &Option<Vec<ExecInst>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match insts {
+        Some(insts) => serializer.serialize_str(&insts.iter().map(|inst| inst.as_str()).collect::<Vec<_>>().join(",")),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub(crate) fn deserialize_exec_inst<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<ExecInst>, D::Error> {
+    let insts = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+    insts
+        .split(',')
+        .filter(|inst| !inst.is_empty())
+        .map(|inst| ExecInst::deserialize(IntoDeserializer::<D::Error>::into_deserializer(inst)))
+        .collect()
+}
+
+// BitMEX sends "" rather than null for unset enum fields, e.g. `pegPriceType` of a limit order
+pub(crate) fn deserialize_empty_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) if !value.is_empty() => T::deserialize(IntoDeserializer::<D::Error>::into_deserializer(value)).map(Some),
+        _ => Ok(None),
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum ContingencyType {
     OneCancelsTheOther,
     OneTriggersTheOther,
     OneUpdatesTheOtherAbsolute,
     OneUpdatesTheOtherProportional,
+    /// A value added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+// Displays the enums as the strings BitMEX uses, e.g. `1m` for `BinSize::M1`
+macro_rules! display_as_serialized {
+    ($($ty:ty),*) => {$(
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match to_value(self) {
+                    Ok(Value::String(s)) => f.write_str(&s),
+                    _ => Err(fmt::Error),
+                }
+            }
+        }
+    )*};
+}
+
+display_as_serialized!(Side, TickDirection, BinSize, PegPriceType, OrdType, OrdStatus, TimeInForce, ExecInst, ContingencyType);
+
+/// A string that names none of the variants of an enum, e.g. `"Limitt"` for `OrdType`
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("Unknown variant: {0:?}")]
+pub struct UnknownVariantError(pub String);
+
+// Parses the strings BitMEX uses, like deserializing them would. Unlike serde, strings that no
+// variant matches fail rather than becoming `Unknown`.
+macro_rules! try_from_str {
+    ($($ty:ty),*) => {$(
+        impl TryFrom<&str> for $ty {
+            type Error = UnknownVariantError;
+
+            fn try_from(s: &str) -> Result<Self, UnknownVariantError> {
+                match Self::deserialize(IntoDeserializer::<de::value::Error>::into_deserializer(s)) {
+                    Ok(Self::Unknown(_)) | Err(_) => Err(UnknownVariantError(s.to_string())),
+                    Ok(value) => Ok(value),
+                }
+            }
+        }
+    )*};
+}
+
+try_from_str!(Side, TickDirection, PegPriceType, OrdType, OrdStatus, TimeInForce, ExecInst, ContingencyType);
+
+impl TryFrom<&str> for BinSize {
+    type Error = UnknownVariantError;
+
+    fn try_from(s: &str) -> Result<Self, UnknownVariantError> {
+        match s {
+            "1m" => Ok(BinSize::M1),
+            "5m" => Ok(BinSize::M5),
+            "1h" => Ok(BinSize::H1),
+            "1d" => Ok(BinSize::D1),
+            _ => Err(UnknownVariantError(s.to_string())),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -99,3 +333,91 @@ pub enum Vararg<T> {
     Single(T),
     Multiple(Vec<T>),
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use serde_json::{from_str, to_string};
+
+    use std::convert::TryFrom;
+
+    use super::{BinSize, ExecInst, GeneralRequest, OrdStatus, OrdType, Pagination, Satoshi, Side, TickDirection, TimeInForce, UnknownVariantError};
+
+    #[test]
+    fn test_pagination() {
+        let pagination = Pagination {
+            count: Some(10),
+            reverse: Some(true),
+            start_time: Some(Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        let query = pagination.to_query();
+        assert_eq!(query.len(), 3);
+        assert!(query.contains(&("count".to_string(), "10".to_string())));
+        assert!(query.contains(&("startTime".to_string(), "2019-01-01T00:00:00Z".to_string())));
+
+        let req = GeneralRequest {
+            symbol: Some("XBTUSD".to_string()),
+            count: 100,
+            start: Some(5),
+            reverse: None,
+            filter: None,
+            columns: None,
+            start_time: None,
+            end_time: None,
+        }
+        .with_pagination(pagination);
+        assert_eq!((req.count, req.start, req.reverse), (10, Some(5), Some(true)));
+    }
+
+    #[test]
+    fn test_satoshi() {
+        let amount: Satoshi = from_str("123456789").unwrap();
+        assert_eq!(amount, Satoshi(123456789));
+        assert_eq!(amount.to_btc(), 1.23456789);
+        assert_eq!(Satoshi::from_btc(0.1 + 0.2), Satoshi(30000000));
+        assert_eq!(to_string(&Satoshi::from_btc(-0.00000001)).unwrap(), "-1");
+    }
+
+    #[test]
+    fn test_unknown_enum_values() {
+        assert!(matches!(from_str(r#""TrailingLimit""#).unwrap(), OrdType::Unknown(ord_type) if ord_type == "TrailingLimit"));
+        assert_eq!(from_str::<OrdStatus>(r#""Parked""#).unwrap(), OrdStatus::Unknown("Parked".to_string()));
+        assert_eq!(from_str::<Side>(r#""Sideways""#).unwrap(), Side::Unknown("Sideways".to_string()));
+        assert!(matches!(from_str(r#""Buy""#).unwrap(), Side::Buy));
+        assert!(matches!(from_str(r#""FutureInst""#).unwrap(), ExecInst::Unknown(inst) if inst == "FutureInst"));
+        assert_eq!(from_str::<TickDirection>(r#""ZeroPlusTick""#).unwrap(), TickDirection::ZeroPlusTick);
+        assert_eq!(from_str::<TickDirection>(r#""NoTick""#).unwrap(), TickDirection::Unknown("NoTick".to_string()));
+
+        // The value is sent back as it came
+        assert_eq!(to_string(&OrdStatus::Unknown("Parked".to_string())).unwrap(), r#""Parked""#);
+        assert_eq!(ExecInst::Unknown("FutureInst".to_string()).to_string(), "FutureInst");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Side::Buy.to_string(), "Buy");
+        assert_eq!(Side::Empty.to_string(), "");
+        assert_eq!(OrdType::Limit.to_string(), "Limit");
+        assert_eq!(format!("{}", TimeInForce::GoodTillCancel), "GoodTillCancel");
+        assert_eq!(OrdStatus::PartiallyFilled.to_string(), "PartiallyFilled");
+        assert_eq!(BinSize::M5.to_string(), "5m");
+        assert_eq!(ExecInst::ReduceOnly.to_string(), "ReduceOnly");
+        assert_eq!(TickDirection::ZeroMinusTick.to_string(), "ZeroMinusTick");
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        assert!(matches!(OrdType::try_from("Limit"), Ok(OrdType::Limit)));
+        assert_eq!(Side::try_from("Sell"), Ok(Side::Sell));
+        assert_eq!(Side::try_from(""), Ok(Side::Empty));
+        assert_eq!(OrdStatus::try_from("Canceled"), Ok(OrdStatus::Canceled));
+        assert!(matches!(TimeInForce::try_from("FillOrKill"), Ok(TimeInForce::FillOrKill)));
+        assert!(matches!(BinSize::try_from("1h"), Ok(BinSize::H1)));
+
+        assert_eq!(OrdType::try_from("limit").unwrap_err(), UnknownVariantError("limit".to_string()));
+        assert!(OrdStatus::try_from("Unknown").is_err());
+        assert!(BinSize::try_from("2m").is_err());
+        assert_eq!(TickDirection::try_from("Up").unwrap_err().to_string(), r#"Unknown variant: "Up""#);
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use super::BitMEXDateTime;
 use serde_derive::Deserialize;
 
 use super::GeneralRequest;
@@ -8,7 +8,7 @@ pub type GetSettlementRequest = GeneralRequest;
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSettlementResponseItem {
-    timestamp: DateTime<Utc>,
+    timestamp: BitMEXDateTime,
     symbol: String,
     settlement_type: String,
     settled_price: f64,
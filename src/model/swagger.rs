@@ -1,4 +1,5 @@
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -16,3 +17,17 @@ pub struct SwaggerApiDescriptionInfo {
     pub terms_of_service: String,
     pub version: String,
 }
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSchemaRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Raw schema document, its shape depends on the model asked for
+#[derive(Deserialize, Debug, Clone)]
+pub struct SchemaResponse(pub Value);
+
+pub type GetSchemaResponse = SchemaResponse;
+pub type GetSchemaWebsocketHelpResponse = SchemaResponse;
@@ -1,9 +1,8 @@
-use std::collections::BTreeMap;
-
 use chrono::{DateTime, Utc};
 use serde_derive::Serialize;
+use serde_json::Value;
 
-use super::BinSize;
+use super::{BinSize, Columns};
 use super::{
     definitions::{Trade, TradeBin},
     GeneralRequest,
@@ -17,13 +16,20 @@ pub type GetTradeResponse = Vec<Trade>;
 pub struct GetTradeBucketedRequest {
     pub partial: bool,
     pub bin_size: BinSize,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub symbol: Option<String>,
-    pub filter: Option<BTreeMap<String, String>>,
-    pub columns: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Columns>,
     pub count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub reverse: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub start_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub end_time: Option<DateTime<Utc>>,
 }
 
@@ -1,5 +1,6 @@
 use super::definitions::{Transaction, User, UserCommission, Wallet};
-use chrono::{DateTime, Utc};
+use super::Satoshi;
+use super::BitMEXDateTime;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -8,6 +9,7 @@ pub type GetUserResponse = User;
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserWalletRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>, // default is XBt
 }
 
@@ -16,6 +18,7 @@ pub type GetUserWalletResponse = Wallet;
 #[derive(Clone, Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserWalletHistoryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>, // default is XBt
 }
 
@@ -24,10 +27,29 @@ pub type GetUserWalletHistoryResponse = Vec<Transaction>;
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserWalletSummaryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>, // default is XBt
 }
 
-pub type GetUserWalletSummaryResponse = Vec<Wallet>;
+/// A row of the wallet summary, one per transaction type plus a `Total` row. The swagger definition
+/// reuses `Wallet`, which has none of these fields.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletSummary {
+    pub account: Option<i64>,
+    pub currency: Option<String>,
+    pub transact_type: Option<String>,
+    pub symbol: Option<String>,
+    pub amount: Option<Satoshi>,
+    pub pending_credit: Option<Satoshi>,
+    pub pending_debit: Option<Satoshi>,
+    pub realised_pnl: Option<Satoshi>,
+    pub wallet_balance: Option<Satoshi>,
+    pub unrealised_pnl: Option<Satoshi>,
+    pub margin_balance: Option<Satoshi>,
+}
+
+pub type GetUserWalletSummaryResponse = Vec<WalletSummary>;
 
 pub type GetUserCommissionResponse = BTreeMap<String, UserCommission>;
 
@@ -39,25 +61,52 @@ pub struct GetUserDepositAddressRequest {
 
 pub type GetUserDepositAddressResponse = String;
 
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUserCheckReferralCodeRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referral_code: Option<String>,
+}
+
+/// The discount of the referral code
+pub type GetUserCheckReferralCodeResponse = f64;
+
 /// The struct from swagger is broken, use this one
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserAffiliateStatusResponse {
-    account: Option<i64>,
-    currency: Option<String>,
-    prev_payout: Option<i64>,
-    prev_turnover: Option<i64>,
-    prev_comm: Option<i64>,
-    prev_timestamp: Option<DateTime<Utc>>,
-    exec_turnover: Option<i64>,
-    exec_comm: Option<i64>,
-    total_referrals: Option<i64>,
-    total_turnover: Option<i64>,
-    total_comm: Option<i64>,
-    payout_pcnt: Option<f64>,
-    pending_payout: Option<i64>,
-    timestamp: Option<DateTime<Utc>>,
-    referrer_account: Option<f64>,
-    referral_discount: Option<f64>,
-    affiliate_payout: Option<f64>,
+    pub account: Option<i64>,
+    pub currency: Option<String>,
+    pub prev_payout: Option<i64>,
+    pub prev_turnover: Option<i64>,
+    pub prev_comm: Option<i64>,
+    pub prev_timestamp: Option<BitMEXDateTime>,
+    pub exec_turnover: Option<i64>,
+    pub exec_comm: Option<i64>,
+    pub total_referrals: Option<i64>,
+    pub total_turnover: Option<i64>,
+    pub total_comm: Option<i64>,
+    pub payout_pcnt: Option<f64>,
+    pub pending_payout: Option<i64>,
+    pub timestamp: Option<BitMEXDateTime>,
+    pub referrer_account: Option<f64>,
+    pub referral_discount: Option<f64>,
+    pub affiliate_payout: Option<f64>,
+    pub usd_value: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUserMinWithdrawalFeeRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>, // default is XBt
+}
+
+/// Fees in satoshis. `fee` is the current network fee, `min_fee` the least BitMEX accepts.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUserMinWithdrawalFeeResponse {
+    pub currency: String,
+    pub fee: Satoshi,
+    pub min_fee: Satoshi,
 }
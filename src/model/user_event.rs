@@ -1,21 +1,34 @@
-use super::definitions::UserEvent;
+pub use super::definitions::UserEvent;
 use serde_derive::{Serialize, Deserialize};
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserEventRequest {
-    count: i64,
-    start_id: i64,
-}
-
-impl Default for GetUserEventRequest {
-    fn default() -> Self {
-        Self { count: 150, start_id: 1 }
-    }
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>, // default is 150
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_id: Option<i64>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUserEventResponse {
-    user_events: Vec<UserEvent>,
+    pub user_events: Vec<UserEvent>,
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::from_str;
+
+    use super::GetUserEventResponse;
+
+    #[test]
+    fn test_parse_user_events() {
+        let resp: GetUserEventResponse = from_str(
+            r#"{"userEvents":[{"id":3172,"type":"apiKeyCreated","status":"success","userId":42,"createdById":42,"ip":"127.0.0.1","geoipCountry":"SG","geoipRegion":"01","geoipSubRegion":"","eventMeta":{"apiKeyID":"abc"},"created":"2019-01-01T00:00:00.000Z"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(resp.user_events[0].r#type, "apiKeyCreated");
+        assert_eq!(resp.user_events[0].created_by_id, Some(42));
+    }
 }
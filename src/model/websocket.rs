@@ -3,5 +3,5 @@ mod message;
 mod topic;
 
 pub use self::command::Command;
-pub use self::message::Message;
+pub use self::message::{Action, Message, TableMessage};
 pub use self::topic::Topic;
@@ -3,8 +3,8 @@ use url::Url;
 use serde_derive::{Deserialize, Serialize};
 
 use super::Topic;
-use crate::consts::WS_URL;
 use crate::error::Result;
+use crate::transport::TransportTrait;
 use crate::BitMEX;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,8 +20,8 @@ pub enum Command {
 }
 
 impl Command {
-    pub fn authenticate(bm: &BitMEX, expires: i64) -> Result<Command> {
-        let (key, sig) = bm.transport.signature(&Method::GET, expires, &Url::parse(&WS_URL)?, "")?;
+    pub fn authenticate<T: TransportTrait>(bm: &BitMEX<T>, expires: i64) -> Result<Command> {
+        let (key, sig) = bm.transport.signature(&Method::GET, expires, &Url::parse(bm.ws_url())?, "")?;
         Ok(Command::Authenticate(key.to_string(), expires, sig))
     }
 }
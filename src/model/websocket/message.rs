@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 
-use chrono::{DateTime, Utc};
-use serde_json::Value;
+use crate::model::BitMEXDateTime;
+use serde::de::{Deserialize, Deserializer, Error};
+use serde_json::{from_value, Value};
 use serde_derive::{Deserialize, Serialize};
 
-
 use super::Command;
+use crate::model::definitions::{OrderBookL2, Quote, Trade, TradeBin};
 
 // Text("{\"success\":true,\"subscribe\":\"chat\",\"request\":{\"args\":[\"chat\"],\"op\":\"subscribe\"}}")
 // Text("{\"table\":\"chat\",\"action\":\"insert\",\"keys\":[\"id\"],\"data\":[{\"channelID\":4,\"date\":\"2018-10-26T05:09:44.159Z\",\"fromBot\":false,\"html\":\"ㅋㅋㅋㅋㅋ ETF 드립 ㅈㄴ웃기네\\n\",\"id\":21699228,\"message\":\"ㅋㅋㅋㅋㅋ ETF 드립 ㅈㄴ웃기네\",\"user\":\"xixixiaqs\"}],\"filterKey\":\"channelID\"}")
@@ -13,18 +14,68 @@ use super::Command;
 // {"success":true,"unsubscribe":"chat","request":{"op":"unsubscribe","args":["chat"]}}
 // {"status":400,"error":"Failed to decode incoming data: Unexpected token a in JSON at position 0. Please see the documentation at https://www.bitmex.com/app/wsAPI.","meta":{}}
 
-#[derive(Clone, Debug, Deserialize)]
-#[serde(untagged)]
+#[derive(Clone, Debug)]
 pub enum Message {
     Success(SuccessMessage),
     Error(ErrorMessage),
+    /// Any table without a typed variant below
     Table(Box<TableMessage<Value>>),
+    Trade(Box<TableMessage<Trade>>),
+    Quote(Box<TableMessage<Quote>>),
+    OrderBookL2(Box<TableMessage<OrderBookL2>>), // orderBookL2 and orderBookL2_25
+    TradeBin(Box<TableMessage<TradeBin>>), // tradeBin1m, tradeBin5m, tradeBin1h and tradeBin1d
+    QuoteBin(Box<TableMessage<Quote>>),    // quoteBin1m, quoteBin5m, quoteBin1h and quoteBin1d
     Info(InfoMessage),
     CancelAllAfter(CancelAllAfterMessage),
     Pong,
+    /// Emitted by the client instead of a delta for a table that never received its `partial`,
+    /// resubscribe to the table to get a fresh image
+    SequenceGap(String),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UntypedMessage {
+    Success(SuccessMessage),
+    Error(ErrorMessage),
+    Table(Box<TableMessage<Value>>),
+    Info(InfoMessage),
+    CancelAllAfter(CancelAllAfterMessage),
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let table = value.get("table").and_then(Value::as_str).unwrap_or("");
+
+        let message = if table == "trade" {
+            Message::Trade(from_value(value).map_err(D::Error::custom)?)
+        } else if table == "quote" {
+            Message::Quote(from_value(value).map_err(D::Error::custom)?)
+        } else if table == "orderBookL2" || table == "orderBookL2_25" {
+            Message::OrderBookL2(from_value(value).map_err(D::Error::custom)?)
+        } else if table.starts_with("tradeBin") {
+            Message::TradeBin(from_value(value).map_err(D::Error::custom)?)
+        } else if table.starts_with("quoteBin") {
+            Message::QuoteBin(from_value(value).map_err(D::Error::custom)?)
+        } else {
+            match from_value(value).map_err(D::Error::custom)? {
+                UntypedMessage::Success(m) => Message::Success(m),
+                UntypedMessage::Error(m) => Message::Error(m),
+                UntypedMessage::Table(m) => Message::Table(m),
+                UntypedMessage::Info(m) => Message::Info(m),
+                UntypedMessage::CancelAllAfter(m) => Message::CancelAllAfter(m),
+            }
+        };
+        Ok(message)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SuccessMessage {
     pub success: bool,
     pub subscribe: Option<String>,
@@ -34,26 +85,29 @@ pub struct SuccessMessage {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CancelAllAfterMessage {
-    pub now: DateTime<Utc>,
-    pub cancel_time: DateTime<Utc>,
+    pub now: BitMEXDateTime,
+    pub cancel_time: BitMEXDateTime,
     pub request: Command,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct InfoMessage {
     pub info: String,
-    pub version: DateTime<Utc>,
-    pub timestamp: DateTime<Utc>,
+    pub version: BitMEXDateTime,
+    pub timestamp: BitMEXDateTime,
     pub docs: String,
     pub limit: Limit,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Limit {
     pub remaining: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ErrorMessage {
     pub status: i64,
     pub error: String,
@@ -83,6 +137,13 @@ pub struct TableFilter {
     symbol: Option<String>,
 }
 
+impl TableFilter {
+    /// The symbol a partial was filtered by, if any
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Action {
@@ -90,4 +151,98 @@ pub enum Action {
     Partial,
     Update,
     Delete,
+    /// An action added by BitMEX after this version of the crate
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::from_str;
+
+    use super::{Action, Message};
+
+    #[test]
+    fn test_parse_trade_bin() {
+        let partial = r#"{"table":"tradeBin1m","action":"partial","keys":[],"types":{"timestamp":"timestamp","symbol":"symbol","open":"float","high":"float","low":"float","close":"float","trades":"long","volume":"long","vwap":"float","lastSize":"long","turnover":"long","homeNotional":"float","foreignNotional":"float"},"foreignKeys":{"symbol":"instrument"},"attributes":{"timestamp":"sorted","symbol":"grouped"},"filter":{"symbol":"XBTUSD"},"data":[{"timestamp":"2018-10-26T05:10:00.000Z","symbol":"XBTUSD","open":6472,"high":6472.5,"low":6471.5,"close":6472,"trades":35,"volume":10453,"vwap":6472.1288,"lastSize":100,"turnover":161511779,"homeNotional":1.6151177900000002,"foreignNotional":10453}]}"#;
+        let insert = r#"{"table":"tradeBin1m","action":"insert","data":[{"timestamp":"2018-10-26T05:11:00.000Z","symbol":"XBTUSD","open":6472,"high":6472.5,"low":6472,"close":6472.5,"trades":12,"volume":1560,"vwap":6472.2982,"lastSize":25,"turnover":24102998,"homeNotional":0.24102998000000003,"foreignNotional":1560}]}"#;
+
+        match from_str(partial).unwrap() {
+            Message::TradeBin(msg) => {
+                assert!(matches!(msg.action, Action::Partial));
+                assert_eq!(msg.data[0].symbol, "XBTUSD");
+                assert_eq!(msg.data[0].volume, Some(10453));
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match from_str(insert).unwrap() {
+            Message::TradeBin(msg) => {
+                assert!(matches!(msg.action, Action::Insert));
+                assert_eq!(msg.data[0].close, Some(6472.5));
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_quote_bin() {
+        let partial = r#"{"table":"quoteBin1m","action":"partial","keys":[],"types":{"timestamp":"timestamp","symbol":"symbol","bidSize":"long","bidPrice":"float","askPrice":"float","askSize":"long"},"foreignKeys":{"symbol":"instrument"},"attributes":{"timestamp":"sorted","symbol":"grouped"},"filter":{"symbol":"XBTUSD"},"data":[{"timestamp":"2018-10-26T05:10:00.000Z","symbol":"XBTUSD","bidSize":594368,"bidPrice":6472,"askPrice":6472.5,"askSize":197577}]}"#;
+        let insert = r#"{"table":"quoteBin1m","action":"insert","data":[{"timestamp":"2018-10-26T05:11:00.000Z","symbol":"XBTUSD","bidSize":586797,"bidPrice":6472,"askPrice":6472.5,"askSize":222575}]}"#;
+
+        match from_str(partial).unwrap() {
+            Message::QuoteBin(msg) => {
+                assert!(matches!(msg.action, Action::Partial));
+                assert_eq!(msg.data[0].bid_size, Some(594368));
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match from_str(insert).unwrap() {
+            Message::QuoteBin(msg) => {
+                assert!(matches!(msg.action, Action::Insert));
+                assert_eq!(msg.data[0].ask_price, Some(6472.5));
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_untyped_table() {
+        let chat = r#"{"table":"chat","action":"insert","keys":["id"],"data":[{"channelID":4,"date":"2018-10-26T05:09:44.159Z","fromBot":false,"html":"hi\n","id":21699228,"message":"hi","user":"xixixiaqs"}],"filterKey":"channelID"}"#;
+        match from_str(chat).unwrap() {
+            Message::Table(msg) => assert_eq!(msg.table, "chat"),
+            other => panic!("Unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown_action() {
+        let chat = r#"{"table":"chat","action":"amend","data":[]}"#;
+        match from_str(chat).unwrap() {
+            Message::Table(msg) => assert!(matches!(msg.action, Action::Unknown(action) if action == "amend")),
+            other => panic!("Unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_typed_tables() {
+        let trade = r#"{"table":"trade","action":"insert","data":[{"timestamp":"2018-10-26T05:09:44.159Z","symbol":"XBTUSD","side":"Buy","size":100,"price":6472.5,"tickDirection":"PlusTick","trdMatchID":"6f9b5b8e-9a8b-6d7e-5c4b-3a2b1c0d9e8f","grossValue":1545000,"homeNotional":0.01545,"foreignNotional":100}]}"#;
+        let quote = r#"{"table":"quote","action":"insert","data":[{"timestamp":"2018-10-26T05:09:44.159Z","symbol":"XBTUSD","bidSize":100,"bidPrice":6472,"askPrice":6472.5,"askSize":200}]}"#;
+        let book = r#"{"table":"orderBookL2_25","action":"update","data":[{"symbol":"XBTUSD","id":8799352750,"side":"Sell","size":300}]}"#;
+
+        match from_str(trade).unwrap() {
+            Message::Trade(msg) => assert_eq!(msg.data[0].price, Some(6472.5)),
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match from_str(quote).unwrap() {
+            Message::Quote(msg) => assert_eq!(msg.data[0].ask_size, Some(200)),
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match from_str(book).unwrap() {
+            Message::OrderBookL2(msg) => {
+                assert!(matches!(msg.action, Action::Update));
+                assert_eq!((msg.data[0].id, msg.data[0].size, msg.data[0].price), (8799352750, Some(300), None));
+            }
+            other => panic!("Unexpected message {:?}", other),
+        }
+    }
 }
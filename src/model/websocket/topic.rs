@@ -15,16 +15,16 @@ pub enum Topic {
     OrderBook10,
     PublicNotifications,
     Quote,
-    QuoteBin1m,
-    QuoteBin5m,
-    QuoteBin1h,
-    QuoteBin1d,
+    QuoteBin1m(Option<String>), // Optional filter
+    QuoteBin5m(Option<String>), // Optional filter
+    QuoteBin1h(Option<String>), // Optional filter
+    QuoteBin1d(Option<String>), // Optional filter
     Settlement,
     Trade(Option<String>),
-    TradeBin1m,
-    TradeBin5m,
-    TradeBin1h,
-    TradeBin1d,
+    TradeBin1m(Option<String>), // Optional filter
+    TradeBin5m(Option<String>), // Optional filter
+    TradeBin1h(Option<String>), // Optional filter
+    TradeBin1d(Option<String>), // Optional filter
 
     // requires auth
     Affiliate,
@@ -37,10 +37,20 @@ pub enum Topic {
     Wallet,
 }
 
-impl Serialize for Topic {
-    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+impl Topic {
+    /// The subscription argument for this topic, qualified with `symbol` (`trade:XBTUSD`) if given.
+    /// The symbol takes the place of the topic's own filter.
+    pub fn with_symbol(&self, symbol: Option<&str>) -> String {
+        let repr = self.repr();
+        match symbol {
+            Some(symbol) => format!("{}:{}", repr.split(':').next().unwrap_or_default(), symbol),
+            None => repr,
+        }
+    }
+
+    fn repr(&self) -> String {
         use self::Topic::*;
-        let repr = match self {
+        match self {
             Announcement => "announcement".to_string(),
             Chat => "chat".to_string(),
             Connected => "connected".to_string(),
@@ -52,33 +62,45 @@ impl Serialize for Topic {
             OrderBookL2_25(None) => "orderBookL2_25".to_string(),
             OrderBookL2(Some(filter)) => format!("orderBookL2:{}", filter),
             OrderBookL2(None) => "orderBookL2".to_string(),
-            OrderBook10 => "announcement".to_string(),
+            OrderBook10 => "orderBook10".to_string(),
             PublicNotifications => "publicNotifications".to_string(),
             Quote => "quote".to_string(),
-            QuoteBin1m => "quoteBin1m".to_string(),
-            QuoteBin5m => "quoteBin5m".to_string(),
-            QuoteBin1h => "quoteBin1h".to_string(),
-            QuoteBin1d => "quoteBin1d".to_string(),
+            QuoteBin1m(Some(filter)) => format!("quoteBin1m:{}", filter),
+            QuoteBin1m(None) => "quoteBin1m".to_string(),
+            QuoteBin5m(Some(filter)) => format!("quoteBin5m:{}", filter),
+            QuoteBin5m(None) => "quoteBin5m".to_string(),
+            QuoteBin1h(Some(filter)) => format!("quoteBin1h:{}", filter),
+            QuoteBin1h(None) => "quoteBin1h".to_string(),
+            QuoteBin1d(Some(filter)) => format!("quoteBin1d:{}", filter),
+            QuoteBin1d(None) => "quoteBin1d".to_string(),
             Settlement => "settlement".to_string(),
             Trade(Some(filter)) => format!("trade:{}", filter),
             Trade(None) => "trade".to_string(),
-            TradeBin1m => "tradeBin1m".to_string(),
-            TradeBin5m => "tradeBin5m".to_string(),
-            TradeBin1h => "tradeBin1h".to_string(),
-            TradeBin1d => "tradeBin1d".to_string(),
+            TradeBin1m(Some(filter)) => format!("tradeBin1m:{}", filter),
+            TradeBin1m(None) => "tradeBin1m".to_string(),
+            TradeBin5m(Some(filter)) => format!("tradeBin5m:{}", filter),
+            TradeBin5m(None) => "tradeBin5m".to_string(),
+            TradeBin1h(Some(filter)) => format!("tradeBin1h:{}", filter),
+            TradeBin1h(None) => "tradeBin1h".to_string(),
+            TradeBin1d(Some(filter)) => format!("tradeBin1d:{}", filter),
+            TradeBin1d(None) => "tradeBin1d".to_string(),
 
             // requires auth
             Affiliate => "affiliate".to_string(),
             Execution => "execution".to_string(),
-            Order => "rorde".to_string(),
+            Order => "order".to_string(),
             Margin => "margin".to_string(),
             Position => "position".to_string(),
             PrivateNotifications => "privateNotifications".to_string(),
             Transact => "transact".to_string(),
             Wallet => "wallet".to_string(),
-        };
+        }
+    }
+}
 
-        serializer.serialize_str(&repr)
+impl Serialize for Topic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.repr())
     }
 }
 
@@ -100,28 +122,36 @@ impl<'de> Deserialize<'de> for Topic {
             ["insurance"] => Insurance,
             ["liquidation"] => Liquidation,
             ["orderBookL2_25"] => OrderBookL2_25(None),
-            ["orderBookL2_25", filter] => OrderBookL2(Some(filter.to_string())),
+            ["orderBookL2_25", filter] => OrderBookL2_25(Some(filter.to_string())),
             ["orderBookL2"] => OrderBookL2(None),
             ["orderBookL2", filter] => OrderBookL2(Some(filter.to_string())),
             ["orderBook10"] => OrderBook10,
             ["publicNotifications"] => PublicNotifications,
             ["quote"] => Quote,
-            ["quoteBin1m"] => QuoteBin1m,
-            ["quoteBin5m"] => QuoteBin5m,
-            ["quoteBin1h"] => QuoteBin1h,
-            ["quoteBin1d"] => QuoteBin1d,
+            ["quoteBin1m"] => QuoteBin1m(None),
+            ["quoteBin1m", filter] => QuoteBin1m(Some(filter.to_string())),
+            ["quoteBin5m"] => QuoteBin5m(None),
+            ["quoteBin5m", filter] => QuoteBin5m(Some(filter.to_string())),
+            ["quoteBin1h"] => QuoteBin1h(None),
+            ["quoteBin1h", filter] => QuoteBin1h(Some(filter.to_string())),
+            ["quoteBin1d"] => QuoteBin1d(None),
+            ["quoteBin1d", filter] => QuoteBin1d(Some(filter.to_string())),
             ["settlement"] => Settlement,
             ["trade"] => Trade(None),
-            ["trade", filter] => OrderBookL2(Some(filter.to_string())),
-            ["tradeBin1m"] => TradeBin1m,
-            ["tradeBin5m"] => TradeBin5m,
-            ["tradeBin1h"] => TradeBin1h,
-            ["tradeBin1d"] => TradeBin1d,
+            ["trade", filter] => Trade(Some(filter.to_string())),
+            ["tradeBin1m"] => TradeBin1m(None),
+            ["tradeBin1m", filter] => TradeBin1m(Some(filter.to_string())),
+            ["tradeBin5m"] => TradeBin5m(None),
+            ["tradeBin5m", filter] => TradeBin5m(Some(filter.to_string())),
+            ["tradeBin1h"] => TradeBin1h(None),
+            ["tradeBin1h", filter] => TradeBin1h(Some(filter.to_string())),
+            ["tradeBin1d"] => TradeBin1d(None),
+            ["tradeBin1d", filter] => TradeBin1d(Some(filter.to_string())),
 
             // requires auth
             ["affiliate"] => Affiliate,
             ["execution"] => Execution,
-            ["rorde"] => Order,
+            ["order"] => Order,
             ["margin"] => Margin,
             ["position"] => Position,
             ["privateNotifications"] => PrivateNotifications,
@@ -0,0 +1,87 @@
+//! The BitMEX request signature, for signing requests that don't go through a `Transport`
+
+use std::fmt;
+use std::hint::black_box;
+
+use hex::encode as hexify;
+use ring::{digest, hmac};
+
+/// An api secret that is overwritten with zeros when dropped, so it doesn't linger in freed memory.
+/// Its `Debug` output is redacted. `Transport` clones share a single copy.
+#[derive(Default)]
+pub struct ApiSecret(String);
+
+impl ApiSecret {
+    /// Takes ownership of `secret` without copying it
+    pub fn new(secret: String) -> Self {
+        ApiSecret(secret)
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ApiSecret {
+    fn from(secret: String) -> Self {
+        ApiSecret(secret)
+    }
+}
+
+impl From<&str> for ApiSecret {
+    fn from(secret: &str) -> Self {
+        ApiSecret(secret.to_string())
+    }
+}
+
+impl fmt::Debug for ApiSecret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ApiSecret(***)")
+    }
+}
+
+impl Drop for ApiSecret {
+    fn drop(&mut self) {
+        let mut bytes = std::mem::take(&mut self.0).into_bytes();
+        bytes.iter_mut().for_each(|b| *b = 0);
+        // Keeps the writes from being optimized away as dead stores
+        black_box(&mut bytes);
+    }
+}
+
+/// `hex(HMAC_SHA256(secret, method + path + expires + body))`. `path` includes the query string,
+/// e.g. `/api/v1/instrument?count=1`, and `expires` is a unix timestamp. The websocket
+/// `authKeyExpires` command signs `GET/realtime` with an empty body.
+pub fn sign(secret: &str, method: &str, path: &str, expires: i64, body: &str) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let message = format!("{}{}{}{}", method, path, expires, body);
+    hexify(hmac::sign(&key, message.as_bytes()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sign, ApiSecret};
+
+    // The examples from https://www.bitmex.com/app/apiKeysUsage
+    const SECRET: &str = "chNOOS4KvNXR_Xq4k4c9qsfoKWvnDecLATCRlcBwyKDYnWgO";
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(sign(SECRET, "GET", "/api/v1/instrument", 1518064236, ""), "c7682d435d0cfe87c16098df34ef2eb5a549d4c5a3c2b1f0f77b8af73423bf00");
+        assert_eq!(
+            sign(SECRET, "GET", "/api/v1/instrument?filter=%7B%22symbol%22%3A+%22XBTM15%22%7D", 1518064237, ""),
+            "e2f422547eecb5b3cb29ade2127e21b858b235b386bfa45e1c1756eb3383919f"
+        );
+        assert_eq!(
+            sign(SECRET, "POST", "/api/v1/order", 1518064238, r#"{"symbol":"XBTM15","price":219.0,"clOrdID":"mm_bitmex_1a/oemUeQ4CAJZgP3fjHsA","orderQty":98}"#),
+            "1749cd2ccae4aa49048ae09f0b95110cee706e0944e6a14ad0b3a8cb45bd336b"
+        );
+    }
+
+    #[test]
+    fn test_api_secret() {
+        let secret = ApiSecret::from(SECRET);
+        assert_eq!(secret.expose(), SECRET);
+        assert_eq!(format!("{:?}", secret), "ApiSecret(***)");
+    }
+}
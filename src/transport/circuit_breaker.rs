@@ -0,0 +1,262 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::future;
+use hyper::Method;
+use url::Url;
+
+use super::{BoxFuture, RawResponse, TransportTrait};
+use crate::error::{BitMEXError, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests go through
+    Closed,
+    /// Requests fail with `BitMEXError::CircuitOpen` without being sent
+    Open,
+    /// The recovery timeout passed, the next request is sent as a probe
+    HalfOpen,
+}
+
+/// Stops sending requests while BitMEX is down. After `failure_threshold` consecutive failures
+/// (network errors and 5xx responses) the circuit opens and requests fail immediately with
+/// `BitMEXError::CircuitOpen`. Once `recovery_timeout` passed, a single probe request decides
+/// whether the circuit closes again.
+///
+/// Rejected requests never count as failures, so retrying them doesn't extend the outage. 4xx
+/// responses, including 429, count as successes: BitMEX is up and answering. Requests sent before
+/// the circuit opened don't count once it is open, only the probe closes it. A probe that is
+/// dropped before it completes, e.g. by a timeout, lets the next request probe instead. Clones
+/// share the state.
+pub struct CircuitBreaker<T> {
+    inner: Arc<T>,
+    breaker: Arc<Mutex<Breaker>>,
+    failure_threshold: u32,
+    recovery_timeout: Duration,
+}
+
+impl<T> Clone for CircuitBreaker<T> {
+    fn clone(&self) -> Self {
+        CircuitBreaker {
+            inner: self.inner.clone(),
+            breaker: self.breaker.clone(),
+            failure_threshold: self.failure_threshold,
+            recovery_timeout: self.recovery_timeout,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+    // Counts the openings of the circuit, tells the requests sent before the last one apart
+    openings: u64,
+}
+
+// A request let through by `CircuitBreaker::admit`
+struct Admission {
+    breaker: Arc<Mutex<Breaker>>,
+    openings: u64,
+    probe: bool,
+}
+
+impl Drop for Admission {
+    // Runs whether the probe completed or was dropped, so the circuit can't be stuck probing
+    fn drop(&mut self) {
+        if self.probe {
+            if let Ok(mut breaker) = self.breaker.lock() {
+                breaker.probing = false;
+            }
+        }
+    }
+}
+
+impl<T> CircuitBreaker<T> {
+    pub fn new(inner: T, failure_threshold: u32, recovery_timeout: Duration) -> Self {
+        CircuitBreaker {
+            inner: Arc::new(inner),
+            breaker: Arc::new(Mutex::new(Breaker::default())),
+            failure_threshold,
+            recovery_timeout,
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let breaker = self.breaker.lock().unwrap();
+        match breaker.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() >= self.recovery_timeout => CircuitState::HalfOpen,
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    // Whether a request may be sent now, marks it as the probe when half open
+    fn admit(&self) -> Option<Admission> {
+        let mut breaker = self.breaker.lock().unwrap();
+        let probe = match breaker.opened_at {
+            None => false,
+            Some(opened_at) if opened_at.elapsed() >= self.recovery_timeout && !breaker.probing => {
+                breaker.probing = true;
+                true
+            }
+            Some(_) => return None,
+        };
+        Some(Admission {
+            breaker: self.breaker.clone(),
+            openings: breaker.openings,
+            probe,
+        })
+    }
+
+    fn record(&self, admission: &Admission, success: bool) {
+        let mut breaker = self.breaker.lock().unwrap();
+        // Sent while the circuit was still closed, the result is older than the outage
+        if !admission.probe && admission.openings != breaker.openings {
+            return;
+        }
+        if success {
+            breaker.failures = 0;
+            breaker.opened_at = None;
+        } else {
+            breaker.failures += 1;
+            if breaker.opened_at.is_some() || breaker.failures >= self.failure_threshold {
+                if breaker.opened_at.is_none() {
+                    breaker.openings += 1;
+                }
+                breaker.opened_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+impl<T: TransportTrait + Send + Sync + 'static> CircuitBreaker<T> {
+    fn guard(&self, admission: Admission, resp: BoxFuture<RawResponse>) -> BoxFuture<RawResponse> {
+        let this = self.clone();
+        Box::pin(async move {
+            let resp = resp.await;
+            let success = match &resp {
+                Ok(resp) => !resp.status.is_server_error(),
+                Err(e) => !e.is_transient(),
+            };
+            this.record(&admission, success);
+            resp
+        })
+    }
+
+    fn rejected() -> BoxFuture<RawResponse> {
+        Box::pin(future::ready(Err(BitMEXError::CircuitOpen)))
+    }
+}
+
+impl<T: TransportTrait + Send + Sync + 'static> TransportTrait for CircuitBreaker<T> {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        match self.admit() {
+            Some(admission) => self.guard(admission, self.inner.request_raw(method, endpoint, query, body)),
+            None => Self::rejected(),
+        }
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        match self.admit() {
+            Some(admission) => self.guard(admission, self.inner.signed_request_raw(method, endpoint, query, body)),
+            None => Self::rejected(),
+        }
+    }
+
+    fn api_key(&self) -> Option<&str> {
+        self.inner.api_key()
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn signature(&self, method: &Method, expires: i64, url: &Url, body: &str) -> Result<(&str, String)> {
+        self.inner.signature(method, expires, url, body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    use futures::executor::block_on;
+    use hyper::StatusCode;
+    use serde_json::Value;
+
+    use super::{CircuitBreaker, CircuitState};
+    use crate::error::{BitMEXError, Result};
+    use crate::transport::{MockTransport, TransportTrait};
+
+    #[test]
+    fn test_circuit_breaker() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_status(StatusCode::SERVICE_UNAVAILABLE, "");
+        tr.push_status(StatusCode::SERVICE_UNAVAILABLE, "");
+        tr.push_status(StatusCode::SERVICE_UNAVAILABLE, "");
+        tr.push_response("[]");
+
+        let breaker = CircuitBreaker::new(tr.clone(), 2, Duration::from_millis(50));
+        let get = || block_on(breaker.get::<Value, ()>("/instrument", None));
+        assert!(get().is_err());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(get().is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        assert!(matches!(get().unwrap_err(), BitMEXError::CircuitOpen));
+        assert_eq!(tr.requests().len(), 2);
+
+        // The failed probe opens the circuit again
+        sleep(Duration::from_millis(60));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(matches!(get().unwrap_err(), BitMEXError::ServiceUnavailable { .. }));
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        sleep(Duration::from_millis(60));
+        get()?;
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(tr.requests().len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dropped_probe() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_status(StatusCode::SERVICE_UNAVAILABLE, "");
+        tr.push_response("[]");
+        tr.push_response("[]");
+
+        let breaker = CircuitBreaker::new(tr.clone(), 1, Duration::from_millis(50));
+        assert!(block_on(breaker.get::<Value, ()>("/instrument", None)).is_err());
+        sleep(Duration::from_millis(60));
+
+        // The probe is given up on, e.g. by a timeout, before it completes
+        drop(breaker.get::<Value, ()>("/instrument", None));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        block_on(breaker.get::<Value, ()>("/instrument", None))?;
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_success() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response("[]");
+        tr.push_status(StatusCode::SERVICE_UNAVAILABLE, "");
+        tr.push_status(StatusCode::SERVICE_UNAVAILABLE, "");
+
+        let breaker = CircuitBreaker::new(tr.clone(), 2, Duration::from_secs(60));
+        // Sent while closed, completes after the circuit opened
+        let slow = breaker.get::<Value, ()>("/instrument", None);
+        assert!(block_on(breaker.get::<Value, ()>("/instrument", None)).is_err());
+        assert!(block_on(breaker.get::<Value, ()>("/instrument", None)).is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        block_on(slow)?;
+        assert_eq!(breaker.state(), CircuitState::Open);
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use hyper::header::{HeaderMap, CONTENT_ENCODING, CONTENT_LENGTH};
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, decompress_to_vec_zlib_with_limit, DecompressError};
+
+use crate::error::{BitMEXError, Result};
+
+// The flags of the gzip header announcing optional fields, RFC 1952
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+// The most a body may decompress to, far above any BitMEX response
+const MAX_DECOMPRESSED_LEN: usize = 64 << 20;
+
+// The CRC-32 table of the gzip trailer, RFC 1952
+const CRC_TABLE: [u32; 256] = crc_table();
+
+/// Decompresses a body sent with `Content-Encoding: gzip` or `deflate` and drops the headers that
+/// describe the compressed body. Other bodies are returned as they are.
+pub(super) fn decompress(headers: &mut HeaderMap, body: Vec<u8>) -> Result<Vec<u8>> {
+    let encoding = match headers.get(CONTENT_ENCODING).and_then(|encoding| encoding.to_str().ok()) {
+        Some(encoding) => encoding.trim().to_ascii_lowercase(),
+        None => return Ok(body),
+    };
+    let body = match encoding.as_str() {
+        "gzip" | "x-gzip" => gunzip(&body, MAX_DECOMPRESSED_LEN)?,
+        // Meant to be zlib wrapped, some servers send raw deflate data
+        "deflate" => decompress_to_vec_zlib_with_limit(&body, MAX_DECOMPRESSED_LEN)
+            .or_else(|_| decompress_to_vec_with_limit(&body, MAX_DECOMPRESSED_LEN))
+            .map_err(inflate_error)?,
+        _ => return Ok(body),
+    };
+    headers.remove(CONTENT_ENCODING);
+    headers.remove(CONTENT_LENGTH);
+    Ok(body)
+}
+
+fn gunzip(body: &[u8], max_len: usize) -> Result<Vec<u8>> {
+    let invalid = || BitMEXError::Decompress("invalid gzip header".to_string());
+    if body.len() < 18 || body[0] != 0x1f || body[1] != 0x8b || body[2] != 8 {
+        return Err(invalid());
+    }
+
+    let flags = body[3];
+    let mut pos = 10;
+    if flags & FEXTRA != 0 {
+        let len = body.get(pos..pos + 2).ok_or_else(invalid)?;
+        pos += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = body.get(pos..).and_then(|rest| rest.iter().position(|&b| b == 0)).ok_or_else(invalid)?;
+            pos += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+
+    // The CRC and size of the data trail it, single member bodies only
+    let (data, trailer) = body.split_at(body.len() - 8);
+    let data = decompress_to_vec_with_limit(data.get(pos..).ok_or_else(invalid)?, max_len).map_err(inflate_error)?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32(&data) || size != data.len() as u32 {
+        return Err(BitMEXError::Decompress("gzip trailer doesn't match the data".to_string()));
+    }
+    Ok(data)
+}
+
+fn inflate_error(error: DecompressError) -> BitMEXError {
+    BitMEXError::Decompress(error.to_string())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &b| CRC_TABLE[((crc ^ u32::from(b)) & 0xff) as usize] ^ (crc >> 8))
+}
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut crc = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            crc = if crc & 1 != 0 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            k += 1;
+        }
+        table[n] = crc;
+        n += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use hyper::header::{HeaderMap, HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH};
+    use miniz_oxide::deflate::{compress_to_vec, compress_to_vec_zlib};
+
+    use super::{crc32, decompress, gunzip};
+    use crate::error::{BitMEXError, Result};
+
+    const BODY: &[u8] = br#"[{"symbol":"XBTUSD","side":"Buy","size":100,"price":3800.5}]"#;
+
+    fn gzip(name: Option<&str>) -> Vec<u8> {
+        let flags = if name.is_some() { 0x08 } else { 0 };
+        let mut body = vec![0x1f, 0x8b, 8, flags, 0, 0, 0, 0, 0, 255];
+        if let Some(name) = name {
+            body.extend_from_slice(name.as_bytes());
+            body.push(0);
+        }
+        body.extend(compress_to_vec(BODY, 6));
+        body.extend_from_slice(&crc32(BODY).to_le_bytes());
+        body.extend_from_slice(&(BODY.len() as u32).to_le_bytes());
+        body
+    }
+
+    fn headers(encoding: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_str(encoding).unwrap());
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("42"));
+        headers
+    }
+
+    #[test]
+    fn test_decompress() -> Result<()> {
+        let mut gzip_headers = headers("gzip");
+        assert_eq!(decompress(&mut gzip_headers, gzip(None))?, BODY);
+        assert!(gzip_headers.get(CONTENT_ENCODING).is_none());
+        assert!(gzip_headers.get(CONTENT_LENGTH).is_none());
+        assert_eq!(decompress(&mut headers("gzip"), gzip(Some("trades.json")))?, BODY);
+
+        assert_eq!(decompress(&mut headers("deflate"), compress_to_vec_zlib(BODY, 6))?, BODY);
+        assert_eq!(decompress(&mut headers("deflate"), compress_to_vec(BODY, 6))?, BODY);
+
+        // Uncompressed bodies are left alone
+        assert_eq!(decompress(&mut HeaderMap::new(), BODY.to_vec())?, BODY);
+        let mut identity = headers("identity");
+        assert_eq!(decompress(&mut identity, BODY.to_vec())?, BODY);
+        assert!(identity.get(CONTENT_ENCODING).is_some());
+
+        let err = decompress(&mut headers("gzip"), BODY.to_vec()).unwrap_err();
+        assert!(matches!(err, BitMEXError::Decompress(_)));
+        let truncated = gzip(None)[..20].to_vec();
+        assert!(decompress(&mut headers("gzip"), truncated).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gzip_trailer() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+
+        let mut corrupt = gzip(None);
+        let crc = corrupt.len() - 8;
+        corrupt[crc] ^= 1;
+        assert!(matches!(gunzip(&corrupt, BODY.len()), Err(BitMEXError::Decompress(_))));
+
+        let mut resized = gzip(None);
+        let size = resized.len() - 4;
+        resized[size] += 1;
+        assert!(matches!(gunzip(&resized, BODY.len()), Err(BitMEXError::Decompress(_))));
+
+        assert_eq!(gunzip(&gzip(None), BODY.len()).unwrap(), BODY);
+        assert!(matches!(gunzip(&gzip(None), BODY.len() - 1), Err(BitMEXError::Decompress(_))));
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use futures::future;
+use hyper::{Method, StatusCode};
+
+use super::{BoxFuture, RawResponse, TransportTrait};
+use crate::error::{BitMEXError, Result};
+
+/// A request received by the `MockTransport`
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+    pub method: Method,
+    pub endpoint: String,
+    pub query: Vec<(String, String)>,
+    pub body: String,
+    pub signed: bool,
+}
+
+/// A transport replaying pre-programmed responses in order, for testing without network access.
+/// Clones share the same queue, so a clone can be kept to inspect the received requests.
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    responses: Arc<Mutex<VecDeque<Result<RawResponse>>>>,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_response<B: Into<Vec<u8>>>(&self, body: B) {
+        self.push_raw(RawResponse::ok(body));
+    }
+
+    pub fn push_status<B: Into<Vec<u8>>>(&self, status: StatusCode, body: B) {
+        self.push_raw(RawResponse::with_status(status, body));
+    }
+
+    /// Queue a response with custom headers
+    pub fn push_raw(&self, resp: RawResponse) {
+        self.responses.lock().unwrap().push_back(Ok(resp));
+    }
+
+    pub fn push_error<E: Into<BitMEXError>>(&self, err: E) {
+        self.responses.lock().unwrap().push_back(Err(err.into()));
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String, signed: bool) -> BoxFuture<RawResponse> {
+        self.requests.lock().unwrap().push(MockRequest {
+            method,
+            endpoint: endpoint.to_string(),
+            query,
+            body,
+            signed,
+        });
+        let resp = match self.responses.lock().unwrap().pop_front() {
+            Some(resp) => resp,
+            None => Err(BitMEXError::Other(format!("MockTransport has no response left for {}", endpoint))),
+        };
+        Box::pin(future::ready(resp))
+    }
+}
+
+impl TransportTrait for MockTransport {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.respond(method, endpoint, query, body, false)
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.respond(method, endpoint, query, body, true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use hyper::Method;
+    use serde_json::{json, Value};
+
+    use super::{MockTransport, TransportTrait};
+    use crate::error::{BitMEXError, Result};
+
+    #[test]
+    fn test_mock_response() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response(r#"[{"symbol":"XBTUSD"}]"#);
+
+        let resp: Value = block_on(tr.get("/instrument", Some(json!({"symbol": "XBTUSD"}))))?;
+        assert_eq!(resp, json!([{"symbol": "XBTUSD"}]));
+
+        let reqs = tr.requests();
+        assert_eq!(reqs[0].method, Method::GET);
+        assert_eq!(reqs[0].endpoint, "/instrument");
+        assert_eq!(reqs[0].query, vec![("symbol".to_string(), "XBTUSD".to_string())]);
+        assert!(!reqs[0].signed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_error() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_error(BitMEXError::NoApiKeySet);
+        tr.push_response(r#"{"error":{"message":"Not Found","name":"HTTPError"}}"#);
+
+        assert!(block_on(tr.signed_get::<Value, ()>("/position", None)).is_err());
+        assert!(block_on(tr.get::<Vec<Value>, ()>("/instrument", None)).is_err());
+        // Queue exhausted
+        assert!(block_on(tr.signed_post::<Value, _>("/order", Some(json!({"symbol": "XBTUSD"})))).is_err());
+        assert_eq!(tr.requests()[2].body, r#"{"symbol":"XBTUSD"}"#);
+        Ok(())
+    }
+}
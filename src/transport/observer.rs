@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hyper::{Method, StatusCode};
+use url::Url;
+
+use super::{BoxFuture, RawResponse, TransportTrait};
+use crate::error::{BitMEXError, Result};
+
+/// Callbacks around every request, e.g. to export latencies as metrics. Every method defaults to
+/// doing nothing, `()` is an observer that ignores everything.
+///
+/// `on_response` sees every response, including 4xx and 5xx ones, `on_error` only requests that got
+/// no response at all. The latency is the wall-clock time from sending the request until its body
+/// was read. Wrap the `Transport` itself to measure BitMEX alone, wrapping a `ThrottledTransport` or
+/// `RetryTransport` includes their waits.
+pub trait RequestObserver: Send + Sync {
+    fn on_request(&self, _method: &Method, _endpoint: &str) {}
+
+    fn on_response(&self, _method: &Method, _endpoint: &str, _status: StatusCode, _latency: Duration) {}
+
+    fn on_error(&self, _method: &Method, _endpoint: &str, _error: &BitMEXError, _latency: Duration) {}
+}
+
+impl RequestObserver for () {}
+
+/// Reports every request of the inner transport to a `RequestObserver`
+pub struct ObservedTransport<T, O> {
+    inner: T,
+    observer: Arc<O>,
+}
+
+impl<T, O: RequestObserver> ObservedTransport<T, O> {
+    pub fn new(inner: T, observer: O) -> Self {
+        ObservedTransport {
+            inner,
+            observer: Arc::new(observer),
+        }
+    }
+}
+
+impl<T: TransportTrait, O: RequestObserver + 'static> ObservedTransport<T, O> {
+    fn observe(&self, signed: bool, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        let resp = if signed {
+            self.inner.signed_request_raw(method.clone(), endpoint, query, body)
+        } else {
+            self.inner.request_raw(method.clone(), endpoint, query, body)
+        };
+
+        let observer = self.observer.clone();
+        let endpoint = endpoint.to_string();
+        // Requests are sent on the first poll, not when the future is created
+        Box::pin(async move {
+            observer.on_request(&method, &endpoint);
+            let start = Instant::now();
+            let resp = resp.await;
+            match &resp {
+                Ok(resp) => observer.on_response(&method, &endpoint, resp.status, start.elapsed()),
+                Err(e) => observer.on_error(&method, &endpoint, e, start.elapsed()),
+            }
+            resp
+        })
+    }
+}
+
+impl<T: TransportTrait, O: RequestObserver + 'static> TransportTrait for ObservedTransport<T, O> {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.observe(false, method, endpoint, query, body)
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.observe(true, method, endpoint, query, body)
+    }
+
+    fn api_key(&self) -> Option<&str> {
+        self.inner.api_key()
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn signature(&self, method: &Method, expires: i64, url: &Url, body: &str) -> Result<(&str, String)> {
+        self.inner.signature(method, expires, url, body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    use futures::executor::block_on;
+    use hyper::{Method, StatusCode};
+    use serde_json::Value;
+
+    use super::{ObservedTransport, RequestObserver};
+    use crate::error::{BitMEXError, Result};
+    use crate::transport::{MockTransport, TransportTrait};
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl RequestObserver for Recorder {
+        fn on_request(&self, method: &Method, endpoint: &str) {
+            self.0.lock().unwrap().push(format!("request {} {}", method, endpoint));
+        }
+
+        fn on_response(&self, method: &Method, endpoint: &str, status: StatusCode, latency: Duration) {
+            assert!(latency < Duration::from_millis(50));
+            self.0.lock().unwrap().push(format!("response {} {} {}", method, endpoint, status.as_u16()));
+        }
+
+        fn on_error(&self, method: &Method, endpoint: &str, error: &BitMEXError, _latency: Duration) {
+            self.0.lock().unwrap().push(format!("error {} {} {}", method, endpoint, error));
+        }
+    }
+
+    #[test]
+    fn test_observer() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response("[]");
+        tr.push_status(StatusCode::NOT_FOUND, r#"{"error":{"message":"Not Found","name":"HTTPError"}}"#);
+        tr.push_error(BitMEXError::Timeout);
+
+        let observed = ObservedTransport::new(tr.clone(), Recorder::default());
+        // The latency starts when the request is sent
+        let resp = observed.get::<Value, ()>("/trade", None);
+        sleep(Duration::from_millis(50));
+        block_on(resp)?;
+        assert!(block_on(observed.signed_get::<Value, ()>("/order", None)).is_err());
+        assert!(block_on(observed.signed_delete::<Value, ()>("/order/all", None)).is_err());
+
+        let events = observed.observer.0.lock().unwrap();
+        let expected = vec![
+            "request GET /trade",
+            "response GET /trade 200",
+            "request GET /order",
+            "response GET /order 404",
+            "request DELETE /order/all",
+            "error DELETE /order/all Request timed out",
+        ];
+        assert_eq!(*events, expected);
+        assert!(tr.requests()[1].signed);
+
+        // The no-op observer
+        tr.push_response("[]");
+        block_on(ObservedTransport::new(tr.clone(), ()).get::<Value, ()>("/trade", None))?;
+        Ok(())
+    }
+}
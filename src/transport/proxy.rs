@@ -0,0 +1,81 @@
+use std::error::Error as StdError;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::Uri;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tower_service::Service;
+
+// The most of a CONNECT response we read before giving up on the proxy
+const MAX_RESPONSE_LEN: usize = 8192;
+
+/// Connects directly, or through an HTTP proxy with a CONNECT tunnel to the destination, over which
+/// `HttpsConnector` then does the TLS handshake as usual
+#[derive(Clone, Debug)]
+pub(crate) struct ProxyConnector {
+    http: HttpConnector,
+    proxy: Option<Uri>,
+}
+
+impl ProxyConnector {
+    pub(crate) fn new(proxy: Option<Uri>) -> Self {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        ProxyConnector { http, proxy }
+    }
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = TokioIo<TcpStream>;
+    type Error = Box<dyn StdError + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        match &self.proxy {
+            None => {
+                let connecting = self.http.call(dst);
+                Box::pin(async move { Ok(connecting.await?) })
+            }
+            Some(proxy) => {
+                let connecting = self.http.call(proxy.clone());
+                Box::pin(async move {
+                    let mut stream = connecting.await?;
+                    tunnel(stream.inner_mut(), &dst).await?;
+                    Ok(stream)
+                })
+            }
+        }
+    }
+}
+
+async fn tunnel(stream: &mut TcpStream, dst: &Uri) -> io::Result<()> {
+    let host = dst.host().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("No host in {}", dst)))?;
+    let port = dst.port_u16().unwrap_or(if dst.scheme_str() == Some("http") { 80 } else { 443 });
+    let authority = format!("{}:{}", host, port);
+    stream.write_all(format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", authority).as_bytes()).await?;
+
+    let mut response = Vec::new();
+    let mut buf = [0; 1024];
+    while !response.windows(4).any(|window| window == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || response.len() + n > MAX_RESPONSE_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Proxy closed or sent an oversized response to CONNECT"));
+        }
+        response.extend_from_slice(&buf[..n]);
+    }
+
+    let status = response.split(|&b| b == b'\r').next().unwrap_or_default();
+    match status.split(|&b| b == b' ').nth(1) {
+        Some(code) if code == b"200" => Ok(()),
+        _ => Err(io::Error::other(format!("Proxy refused to tunnel to {}: {}", authority, String::from_utf8_lossy(status)))),
+    }
+}
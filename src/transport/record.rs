@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use futures::future;
+use hyper::{Method, StatusCode};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{from_reader, to_writer_pretty};
+use url::Url;
+
+use super::{BoxFuture, RawResponse, TransportTrait};
+use crate::error::{BitMEXError, Result};
+
+/// One recorded exchange. Signing headers are not part of it, so replays don't need credentials.
+/// Response headers aren't recorded either.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub method: String,
+    pub endpoint: String,
+    pub query: Vec<(String, String)>,
+    pub body: String,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    pub response: String,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+impl Fixture {
+    fn matches(&self, method: &Method, endpoint: &str, query: &[(String, String)], body: &str) -> bool {
+        self.method == method.as_str() && self.endpoint == endpoint && self.query == query && self.body == body
+    }
+}
+
+/// Wraps a transport and writes every response it gets to a JSON fixture file,
+/// to be served later by a `ReplayTransport`. The file is rewritten after each response.
+///
+/// Recording needs the live API, and credentials for private endpoints. The integration tests in
+/// `tests/` record to and replay from `tests/fixtures` with the `record` feature, see
+/// `tests/common/mod.rs`.
+#[derive(Clone)]
+pub struct RecordingTransport<T> {
+    inner: T,
+    path: PathBuf,
+    fixtures: Arc<Mutex<Vec<Fixture>>>,
+}
+
+impl<T: TransportTrait> RecordingTransport<T> {
+    pub fn new<P: AsRef<Path>>(inner: T, path: P) -> Self {
+        RecordingTransport {
+            inner,
+            path: path.as_ref().to_path_buf(),
+            fixtures: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    fn record(&self, fixture: Fixture, fut: BoxFuture<RawResponse>) -> BoxFuture<RawResponse> {
+        let fixtures = self.fixtures.clone();
+        let path = self.path.clone();
+
+        Box::pin(async move {
+            let resp = fut.await?;
+            let mut fixtures = fixtures.lock().unwrap();
+            fixtures.push(Fixture {
+                status: resp.status.as_u16(),
+                response: String::from_utf8_lossy(&resp.body).into_owned(),
+                ..fixture
+            });
+            to_writer_pretty(File::create(&path)?, &*fixtures)?;
+            Ok(resp)
+        })
+    }
+}
+
+impl<T: TransportTrait> TransportTrait for RecordingTransport<T> {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        let fixture = Fixture {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            query: query.clone(),
+            body: body.clone(),
+            status: default_status(),
+            response: String::new(),
+        };
+        let fut = self.inner.request_raw(method, endpoint, query, body);
+        self.record(fixture, fut)
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        let fixture = Fixture {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            query: query.clone(),
+            body: body.clone(),
+            status: default_status(),
+            response: String::new(),
+        };
+        let fut = self.inner.signed_request_raw(method, endpoint, query, body);
+        self.record(fixture, fut)
+    }
+
+    fn api_key(&self) -> Option<&str> {
+        self.inner.api_key()
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn signature(&self, method: &Method, expires: i64, url: &Url, body: &str) -> Result<(&str, String)> {
+        self.inner.signature(method, expires, url, body)
+    }
+}
+
+/// Serves the responses of a fixture file written by `RecordingTransport`, without network access.
+/// Each request consumes the first unused fixture with the same method, endpoint, query and body.
+#[derive(Clone)]
+pub struct ReplayTransport {
+    fixtures: Arc<Mutex<VecDeque<Fixture>>>,
+}
+
+impl ReplayTransport {
+    pub fn new(fixtures: Vec<Fixture>) -> Self {
+        ReplayTransport {
+            fixtures: Arc::new(Mutex::new(fixtures.into())),
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let fixtures: Vec<Fixture> = from_reader(File::open(path)?)?;
+        Ok(Self::new(fixtures))
+    }
+
+    fn replay(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        let mut fixtures = self.fixtures.lock().unwrap();
+        let pos = fixtures.iter().position(|f| f.matches(&method, endpoint, &query, &body));
+        let resp = match pos.and_then(|pos| fixtures.remove(pos)) {
+            Some(fixture) => StatusCode::from_u16(fixture.status)
+                .map(|status| RawResponse::with_status(status, fixture.response))
+                .map_err(|e| BitMEXError::Other(format!("Invalid status in fixture: {}", e))),
+            None => Err(BitMEXError::Other(format!("No recorded response for {} {} {:?}", method, endpoint, query))),
+        };
+        Box::pin(future::ready(resp))
+    }
+}
+
+impl TransportTrait for ReplayTransport {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.replay(method, endpoint, query, body)
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.replay(method, endpoint, query, body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    use futures::executor::block_on;
+    use serde_json::{json, Value};
+
+    use super::{RecordingTransport, ReplayTransport};
+    use crate::error::Result;
+    use crate::transport::{MockTransport, TransportTrait};
+
+    #[test]
+    fn test_record_and_replay() -> Result<()> {
+        let path = temp_dir().join("bitmex-rs-test-record-and-replay.json");
+
+        let mock = MockTransport::new();
+        mock.push_response(r#"[{"symbol":"XBTUSD"}]"#);
+        mock.push_response(r#"{"orderID":"abc"}"#);
+
+        let recorder = RecordingTransport::new(mock, &path);
+        let instruments: Value = block_on(recorder.get("/instrument", Some(json!({"symbol": "XBTUSD"}))))?;
+        let order: Value = block_on(recorder.signed_post("/order", Some(json!({"symbol": "XBTUSD"}))))?;
+
+        let replay = ReplayTransport::from_file(&path)?;
+        remove_file(&path)?;
+
+        // Replays don't depend on the request order
+        let replayed_order: Value = block_on(replay.signed_post("/order", Some(json!({"symbol": "XBTUSD"}))))?;
+        let replayed_instruments: Value = block_on(replay.get("/instrument", Some(json!({"symbol": "XBTUSD"}))))?;
+        assert_eq!(replayed_instruments, instruments);
+        assert_eq!(replayed_order, order);
+
+        // Every fixture is served once, and only to a matching request
+        assert!(block_on(replay.get::<Value, _>("/instrument", Some(json!({"symbol": "XBTUSD"})))).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::{Method, StatusCode};
+use serde_json::{from_str, Value};
+use tokio::time::sleep;
+use url::Url;
+
+use super::throttle::rate_limit_delay;
+use super::{BoxFuture, RawResponse, TransportTrait};
+use crate::error::Result;
+
+/// When to send a request again, see `RetryTransport`
+#[derive(Clone, Copy, Debug)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    /// The delay before the first retry, doubled for every following one
+    pub backoff: Duration,
+    /// Retry every request, including POSTs without a `clOrdID` and PUTs. A request that timed
+    /// out may still have been applied, retrying it can place or amend an order twice.
+    pub retry_all: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: 3,
+            backoff: Duration::from_millis(500),
+            retry_all: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub(super) fn is_retryable(&self, method: &Method, body: &str) -> bool {
+        self.retry_all || *method == Method::GET || *method == Method::DELETE || (*method == Method::POST && carries_cl_ord_id(body))
+    }
+}
+
+/// Sends a request again after network failures and 5xx responses, waiting `backoff` before the
+/// first retry and twice as long before each following one. A 429 is retried after the delay its
+/// `Retry-After` or `X-RateLimit-Reset` asks for, or `backoff` if it has neither.
+///
+/// Only requests that are safe to repeat are retried: GETs, DELETEs, and POSTs carrying a `clOrdID`
+/// (for `/order/bulk` every order has to carry one). BitMEX rejects a second order with the same
+/// `clOrdID`, so a POST that timed out but was applied can't be placed twice. Any other POST or PUT
+/// fails on the first error, use `retry_all(true)` to retry them anyway. BitMEX doesn't process a
+/// request it answers with 429, so those are retried for every method. Clones share the inner transport.
+pub struct RetryTransport<T> {
+    inner: Arc<T>,
+    config: RetryConfig,
+}
+
+impl<T> Clone for RetryTransport<T> {
+    fn clone(&self) -> Self {
+        RetryTransport {
+            inner: self.inner.clone(),
+            config: self.config,
+        }
+    }
+}
+
+impl<T> RetryTransport<T> {
+    pub fn new(inner: T, max_retries: u32) -> Self {
+        Self::with_config(
+            inner,
+            RetryConfig {
+                max_retries,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn with_config(inner: T, config: RetryConfig) -> Self {
+        RetryTransport { inner: Arc::new(inner), config }
+    }
+
+    /// The delay before the first retry, 500ms by default
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.config.backoff = backoff;
+        self
+    }
+
+    /// See `RetryConfig::retry_all`
+    pub fn retry_all(mut self, retry_all: bool) -> Self {
+        self.config.retry_all = retry_all;
+        self
+    }
+}
+
+fn carries_cl_ord_id(body: &str) -> bool {
+    let has_id = |order: &Value| order.get("clOrdID").is_some_and(|id| !id.is_null());
+    match from_str::<Value>(body) {
+        Ok(data) => match data.get("orders") {
+            Some(Value::Array(orders)) => !orders.is_empty() && orders.iter().all(has_id),
+            _ => has_id(&data),
+        },
+        Err(_) => false,
+    }
+}
+
+/// Calls `send` until it succeeds, fails for good or `config` allows no more retries. Requests that
+/// aren't `retryable` are only sent again after a 429.
+pub(super) fn retry<F>(config: RetryConfig, retryable: bool, send: F) -> BoxFuture<RawResponse>
+where
+    F: Fn() -> BoxFuture<RawResponse> + Send + 'static,
+{
+    Box::pin(async move {
+        let mut retries = 0;
+        let mut backoff = config.backoff;
+        loop {
+            let resp = send().await;
+            let (failed, delay) = match &resp {
+                Ok(resp) if resp.status == StatusCode::TOO_MANY_REQUESTS => (true, rate_limit_delay(&resp.headers)),
+                Ok(resp) => (retryable && resp.status.is_server_error(), None),
+                Err(e) => (retryable && e.is_transient(), None),
+            };
+            if !failed || retries >= config.max_retries {
+                return resp;
+            }
+
+            retries += 1;
+            match delay {
+                Some(delay) => sleep(delay).await,
+                None => {
+                    sleep(backoff).await;
+                    backoff *= 2;
+                }
+            }
+        }
+    })
+}
+
+impl<T: TransportTrait + Send + Sync + 'static> RetryTransport<T> {
+    fn retry(&self, signed: bool, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        let inner = self.inner.clone();
+        let endpoint = endpoint.to_string();
+        let retryable = self.config.is_retryable(&method, &body);
+        retry(self.config, retryable, move || {
+            let (method, query, body) = (method.clone(), query.clone(), body.clone());
+            if signed {
+                inner.signed_request_raw(method, &endpoint, query, body)
+            } else {
+                inner.request_raw(method, &endpoint, query, body)
+            }
+        })
+    }
+}
+
+impl<T: TransportTrait + Send + Sync + 'static> TransportTrait for RetryTransport<T> {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.retry(false, method, endpoint, query, body)
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.retry(true, method, endpoint, query, body)
+    }
+
+    fn api_key(&self) -> Option<&str> {
+        self.inner.api_key()
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn signature(&self, method: &Method, expires: i64, url: &Url, body: &str) -> Result<(&str, String)> {
+        self.inner.signature(method, expires, url, body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use hyper::header::{HeaderValue, RETRY_AFTER};
+    use hyper::StatusCode;
+    use serde_json::{json, Value};
+    use tokio::runtime::Runtime;
+
+    use super::{carries_cl_ord_id, RetryTransport};
+    use crate::error::{BitMEXError, Result};
+    use crate::transport::{MockTransport, RawResponse, TransportTrait};
+
+    #[test]
+    fn test_carries_cl_ord_id() {
+        assert!(carries_cl_ord_id(r#"{"symbol":"XBTUSD","orderQty":1,"clOrdID":"a"}"#));
+        assert!(!carries_cl_ord_id(r#"{"symbol":"XBTUSD","orderQty":1,"clOrdID":null}"#));
+        assert!(carries_cl_ord_id(r#"{"orders":[{"clOrdID":"a"},{"clOrdID":"b"}]}"#));
+        assert!(!carries_cl_ord_id(r#"{"orders":[{"clOrdID":"a"},{"orderQty":1}]}"#));
+        assert!(!carries_cl_ord_id(""));
+    }
+
+    #[test]
+    fn test_retry_idempotent_only() -> Result<()> {
+        let rt = Runtime::new()?;
+        let tr = MockTransport::new();
+        let retry = RetryTransport::new(tr.clone(), 2).with_backoff(Duration::from_millis(1));
+
+        tr.push_error(BitMEXError::Timeout);
+        tr.push_status(StatusCode::BAD_GATEWAY, "");
+        tr.push_response("[]");
+        rt.block_on(retry.get::<Value, ()>("/trade", None))?;
+        assert_eq!(tr.requests().len(), 3);
+
+        tr.push_error(BitMEXError::Timeout);
+        let order = json!({"symbol": "XBTUSD", "orderQty": 1});
+        let err = rt.block_on(retry.signed_post::<Value, _>("/order", Some(order.clone()))).unwrap_err();
+        assert!(matches!(err, BitMEXError::Timeout));
+        assert_eq!(tr.requests().len(), 4);
+
+        tr.push_error(BitMEXError::Timeout);
+        tr.push_response("{}");
+        rt.block_on(retry.signed_post::<Value, _>("/order", Some(json!({"symbol": "XBTUSD", "orderQty": 1, "clOrdID": "a"}))))?;
+        assert_eq!(tr.requests().len(), 6);
+
+        // Errors that won't go away are not retried
+        tr.push_status(StatusCode::BAD_REQUEST, r#"{"error":{"message":"Invalid orderQty","name":"ValidationError"}}"#);
+        assert!(rt.block_on(retry.get::<Value, ()>("/trade", None)).is_err());
+        assert_eq!(tr.requests().len(), 7);
+
+        let retry = retry.retry_all(true);
+        tr.push_error(BitMEXError::Timeout);
+        tr.push_response("{}");
+        rt.block_on(retry.signed_post::<Value, _>("/order", Some(order)))?;
+        assert_eq!(tr.requests().len(), 9);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_rate_limited() -> Result<()> {
+        let rt = Runtime::new()?;
+        let tr = MockTransport::new();
+        // The long backoff would time the test out, the Retry-After of 0 is waited for instead
+        let retry = RetryTransport::new(tr.clone(), 1).with_backoff(Duration::from_secs(600));
+
+        let mut rate_limited = RawResponse::with_status(StatusCode::TOO_MANY_REQUESTS, "");
+        rate_limited.headers.insert(RETRY_AFTER, HeaderValue::from(0));
+        tr.push_raw(rate_limited.clone());
+        tr.push_response("{}");
+        // Retried even though the POST carries no clOrdID
+        rt.block_on(retry.signed_post::<Value, _>("/order", Some(json!({"symbol": "XBTUSD", "orderQty": 1}))))?;
+        assert_eq!(tr.requests().len(), 2);
+
+        tr.push_raw(rate_limited.clone());
+        tr.push_raw(rate_limited);
+        let err = rt.block_on(retry.get::<Value, ()>("/trade", None)).unwrap_err();
+        assert!(matches!(err, BitMEXError::RateLimited { .. }));
+        assert_eq!(tr.requests().len(), 4);
+        Ok(())
+    }
+}
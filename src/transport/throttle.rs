@@ -0,0 +1,356 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hyper::header::RETRY_AFTER;
+use hyper::{HeaderMap, Method, StatusCode};
+use tokio::time::sleep;
+use url::Url;
+
+use super::{BoxFuture, RawResponse, TransportTrait};
+use crate::error::Result;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+// Used when a 429 carries neither Retry-After nor X-RateLimit-Reset
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Keeps requests within the BitMEX rate limit. The quota announced by `X-RateLimit-Remaining` is
+/// tracked across requests; once less than `min_remaining` is left, requests wait for `X-RateLimit-Reset`.
+/// Responses with a 429 are retried after the advertised delay.
+///
+/// Clones share the quota, so one throttler can be cloned into every task using the same api key.
+///
+/// With `with_rate_governor` requests are also paced: the remaining quota is spread evenly until
+/// the reset instead of being spent in a burst, so a steady stream of requests never hits the limit.
+pub struct ThrottledTransport<T> {
+    inner: Arc<T>,
+    limit: Arc<Mutex<RateLimit>>,
+    min_remaining: u32,
+    max_retries: u32,
+    governor: Option<Arc<RateGovernor>>,
+}
+
+impl<T> Clone for ThrottledTransport<T> {
+    fn clone(&self) -> Self {
+        ThrottledTransport {
+            inner: self.inner.clone(),
+            limit: self.limit.clone(),
+            min_remaining: self.min_remaining,
+            max_retries: self.max_retries,
+            governor: self.governor.clone(),
+        }
+    }
+}
+
+// BitMEX allows these routes 10 requests per second on top of the per minute limit, see "Request
+// Rate Limits" in https://www.bitmex.com/app/restAPI
+const ORDER_ROUTES: [(Method, &str); 6] = [
+    (Method::POST, "/order"),
+    (Method::PUT, "/order"),
+    (Method::DELETE, "/order"),
+    (Method::DELETE, "/order/all"),
+    (Method::POST, "/order/bulk"),
+    (Method::PUT, "/order/bulk"),
+];
+const ORDER_BURST: usize = 10;
+const ORDER_BURST_WINDOW: Duration = Duration::from_secs(1);
+
+/// The cost of each endpoint against the rate limit, see `ThrottledTransport::with_rate_governor`.
+/// Endpoints weigh 1 unless set, BitMEX doesn't document other weights. The order routes are also
+/// kept to their own limit of 10 requests per second. Clones share that limit.
+#[derive(Clone, Debug, Default)]
+pub struct RateGovernor {
+    weights: HashMap<(Method, String), u32>,
+    // When the last order requests were, or are going to be, sent
+    order_slots: Arc<Mutex<VecDeque<Instant>>>,
+}
+
+impl RateGovernor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count requests to `endpoint`, e.g. `/order/bulk`, as `weight` requests
+    pub fn with_weight(mut self, method: Method, endpoint: &str, weight: u32) -> Self {
+        self.weights.insert((method, endpoint.to_string()), weight);
+        self
+    }
+
+    pub fn weight(&self, method: &Method, endpoint: &str) -> u32 {
+        self.weights.get(&(method.clone(), endpoint.to_string())).copied().unwrap_or(1)
+    }
+
+    // How long a request has to wait for the order route limit, reserving its slot
+    fn order_wait(&self, method: &Method, endpoint: &str, now: Instant) -> Option<Duration> {
+        if !ORDER_ROUTES.iter().any(|(m, e)| m == method && *e == endpoint) {
+            return None;
+        }
+        let mut slots = self.order_slots.lock().unwrap();
+        while slots.front().is_some_and(|slot| *slot + ORDER_BURST_WINDOW <= now) {
+            slots.pop_front();
+        }
+        let slot = match slots.len().checked_sub(ORDER_BURST) {
+            Some(full) => slots[full] + ORDER_BURST_WINDOW,
+            None => now,
+        };
+        slots.push_back(slot);
+        slot.checked_duration_since(now).filter(|wait| !wait.is_zero())
+    }
+}
+
+#[derive(Debug, Default)]
+struct RateLimit {
+    remaining: Option<u32>,
+    reset: Option<SystemTime>,
+    // The earliest time the next paced request may be sent
+    next_slot: Option<SystemTime>,
+}
+
+impl RateLimit {
+    /// How long the next request has to wait. Reserves `weight` requests from the quota, so
+    /// concurrent requests don't all see the same remaining count.
+    fn reserve(&mut self, min_remaining: u32, weight: u32, pace: bool, now: SystemTime) -> Option<Duration> {
+        let remaining = self.remaining?;
+        let reset = self.reset?;
+        if now >= reset {
+            self.remaining = None;
+            self.next_slot = None;
+            return None;
+        }
+        self.remaining = Some(remaining.saturating_sub(weight));
+        // Paced requests wait for the reset once they don't fit the quota anymore
+        if remaining < min_remaining + if pace { weight } else { 0 } {
+            return reset.duration_since(now).ok();
+        }
+        if !pace {
+            return None;
+        }
+
+        // Every request gets its share of the time left until the reset
+        let slot = self.next_slot.filter(|slot| *slot > now).unwrap_or(now);
+        let share = reset.duration_since(now).unwrap_or_default() * weight / (remaining - min_remaining).max(1);
+        self.next_slot = Some(slot + share);
+        slot.duration_since(now).ok().filter(|wait| !wait.is_zero())
+    }
+
+    fn update(&mut self, headers: &HeaderMap) {
+        if let Some(remaining) = header_u64(headers, "x-ratelimit-remaining") {
+            self.remaining = Some(remaining as u32);
+        }
+        if let Some(reset) = header_u64(headers, "x-ratelimit-reset") {
+            self.reset = Some(UNIX_EPOCH + Duration::from_secs(reset));
+        }
+    }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name).and_then(|v| v.to_str().ok()).and_then(|v| v.parse().ok())
+}
+
+// How long a 429 asks to wait: its Retry-After, or else the time left until X-RateLimit-Reset
+pub(super) fn rate_limit_delay(headers: &HeaderMap) -> Option<Duration> {
+    header_u64(headers, RETRY_AFTER.as_str()).map(Duration::from_secs).or_else(|| {
+        let reset = UNIX_EPOCH + Duration::from_secs(header_u64(headers, "x-ratelimit-reset")?);
+        Some(reset.duration_since(SystemTime::now()).unwrap_or_default())
+    })
+}
+
+impl<T> ThrottledTransport<T> {
+    pub fn new(inner: T, min_remaining: u32) -> Self {
+        ThrottledTransport {
+            inner: Arc::new(inner),
+            limit: Arc::new(Mutex::new(RateLimit::default())),
+            min_remaining,
+            max_retries: DEFAULT_MAX_RETRIES,
+            governor: None,
+        }
+    }
+
+    /// Pace requests to stay within the quota, counting each by its weight in `governor`
+    pub fn with_rate_governor(mut self, governor: RateGovernor) -> Self {
+        self.governor = Some(Arc::new(governor));
+        self
+    }
+
+    /// How often a request answered with a 429 is sent again, 3 by default
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+}
+
+impl<T: TransportTrait + Send + Sync + 'static> ThrottledTransport<T> {
+    fn throttle(&self, signed: bool, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        let this = self.clone();
+        let endpoint = endpoint.to_string();
+        Box::pin(async move {
+            let mut retries = 0;
+            loop {
+                let weight = this.governor.as_ref().map_or(1, |governor| governor.weight(&method, &endpoint));
+                let wait = this.limit.lock().unwrap().reserve(this.min_remaining, weight, this.governor.is_some(), SystemTime::now());
+                if let Some(wait) = wait {
+                    sleep(wait).await;
+                }
+                if let Some(wait) = this.governor.as_ref().and_then(|governor| governor.order_wait(&method, &endpoint, Instant::now())) {
+                    sleep(wait).await;
+                }
+
+                let (method, query, body) = (method.clone(), query.clone(), body.clone());
+                let resp = if signed {
+                    this.inner.signed_request_raw(method, &endpoint, query, body).await?
+                } else {
+                    this.inner.request_raw(method, &endpoint, query, body).await?
+                };
+
+                let reset = {
+                    let mut limit = this.limit.lock().unwrap();
+                    limit.update(&resp.headers);
+                    limit.reset
+                };
+                if resp.status != StatusCode::TOO_MANY_REQUESTS || retries >= this.max_retries {
+                    return Ok(resp);
+                }
+                let retry_after = rate_limit_delay(&resp.headers)
+                    .or_else(|| reset.and_then(|reset| reset.duration_since(SystemTime::now()).ok()))
+                    .unwrap_or(DEFAULT_RETRY_DELAY);
+
+                retries += 1;
+                sleep(retry_after).await;
+            }
+        })
+    }
+}
+
+impl<T: TransportTrait + Send + Sync + 'static> TransportTrait for ThrottledTransport<T> {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.throttle(false, method, endpoint, query, body)
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.throttle(true, method, endpoint, query, body)
+    }
+
+    fn api_key(&self) -> Option<&str> {
+        self.inner.api_key()
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn signature(&self, method: &Method, expires: i64, url: &Url, body: &str) -> Result<(&str, String)> {
+        self.inner.signature(method, expires, url, body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    use hyper::header::{HeaderValue, RETRY_AFTER};
+    use hyper::{Method, StatusCode};
+    use serde_json::Value;
+    use tokio::runtime::Runtime;
+
+    use super::{RateGovernor, RateLimit, ThrottledTransport};
+    use crate::error::{BitMEXError, Result};
+    use crate::transport::{MockTransport, RawResponse, TransportTrait};
+
+    fn limited(remaining: u32, reset: SystemTime) -> RawResponse {
+        let reset = reset.duration_since(UNIX_EPOCH).unwrap().as_secs() + 2;
+        let mut resp = RawResponse::ok("[]");
+        resp.headers.insert("x-ratelimit-remaining", HeaderValue::from(remaining));
+        resp.headers.insert("x-ratelimit-reset", HeaderValue::from(reset));
+        resp
+    }
+
+    #[test]
+    fn test_throttle() -> Result<()> {
+        let rt = Runtime::new()?;
+        let tr = MockTransport::new();
+        tr.push_raw(limited(1, SystemTime::now()));
+        tr.push_response("[]");
+
+        let throttled = ThrottledTransport::new(tr.clone(), 2);
+        let start = Instant::now();
+        rt.block_on(throttled.get::<Value, ()>("/trade", None))?;
+        assert!(start.elapsed() < Duration::from_millis(500));
+        rt.block_on(throttled.get::<Value, ()>("/trade", None))?;
+        assert!(start.elapsed() > Duration::from_millis(900));
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_rate_limited() -> Result<()> {
+        let rt = Runtime::new()?;
+        let tr = MockTransport::new();
+        let mut rate_limited = RawResponse::with_status(StatusCode::TOO_MANY_REQUESTS, "");
+        rate_limited.headers.insert(RETRY_AFTER, HeaderValue::from(0));
+        for _ in 0..3 {
+            tr.push_raw(rate_limited.clone());
+        }
+        tr.push_response("[]");
+
+        let throttled = ThrottledTransport::new(tr.clone(), 0).with_max_retries(1);
+        let err = rt.block_on(throttled.get::<Value, ()>("/trade", None)).unwrap_err();
+        assert!(matches!(err, BitMEXError::RateLimited { .. }));
+        assert_eq!(tr.requests().len(), 2);
+
+        let throttled = throttled.with_max_retries(3);
+        rt.block_on(throttled.signed_get::<Value, ()>("/order", None))?;
+        assert_eq!(tr.requests().len(), 4);
+        assert!(tr.requests()[3].signed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_governor() {
+        let governor = RateGovernor::new().with_weight(Method::POST, "/order/bulk", 5);
+        assert_eq!(governor.weight(&Method::POST, "/order/bulk"), 5);
+        assert_eq!(governor.weight(&Method::GET, "/order/bulk"), 1);
+
+        // 10 requests left for the next 10s are sent a second apart
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut limit = RateLimit {
+            remaining: Some(10),
+            reset: Some(now + Duration::from_secs(10)),
+            next_slot: None,
+        };
+        assert_eq!(limit.reserve(0, 1, true, now), None);
+        assert_eq!(limit.reserve(0, 1, true, now), Some(Duration::from_secs(1)));
+        assert_eq!(limit.remaining, Some(8));
+
+        // A heavy request takes a bigger share, one that doesn't fit waits for the reset
+        let later = now + Duration::from_secs(5);
+        assert_eq!(limit.reserve(0, 4, true, later), None);
+        assert_eq!(limit.reserve(0, 4, true, later), Some(Duration::from_millis(2500)));
+        assert_eq!(limit.reserve(0, 1, true, later), Some(Duration::from_secs(5)));
+
+        // Without pacing only the reset is waited for
+        let mut limit = RateLimit {
+            remaining: Some(10),
+            reset: Some(now + Duration::from_secs(10)),
+            next_slot: None,
+        };
+        assert_eq!(limit.reserve(0, 1, false, now), None);
+        assert_eq!(limit.reserve(0, 1, false, now), None);
+    }
+
+    #[test]
+    fn test_order_burst() {
+        let governor = RateGovernor::new();
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(governor.order_wait(&Method::POST, "/order", now), None);
+        }
+        // The 11th order request waits for the first to leave the window, the 12th for the second
+        assert_eq!(governor.order_wait(&Method::PUT, "/order/bulk", now), Some(Duration::from_secs(1)));
+        assert_eq!(governor.order_wait(&Method::DELETE, "/order", now + Duration::from_millis(500)), Some(Duration::from_millis(500)));
+        assert_eq!(governor.order_wait(&Method::GET, "/order", now), None);
+
+        // Clones share the limit, the window moves on with time
+        let clone = governor.clone();
+        assert_eq!(clone.order_wait(&Method::POST, "/order", now), Some(Duration::from_secs(1)));
+        assert_eq!(governor.order_wait(&Method::POST, "/order", now + Duration::from_secs(3)), None);
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::Instant;
+
+use hyper::Method;
+use serde_json::from_slice;
+use tracing::{error, field, info_span, trace, Instrument};
+use url::Url;
+
+use super::{rest_url, BoxFuture, ErrorBody, RawResponse, TransportTrait};
+use crate::error::Result;
+
+/// Wraps every request in a `bitmex_request` span with the fields `http.method`, `http.url`,
+/// `http.status_code` and `latency_ms`, plus `api_key` for signed requests. The secret never leaves
+/// the inner transport. Failed requests are reported with `error!`.
+pub struct TracingTransport<T> {
+    inner: T,
+    log_bodies: bool,
+}
+
+impl<T> TracingTransport<T> {
+    pub fn new(inner: T) -> Self {
+        TracingTransport { inner, log_bodies: false }
+    }
+
+    /// Emit request and response bodies as `TRACE` events. Off by default, bodies can be large
+    /// and carry account data.
+    pub fn log_bodies(mut self, log_bodies: bool) -> Self {
+        self.log_bodies = log_bodies;
+        self
+    }
+}
+
+impl<T: TransportTrait> TracingTransport<T> {
+    fn trace(&self, signed: bool, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        let url = rest_url(self.inner.base_url(), endpoint, query.clone()).map(|url| url.to_string()).unwrap_or_else(|_| endpoint.to_string());
+        let span = info_span!(
+            "bitmex_request",
+            http.method = %method,
+            http.url = %url,
+            http.status_code = field::Empty,
+            latency_ms = field::Empty,
+            api_key = field::Empty,
+        );
+        if signed {
+            if let Some(key) = self.inner.api_key() {
+                span.record("api_key", key);
+            }
+        }
+        let log_bodies = self.log_bodies;
+        if log_bodies {
+            span.in_scope(|| trace!(body = %body, "Request body"));
+        }
+
+        let start = Instant::now();
+        let resp = if signed {
+            self.inner.signed_request_raw(method, endpoint, query, body)
+        } else {
+            self.inner.request_raw(method, endpoint, query, body)
+        };
+
+        let current = span.clone();
+        Box::pin(
+            async move {
+                let resp = resp.await;
+                current.record("latency_ms", start.elapsed().as_millis() as u64);
+                match &resp {
+                    Ok(resp) => {
+                        current.record("http.status_code", resp.status.as_u16());
+                        if log_bodies {
+                            trace!(body = %String::from_utf8_lossy(&resp.body), "Response body");
+                        }
+                        if !resp.status.is_success() {
+                            match from_slice::<ErrorBody>(&resp.body) {
+                                Ok(ErrorBody { error }) => error!(name = %error.name, "BitMEX error: {}", error.message),
+                                Err(_) => error!("BitMEX error: {}", resp.status),
+                            }
+                        }
+                    }
+                    Err(e) => error!("Request failed: {}", e),
+                }
+                resp
+            }
+            .instrument(span),
+        )
+    }
+}
+
+impl<T: TransportTrait> TransportTrait for TracingTransport<T> {
+    fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.trace(false, method, endpoint, query, body)
+    }
+
+    fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+        self.trace(true, method, endpoint, query, body)
+    }
+
+    fn api_key(&self) -> Option<&str> {
+        self.inner.api_key()
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn signature(&self, method: &Method, expires: i64, url: &Url, body: &str) -> Result<(&str, String)> {
+        self.inner.signature(method, expires, url, body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use hyper::StatusCode;
+    use serde_json::Value;
+
+    use super::TracingTransport;
+    use crate::error::{BitMEXError, Result};
+    use crate::transport::{MockTransport, TransportTrait};
+
+    #[test]
+    fn test_tracing_passes_through() -> Result<()> {
+        let tr = MockTransport::new();
+        tr.push_response("[]");
+        tr.push_status(StatusCode::BAD_REQUEST, r#"{"error":{"message":"Invalid ordStatus","name":"HTTPError"}}"#);
+
+        let traced = TracingTransport::new(tr.clone()).log_bodies(true);
+        block_on(traced.get::<Value, ()>("/trade", None))?;
+        let err = block_on(traced.signed_get::<Value, ()>("/order", None)).unwrap_err();
+        assert!(matches!(err, BitMEXError::Api { status: 400, .. }));
+        assert!(tr.requests()[1].signed);
+        Ok(())
+    }
+}
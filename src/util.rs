@@ -0,0 +1,540 @@
+//! Calculations on top of the REST models that don't need a request
+
+use crate::model::definitions::{Trade, TradeBin};
+use crate::model::instrument::{GetInstrumentResponse, MinOrderInfo};
+use crate::model::order_book::GetOrderBookL2Response;
+use crate::model::position::GeneralPositionResponse;
+use crate::model::{OrdType, Satoshi, Side};
+
+/// The price at which an isolated position opened at `entry` gets liquidated. `side` is `Buy` for
+/// longs and `Sell` for shorts, `maint_margin_req` is the instrument's `maintMargin`, e.g. 0.005
+/// for 0.5%. Fees and funding are ignored, so BitMEX's `liquidationPrice` is slightly closer to the
+/// entry. Returns NaN for an `Empty` or `Unknown` side.
+///
+/// Inverse contracts use `entry / (1 ± 1 / leverage ∓ maint_margin_req)`, linear contracts
+/// `entry * (1 ∓ 1 / leverage ± maint_margin_req)`.
+pub fn calculate_liquidation_price(entry: f64, leverage: f64, side: Side, is_inverse: bool, maint_margin_req: f64) -> f64 {
+    let sign = match side {
+        Side::Buy => 1.,
+        Side::Sell => -1.,
+        Side::Empty | Side::Unknown(_) => return f64::NAN,
+    };
+    let margin = 1. / leverage - maint_margin_req;
+    if is_inverse {
+        entry / (1. + sign * margin)
+    } else {
+        entry * (1. - sign * margin)
+    }
+}
+
+/// The initial margin, in the settlement currency (satoshis for XBt), that an order for `qty`
+/// contracts at `price` reserves. A `leverage` of 0 means cross margin, which reserves the
+/// instrument's `initMargin`. Isolated positions reserve `1 / leverage`, but never less than
+/// `initMargin`.
+///
+/// The estimated fee is on top: `takerFee` for market orders, `makerFee` for limit orders resting
+/// in the book. A maker rebate is paid out only after the fill, so it doesn't reduce the margin.
+/// Returns NaN if the instrument has no multiplier.
+pub fn calculate_order_margin(instrument: &GetInstrumentResponse, qty: i64, price: f64, ord_type: OrdType, leverage: f64) -> f64 {
+    let value = position_value(instrument, qty, price, instrument.multiplier.unwrap_or(f64::NAN)).abs();
+
+    let init_margin = instrument.init_margin.unwrap_or(0.);
+    let margin_req = if leverage == 0. { init_margin } else { init_margin.max(1. / leverage) };
+    let fee = match ord_type {
+        OrdType::Market | OrdType::Stop | OrdType::MarketIfTouched | OrdType::MarketWithLeftOverAsLimit => instrument.taker_fee,
+        _ => instrument.maker_fee,
+    };
+    value * (margin_req + fee.unwrap_or(0.).max(0.))
+}
+
+/// The margin, in the settlement currency (satoshis for XBt), that entering a position of `qty`
+/// contracts at `price` costs: the position's value times `init_margin_req`, e.g. `1 / leverage`.
+/// Fees are not included, see `calculate_order_fee`. `None` if the instrument has no multiplier.
+pub fn calculate_order_cost(qty: i64, price: f64, instrument: &GetInstrumentResponse, init_margin_req: f64) -> Option<Satoshi> {
+    let value = position_value(instrument, qty, price, instrument.multiplier?).abs();
+    Some(Satoshi((value * init_margin_req).round() as i64))
+}
+
+/// The fee for filling `qty` contracts at `price`, in the settlement currency. Negative for a maker
+/// rebate. `None` if the instrument has no multiplier or fee.
+pub fn calculate_order_fee(qty: i64, price: f64, instrument: &GetInstrumentResponse, is_maker: bool) -> Option<Satoshi> {
+    let value = position_value(instrument, qty, price, instrument.multiplier?).abs();
+    let fee = if is_maker { instrument.maker_fee? } else { instrument.taker_fee? };
+    Some(Satoshi((value * fee).round() as i64))
+}
+
+/// The most contracts to enter at `entry_price` so that a stop at `stop_price` loses at most
+/// `risk_pct` percent (e.g. 1.0 for 1%) of `account_balance_xbt` satoshis. Works for longs and
+/// shorts alike. Clamped to the instrument's `maxOrderQty` and rounded down to its `lotSize`, 0 if
+/// the stop is at the entry or the instrument has no multiplier.
+pub fn position_size_by_risk(account_balance_xbt: i64, risk_pct: f64, entry_price: f64, stop_price: f64, instrument: &MinOrderInfo) -> i64 {
+    if instrument.multiplier == 0 || entry_price == stop_price {
+        return 0;
+    }
+    let (multiplier, distance) = (instrument.multiplier.abs() as f64, (entry_price - stop_price).abs());
+    let risk = account_balance_xbt as f64 * risk_pct / 100.;
+    // The loss per contract is `multiplier * |1 / stop - 1 / entry|` for inverse contracts,
+    // `multiplier * |entry - stop|` otherwise
+    let contracts = if instrument.is_inverse {
+        risk * entry_price * stop_price / (multiplier * distance)
+    } else {
+        risk / (multiplier * distance)
+    };
+    let contracts = contracts.min(instrument.max_order_qty as f64);
+    floor_to_lot(contracts, instrument.lot_size)
+}
+
+/// The profit of `position` if it was closed at `mark_price`, in the settlement currency (satoshis
+/// for XBt). Fees and funding are ignored. `None` if the position has no entry price or the
+/// instrument no multiplier.
+pub fn calculate_unrealized_pnl(position: &GeneralPositionResponse, instrument: &GetInstrumentResponse, mark_price: f64) -> Option<Satoshi> {
+    let qty = position.current_qty;
+    if qty == 0 {
+        return Some(Satoshi(0));
+    }
+    let (entry, multiplier) = (position.avg_entry_price?, instrument.multiplier?);
+    let pnl = if instrument.is_inverse.unwrap_or(false) {
+        position_value(instrument, qty, entry, multiplier) - position_value(instrument, qty, mark_price, multiplier)
+    } else {
+        position_value(instrument, qty, mark_price, multiplier) - position_value(instrument, qty, entry, multiplier)
+    };
+    Some(Satoshi(pnl.round() as i64))
+}
+
+/// The next funding payment of `position`, in the settlement currency (satoshis for XBt). Positive
+/// if the position pays, negative if it receives: longs pay shorts while the rate is positive.
+///
+/// Uses the instrument's `fundingRate`, the rate exchanged at the next `fundingTimestamp`.
+/// `indicativeFundingRate` is the estimate for the interval after. `None` if the position has no
+/// mark price or the instrument no multiplier or funding rate.
+pub fn estimate_funding_payment(position: &GeneralPositionResponse, instrument: &GetInstrumentResponse) -> Option<Satoshi> {
+    if position.current_qty == 0 {
+        return Some(Satoshi(0));
+    }
+    let (mark_price, multiplier, funding_rate) = (position.mark_price?, instrument.multiplier?, instrument.funding_rate?);
+    Some(Satoshi((position_value(instrument, position.current_qty, mark_price, multiplier) * funding_rate).round() as i64))
+}
+
+/// The volume weighted average price of `trades`, buys and sells alike. Trades without price or
+/// size are skipped, `None` if no contract traded.
+pub fn calculate_vwap(trades: &[Trade]) -> Option<f64> {
+    vwap(trades.iter().filter_map(|trade| Some((trade.price?, trade.size?))))
+}
+
+/// Like `calculate_vwap`, from the `vwap` and `volume` of trade buckets, e.g. when the individual
+/// trades aren't available anymore
+pub fn calculate_vwap_from_buckets(buckets: &[TradeBin]) -> Option<f64> {
+    vwap(buckets.iter().filter_map(|bucket| Some((bucket.vwap?, bucket.volume?))))
+}
+
+fn vwap(fills: impl Iterator<Item = (f64, i64)>) -> Option<f64> {
+    let (notional, volume) = fills.fold((0., 0), |(notional, volume), (price, size)| (notional + price * size as f64, volume + size));
+    if volume == 0 {
+        None
+    } else {
+        Some(notional / volume as f64)
+    }
+}
+
+/// The highest bid of an L2 book as returned by `get_order_book_l2`, as price and size. The levels
+/// may come in any order, `None` if there are no bids.
+pub fn best_bid(book: &[GetOrderBookL2Response]) -> Option<(f64, i64)> {
+    best(book, Side::Buy, |price, best| price > best)
+}
+
+/// The lowest ask of an L2 book, `None` if there are no asks
+pub fn best_ask(book: &[GetOrderBookL2Response]) -> Option<(f64, i64)> {
+    best(book, Side::Sell, |price, best| price < best)
+}
+
+fn best(book: &[GetOrderBookL2Response], side: Side, better: impl Fn(f64, f64) -> bool) -> Option<(f64, i64)> {
+    book.iter()
+        .filter(|level| level.side == side)
+        .fold(None, |best: Option<&GetOrderBookL2Response>, level| match best {
+            Some(best) if !better(level.price, best.price) => Some(best),
+            _ => Some(level),
+        })
+        .map(|level| (level.price, level.size as i64))
+}
+
+/// The midpoint between best bid and best ask
+pub fn mid_price(book: &[GetOrderBookL2Response]) -> Option<f64> {
+    Some((best_bid(book)?.0 + best_ask(book)?.0) / 2.)
+}
+
+/// Best ask minus best bid
+pub fn spread(book: &[GetOrderBookL2Response]) -> Option<f64> {
+    Some(best_ask(book)?.0 - best_bid(book)?.0)
+}
+
+/// The spread in basis points of the mid price
+pub fn spread_bps(book: &[GetOrderBookL2Response]) -> Option<f64> {
+    Some(spread(book)? / mid_price(book)? * 10000.)
+}
+
+/// `price` rounded to the nearest multiple of `tick_size`, e.g. the instrument's `tickSize`. Prices
+/// within float error of a tick count as on it, and the result has no more decimals than the tick
+/// size, so `219.00000000001` rounds to `219` for a tick of 0.5 or 0.1. Returns `price` for a tick
+/// size of 0.
+pub fn round_to_tick(price: f64, tick_size: f64) -> f64 {
+    to_tick(price, tick_size, f64::round)
+}
+
+/// Like `round_to_tick`, rounding down, e.g. for a buy that must not pay more
+pub fn floor_to_tick(price: f64, tick_size: f64) -> f64 {
+    to_tick(price, tick_size, f64::floor)
+}
+
+/// Like `round_to_tick`, rounding up, e.g. for a sell that must not get less
+pub fn ceil_to_tick(price: f64, tick_size: f64) -> f64 {
+    to_tick(price, tick_size, f64::ceil)
+}
+
+/// Whether `price` is a multiple of `tick_size` up to float error, i.e. whether BitMEX accepts it
+/// once rounded with `round_to_tick`. Any price is for a tick size of 0.
+pub fn is_tick_multiple(price: f64, tick_size: f64) -> bool {
+    if tick_size <= 0. {
+        return true;
+    }
+    let ticks = price / tick_size;
+    (ticks - ticks.round()).abs() < 1e-9
+}
+
+fn to_tick(price: f64, tick_size: f64, round: fn(f64) -> f64) -> f64 {
+    if tick_size <= 0. || !price.is_finite() {
+        return price;
+    }
+    let ticks = price / tick_size;
+    let ticks = if is_tick_multiple(price, tick_size) { ticks.round() } else { round(ticks) };
+
+    // Ticks like 0.1 aren't exact floats, cut the result to the decimals of the tick size
+    let decimals = (0..=12).find(|&decimals| {
+        let scaled = tick_size * 10f64.powi(decimals);
+        (scaled - scaled.round()).abs() < 1e-9 * scaled.max(1.)
+    });
+    match decimals {
+        Some(decimals) => {
+            let scale = 10f64.powi(decimals);
+            (ticks * tick_size * scale).round() / scale
+        }
+        None => ticks * tick_size,
+    }
+}
+
+/// `qty` rounded to the nearest multiple of `lot_size`, the instrument's `lotSize`, e.g. for a
+/// quantity computed from a notional value. Like the tick functions, quantities within float error
+/// of a lot count as on it. A lot size below 1 is taken as 1.
+pub fn round_to_lot(qty: f64, lot_size: i64) -> i64 {
+    to_lot(qty, lot_size, f64::round)
+}
+
+/// Like `round_to_lot`, rounding towards zero so that the order is never larger than `qty`
+pub fn floor_to_lot(qty: f64, lot_size: i64) -> i64 {
+    to_lot(qty, lot_size, f64::trunc)
+}
+
+fn to_lot(qty: f64, lot_size: i64, round: fn(f64) -> f64) -> i64 {
+    let lot_size = lot_size.max(1);
+    let lots = qty / lot_size as f64;
+    let nearest = lots.round();
+    let lots = if (lots - nearest).abs() < 1e-9 { nearest } else { round(lots) };
+    // `as` saturates, NaN becomes 0
+    (lots as i64).saturating_mul(lot_size)
+}
+
+// Inverse contracts are worth a fixed amount of the quote currency, quanto and linear contracts a
+// fixed amount of the underlying. Negative for shorts.
+fn position_value(instrument: &GetInstrumentResponse, qty: i64, price: f64, multiplier: f64) -> f64 {
+    if instrument.is_inverse.unwrap_or(false) {
+        multiplier.abs() * qty as f64 / price
+    } else {
+        multiplier * qty as f64 * price
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use serde_json::from_str;
+
+    use super::{best_ask, best_bid, ceil_to_tick, floor_to_lot, floor_to_tick, is_tick_multiple, mid_price, round_to_lot, round_to_tick, spread, spread_bps};
+    use super::{
+        calculate_liquidation_price, calculate_order_cost, calculate_order_fee, calculate_order_margin, calculate_unrealized_pnl, calculate_vwap, calculate_vwap_from_buckets, estimate_funding_payment, position_size_by_risk,
+    };
+    use crate::model::definitions::{Trade, TradeBin};
+    use crate::model::instrument::{GetInstrumentResponse, MinOrderInfo};
+    use crate::model::order_book::GetOrderBookL2Response;
+    use crate::model::position::GeneralPositionResponse;
+    use crate::model::{OrdType, Satoshi, Side};
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 0.01, "{} != {}", actual, expected);
+    }
+
+    fn xbtusd() -> GetInstrumentResponse {
+        from_str(
+            r#"{"symbol":"XBTUSD","multiplier":-100000000,"isQuanto":false,"isInverse":true,"initMargin":0.01,"makerFee":-0.00025,"takerFee":0.00075,"timestamp":"2019-01-01T00:00:00.000Z"}"#,
+        )
+        .unwrap()
+    }
+
+    // 1 contract pays 100 satoshis per dollar
+    fn ethusd() -> GetInstrumentResponse {
+        from_str(
+            r#"{"symbol":"ETHUSD","multiplier":100,"isQuanto":true,"isInverse":false,"initMargin":0.02,"makerFee":-0.00025,"takerFee":0.00075,"timestamp":"2019-01-01T00:00:00.000Z"}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_liquidation_price() {
+        // XBTUSD, maintMargin 0.5%
+        assert_close(calculate_liquidation_price(10000., 100., Side::Buy, true, 0.005), 9950.25);
+        assert_close(calculate_liquidation_price(10000., 100., Side::Sell, true, 0.005), 10050.25);
+        assert_close(calculate_liquidation_price(3700., 10., Side::Buy, true, 0.005), 3379.00);
+        assert_close(calculate_liquidation_price(3700., 10., Side::Sell, true, 0.005), 4088.40);
+        // A 1x long on an inverse contract survives until about half the entry
+        assert_close(calculate_liquidation_price(3700., 1., Side::Buy, true, 0.005), 1854.64);
+
+        // A linear contract, maintMargin 1%
+        assert_close(calculate_liquidation_price(200., 20., Side::Buy, false, 0.01), 192.);
+        assert_close(calculate_liquidation_price(200., 20., Side::Sell, false, 0.01), 208.);
+
+        assert!(calculate_liquidation_price(200., 20., Side::Empty, false, 0.01).is_nan());
+    }
+
+    #[test]
+    fn test_order_margin() {
+        let xbtusd = xbtusd();
+        // 10000 contracts at 10000 are worth 1 XBT
+        assert_close(calculate_order_margin(&xbtusd, 10000, 10000., OrdType::Market, 10.), 10_075_000.);
+        assert_close(calculate_order_margin(&xbtusd, -10000, 10000., OrdType::Limit, 10.), 10_000_000.);
+        assert_close(calculate_order_margin(&xbtusd, 10000, 10000., OrdType::Limit, 0.), 1_000_000.);
+        // More leverage than initMargin allows
+        assert_close(calculate_order_margin(&xbtusd, 10000, 10000., OrdType::Limit, 200.), 1_000_000.);
+
+        let ethusd = ethusd();
+        assert_close(calculate_order_margin(&ethusd, 1000, 200., OrdType::StopLimit, 5.), 4_000_000.);
+        assert_close(calculate_order_margin(&ethusd, 1000, 200., OrdType::Stop, 0.), 415_000.);
+    }
+
+    #[test]
+    fn test_order_cost() {
+        let (xbtusd, ethusd) = (xbtusd(), ethusd());
+        // 1 XBT worth of contracts at 10x
+        assert_eq!(calculate_order_cost(10000, 10000., &xbtusd, 0.1), Some(Satoshi(10_000_000)));
+        assert_eq!(calculate_order_cost(-10000, 10000., &xbtusd, 0.1), Some(Satoshi(10_000_000)));
+        assert_eq!(calculate_order_cost(1000, 200., &ethusd, 0.02), Some(Satoshi(400_000)));
+        // Rounded to whole satoshis
+        assert_eq!(calculate_order_cost(1, 3700., &xbtusd, 0.01), Some(Satoshi(270)));
+
+        assert_eq!(calculate_order_fee(10000, 10000., &xbtusd, false), Some(Satoshi(75_000)));
+        assert_eq!(calculate_order_fee(-10000, 10000., &xbtusd, true), Some(Satoshi(-25_000)));
+        assert_eq!(calculate_order_fee(1000, 200., &ethusd, false), Some(Satoshi(15_000)));
+
+        let unknown = GetInstrumentResponse { multiplier: None, ..ethusd };
+        assert_eq!(calculate_order_cost(1000, 200., &unknown, 0.02), None);
+    }
+
+    #[test]
+    fn test_position_size_by_risk() {
+        let xbtusd = MinOrderInfo::from(&GetInstrumentResponse {
+            lot_size: Some(100.),
+            max_order_qty: Some(10_000_000.),
+            ..xbtusd()
+        });
+        // Risking 1% of 1 XBT, a stop 1000 below loses 1111 satoshis per contract
+        assert_eq!(position_size_by_risk(100_000_000, 1., 10000., 9000., &xbtusd), 900);
+        assert_eq!(position_size_by_risk(100_000_000, 1., 10000., 11000., &xbtusd), 1100);
+        // 3233 contracts, rounded down to the lot size
+        assert_eq!(position_size_by_risk(100_000_000, 1., 10000., 9700., &xbtusd), 3200);
+        assert_eq!(position_size_by_risk(100_000_000, 10., 10000., 9999.5, &xbtusd), 10_000_000);
+        assert_eq!(position_size_by_risk(100_000_000, 1., 10000., 10000., &xbtusd), 0);
+
+        // 100 satoshis per dollar and contract
+        let ethusd = MinOrderInfo::from(&ethusd());
+        assert_eq!(position_size_by_risk(100_000_000, 1., 200., 190., &ethusd), 1000);
+        assert_eq!(position_size_by_risk(100_000_000, 2.5, 200., 210., &ethusd), 2500);
+        assert_eq!(position_size_by_risk(100_000_000, 1., 200., 197., &ethusd), 3333);
+
+        assert_eq!(
+            xbtusd,
+            MinOrderInfo {
+                symbol: "XBTUSD".into(),
+                tick_size: 0.,
+                lot_size: 100,
+                max_order_qty: 10_000_000,
+                max_price: f64::INFINITY,
+                is_inverse: true,
+                multiplier: -100_000_000,
+            }
+        );
+        let unknown = MinOrderInfo { multiplier: 0, ..ethusd };
+        assert_eq!(position_size_by_risk(100_000_000, 1., 200., 190., &unknown), 0);
+    }
+
+    #[test]
+    fn test_unrealized_pnl() {
+        let position = |current_qty, avg_entry_price| GeneralPositionResponse {
+            current_qty,
+            avg_entry_price,
+            ..Default::default()
+        };
+
+        // 10000 contracts bought at 10000 are worth 1 XBT, at 12500 only 0.8 XBT
+        let (xbtusd, long, short) = (xbtusd(), position(10000, Some(10000.)), position(-10000, Some(10000.)));
+        assert_eq!(calculate_unrealized_pnl(&long, &xbtusd, 12500.), Some(Satoshi(20_000_000)));
+        assert_eq!(calculate_unrealized_pnl(&long, &xbtusd, 8000.), Some(Satoshi(-25_000_000)));
+        assert_eq!(calculate_unrealized_pnl(&short, &xbtusd, 12500.), Some(Satoshi(-20_000_000)));
+        assert_eq!(calculate_unrealized_pnl(&short, &xbtusd, 8000.), Some(Satoshi(25_000_000)));
+
+        let (ethusd, long, short) = (ethusd(), position(1000, Some(200.)), position(-1000, Some(200.)));
+        assert_eq!(calculate_unrealized_pnl(&long, &ethusd, 210.), Some(Satoshi(1_000_000)));
+        assert_eq!(calculate_unrealized_pnl(&long, &ethusd, 190.), Some(Satoshi(-1_000_000)));
+        assert_eq!(calculate_unrealized_pnl(&short, &ethusd, 210.), Some(Satoshi(-1_000_000)));
+        assert_eq!(calculate_unrealized_pnl(&short, &ethusd, 190.), Some(Satoshi(1_000_000)));
+
+        assert_eq!(calculate_unrealized_pnl(&position(0, None), &ethusd, 190.), Some(Satoshi(0)));
+        assert_eq!(calculate_unrealized_pnl(&position(10, None), &ethusd, 190.), None);
+    }
+
+    #[test]
+    fn test_funding_payment() {
+        let position = |current_qty, mark_price| GeneralPositionResponse {
+            current_qty,
+            mark_price,
+            ..Default::default()
+        };
+        let instrument = |funding_rate: f64| GetInstrumentResponse {
+            funding_rate: Some(funding_rate),
+            ..xbtusd()
+        };
+
+        // 1 XBT worth of contracts at 0.01%
+        let (long, short) = (position(10000, Some(10000.)), position(-10000, Some(10000.)));
+        assert_eq!(estimate_funding_payment(&long, &instrument(0.0001)), Some(Satoshi(10_000)));
+        assert_eq!(estimate_funding_payment(&short, &instrument(0.0001)), Some(Satoshi(-10_000)));
+        assert_eq!(estimate_funding_payment(&long, &instrument(-0.0001)), Some(Satoshi(-10_000)));
+        assert_eq!(estimate_funding_payment(&short, &instrument(-0.0001)), Some(Satoshi(10_000)));
+
+        let ethusd = GetInstrumentResponse {
+            funding_rate: Some(0.0001),
+            ..ethusd()
+        };
+        assert_eq!(estimate_funding_payment(&position(1000, Some(200.)), &ethusd), Some(Satoshi(2_000)));
+        assert_eq!(estimate_funding_payment(&position(0, None), &ethusd), Some(Satoshi(0)));
+        assert_eq!(estimate_funding_payment(&position(1000, None), &ethusd), None);
+        assert_eq!(estimate_funding_payment(&long, &xbtusd()), None);
+    }
+
+    #[test]
+    fn test_vwap() {
+        let trade = |side, price, size| Trade {
+            timestamp: Utc::now().into(),
+            symbol: "XBTUSD".into(),
+            side: Some(side),
+            size: Some(size),
+            price: Some(price),
+            tick_direction: None,
+            trd_match_id: None,
+            gross_value: None,
+            home_notional: None,
+            foreign_notional: None,
+        };
+
+        assert_eq!(calculate_vwap(&[]), None);
+        assert_eq!(calculate_vwap(&[trade(Side::Buy, 3700., 0), trade(Side::Sell, 3701., 0)]), None);
+        assert_eq!(calculate_vwap(&[trade(Side::Buy, 3700.5, 10)]), Some(3700.5));
+        assert_close(calculate_vwap(&[trade(Side::Buy, 3700., 100), trade(Side::Sell, 3710., 300)]).unwrap(), 3707.5);
+        let mut unpriced = trade(Side::Sell, 0., 1000);
+        unpriced.price = None;
+        assert_close(calculate_vwap(&[trade(Side::Buy, 3700., 100), unpriced]).unwrap(), 3700.);
+
+        let bucket = |vwap, volume| TradeBin {
+            timestamp: Utc::now().into(),
+            symbol: "XBTUSD".into(),
+            open: None,
+            high: None,
+            low: None,
+            close: None,
+            trades: None,
+            volume: Some(volume),
+            vwap,
+            last_size: None,
+            turnover: None,
+            home_notional: None,
+            foreign_notional: None,
+        };
+        // Buckets without trades have no vwap
+        assert_close(calculate_vwap_from_buckets(&[bucket(Some(3700.), 1000), bucket(Some(3720.), 3000), bucket(None, 0)]).unwrap(), 3715.);
+        assert_eq!(calculate_vwap_from_buckets(&[bucket(None, 0)]), None);
+    }
+
+    #[test]
+    fn test_order_book() {
+        let level = |id, side, price, size| GetOrderBookL2Response {
+            symbol: "XBTUSD".into(),
+            id,
+            side,
+            size,
+            price,
+        };
+        let book = [
+            level(1, Side::Sell, 3702., 500.),
+            level(2, Side::Buy, 3699., 200.),
+            level(3, Side::Sell, 3701., 100.),
+            level(4, Side::Buy, 3700., 300.),
+        ];
+        assert_eq!(best_bid(&book), Some((3700., 300)));
+        assert_eq!(best_ask(&book), Some((3701., 100)));
+        assert_eq!(mid_price(&book), Some(3700.5));
+        assert_eq!(spread(&book), Some(1.));
+        assert_close(spread_bps(&book).unwrap(), 2.70);
+
+        // One side is empty
+        let bids = &book[1..2];
+        assert_eq!(best_bid(bids), Some((3699., 200)));
+        assert_eq!((best_ask(bids), mid_price(bids), spread(bids), spread_bps(bids)), (None, None, None, None));
+        assert_eq!(best_bid(&[]), None);
+    }
+
+    #[test]
+    fn test_round_to_tick() {
+        assert_eq!(round_to_tick(3700.3, 0.5), 3700.5);
+        assert_eq!(round_to_tick(3700.2, 0.5), 3700.);
+        assert_eq!(floor_to_tick(3700.9, 0.5), 3700.5);
+        assert_eq!(ceil_to_tick(3700.1, 0.5), 3700.5);
+
+        // Just off a tick because of float error, not a tick away
+        assert_eq!(floor_to_tick(219.00000000001, 0.5), 219.);
+        assert_eq!(ceil_to_tick(218.99999999999, 0.5), 219.);
+        assert_eq!(ceil_to_tick(0.1 + 0.2, 0.1), 0.3);
+        assert_eq!(floor_to_tick(0.3, 0.1), 0.3);
+        assert_eq!(round_to_tick(2190. * 0.1, 0.1), 219.);
+        assert_eq!(ceil_to_tick(219.01, 0.05), 219.05);
+        assert_eq!(floor_to_tick(0.00012349, 0.00000001), 0.00012349);
+
+        assert_eq!(round_to_tick(3700.25, 0.), 3700.25);
+        assert_eq!(round_to_tick(7.3, 2.5), 7.5);
+
+        assert!(is_tick_multiple(219.00000000001, 0.5) && is_tick_multiple(0.1 + 0.2, 0.1));
+        assert!(!is_tick_multiple(219.3, 0.5) && is_tick_multiple(219.3, 0.));
+    }
+
+    #[test]
+    fn test_round_to_lot() {
+        let below = |qty: f64| f64::from_bits(qty.to_bits() - 1);
+        assert_eq!(floor_to_lot(200., 100), 200);
+        assert_eq!(floor_to_lot(below(200.), 100), 200);
+        assert_eq!(floor_to_lot(below(1.), 1), 1);
+        assert_eq!(floor_to_lot(199.9, 100), 100);
+        assert_eq!(floor_to_lot(-250., 100), -200);
+
+        assert_eq!(round_to_lot(below(150.), 100), 100);
+        assert_eq!(round_to_lot(150., 100), 200);
+        assert_eq!(round_to_lot(0.1 * 3. * 1000., 100), 300);
+        assert_eq!(round_to_lot(42.4, 1), 42);
+        assert_eq!(round_to_lot(42.6, 0), 43);
+        assert_eq!(floor_to_lot(f64::NAN, 100), 0);
+    }
+}
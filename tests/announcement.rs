@@ -1,16 +1,19 @@
 extern crate bitmex;
 extern crate tokio;
 
+mod common;
+
 use bitmex::model::announcement::GetAnnouncementRequest;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_announcement() -> Result<()> {
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_announcement(GetAnnouncementRequest { ..Default::default() })?;
+    let bm = common::client("announcement/get_announcement")?;
+    let fut = bm.get_announcement(GetAnnouncementRequest { ..Default::default() });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -18,10 +21,10 @@ fn get_announcement() -> Result<()> {
 
 #[test]
 fn get_announcement_urgent() -> Result<()> {
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_announcement_urgent()?;
+    let bm = common::client("announcement/get_announcement_urgent")?;
+    let fut = bm.get_announcement_urgent();
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -3,12 +3,13 @@ extern crate dotenv;
 extern crate env_logger;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::api_key::{DeleteApiKeyRequest, PostApiKeyDisableRequest, PostApiKeyEnableRequest, PostApiKeyRequest};
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 const TEST_KEY: &'static str = "kaiYN1s2-FIQzTxgHYisfovQ";
 #[allow(dead_code)]
 const TEST_SECRET: &'static str = "f6gkw2IfJgV62EWoxn_k7kj1SKpY9CBll_jJRmFI9AHjSlnL";
@@ -17,10 +18,10 @@ const TEST_SECRET: &'static str = "f6gkw2IfJgV62EWoxn_k7kj1SKpY9CBll_jJRmFI9AHjS
 fn get_api_key() -> Result<()> {
     ::dotenv::dotenv().ok();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_api_key()?;
+    let bm = common::client("apikey/get_api_key")?;
+    let fut = bm.get_api_key();
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -30,10 +31,10 @@ fn get_api_key() -> Result<()> {
 fn create_api_key() -> Result<()> {
     ::dotenv::dotenv().ok();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.post_api_key(PostApiKeyRequest { ..Default::default() })?;
+    let bm = common::client("apikey/create_api_key")?;
+    let fut = bm.post_api_key(PostApiKeyRequest { ..Default::default() });
 
     assert!(rt.block_on(fut).is_err());
     Ok(())
@@ -43,10 +44,10 @@ fn create_api_key() -> Result<()> {
 fn enable_api_key() -> Result<()> {
     ::dotenv::dotenv().ok();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.post_api_key_enable(PostApiKeyEnableRequest { api_key_id: TEST_KEY.into() })?;
+    let bm = common::client("apikey/enable_api_key")?;
+    let fut = bm.post_api_key_enable(PostApiKeyEnableRequest { api_key_id: TEST_KEY.into() });
 
     assert!(rt.block_on(fut).is_err());
     Ok(())
@@ -57,10 +58,10 @@ fn enable_api_key() -> Result<()> {
 fn disable_api_key() -> Result<()> {
     ::dotenv::dotenv().ok();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.post_api_key_disable(PostApiKeyDisableRequest { api_key_id: TEST_KEY.into() })?;
+    let bm = common::client("apikey/disable_api_key")?;
+    let fut = bm.post_api_key_disable(PostApiKeyDisableRequest { api_key_id: TEST_KEY.into() });
 
     assert!(rt.block_on(fut).is_err());
     Ok(())
@@ -71,10 +72,10 @@ fn disable_api_key() -> Result<()> {
 fn delete_api_key() -> Result<()> {
     ::dotenv::dotenv().ok();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.delete_api_key(DeleteApiKeyRequest { api_key_id: TEST_KEY.into() })?;
+    let bm = common::client("apikey/delete_api_key")?;
+    let fut = bm.delete_api_key(DeleteApiKeyRequest { api_key_id: TEST_KEY.into() });
 
     assert!(rt.block_on(fut).is_err());
     Ok(())
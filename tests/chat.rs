@@ -2,23 +2,24 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::chat::{GetChatRequest, PostChatRequest};
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_chat() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
+    let bm = common::client("chat/get_chat")?;
     let fut = bm.get_chat(GetChatRequest {
         count: 1,
         channel_id: Some(1),
         ..Default::default()
-    })?;
+    });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -29,13 +30,13 @@ fn get_chat() -> Result<()> {
 fn post_chat() -> Result<()> {
     ::dotenv::dotenv().ok();
 
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("chat/post_chat")?;
     let fut = bm.post_chat(PostChatRequest {
         message: "\n---- IGNORE ME ----\nbitmex-rs library testing\n---- IGNORE ME ----".into(),
         channel_id: 1,
         ..Default::default()
-    })?;
+    });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -44,10 +45,10 @@ fn post_chat() -> Result<()> {
 #[test]
 fn get_chat_channels() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_chat_channels()?;
+    let bm = common::client("chat/get_chat_channels")?;
+    let fut = bm.get_chat_channels();
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -56,10 +57,10 @@ fn get_chat_channels() -> Result<()> {
 #[test]
 fn get_chat_connected() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_chat_connected()?;
+    let bm = common::client("chat/get_chat_connected")?;
+    let fut = bm.get_chat_connected();
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -0,0 +1,107 @@
+//! The client of the integration tests. Signed requests use `BITMEX_KEY` and `BITMEX_SECRET`.
+//!
+//! With the `record` feature a test replays `tests/fixtures/<fixture>.json` if it exists, so it runs
+//! offline and without credentials. Set `BITMEX_RECORD` to (re)write the fixture from the live API.
+//! Tests without a fixture, and all of them without the feature, talk to BitMEX directly.
+
+use std::env::var;
+
+use bitmex::{BitMEX, Transport};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[cfg(not(feature = "record"))]
+pub fn client(_fixture: &str) -> Result<BitMEX> {
+    Ok(BitMEX::with_transport(live()))
+}
+
+#[cfg(feature = "record")]
+pub fn client(fixture: &str) -> Result<BitMEX<fixtures::Fixtures>> {
+    Ok(BitMEX::with_transport(fixtures::Fixtures::open(fixture, live)?))
+}
+
+fn live() -> Transport {
+    ::dotenv::dotenv().ok();
+    match (var("BITMEX_KEY"), var("BITMEX_SECRET")) {
+        (Ok(key), Ok(secret)) => Transport::with_credential(&key, &secret),
+        _ => Transport::new(),
+    }
+}
+
+#[cfg(feature = "record")]
+mod fixtures {
+    use std::env::var_os;
+    use std::fs::create_dir_all;
+    use std::path::Path;
+
+    use bitmex::{BoxFuture, RawResponse, RecordingTransport, ReplayTransport, Transport, TransportTrait};
+    use hyper::Method;
+    use url::Url;
+
+    use super::Result;
+
+    /// The transport of a test: live, recording a fixture or replaying it
+    pub enum Fixtures {
+        Live(Transport),
+        Record(RecordingTransport<Transport>),
+        Replay(ReplayTransport),
+    }
+
+    impl Fixtures {
+        pub fn open(fixture: &str, live: impl FnOnce() -> Transport) -> Result<Self> {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(format!("{}.json", fixture));
+            if var_os("BITMEX_RECORD").is_some() {
+                if let Some(dir) = path.parent() {
+                    create_dir_all(dir)?;
+                }
+                return Ok(Fixtures::Record(RecordingTransport::new(live(), path)));
+            }
+            if path.exists() {
+                return Ok(Fixtures::Replay(ReplayTransport::from_file(path)?));
+            }
+            Ok(Fixtures::Live(live()))
+        }
+    }
+
+    impl TransportTrait for Fixtures {
+        fn request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+            match self {
+                Fixtures::Live(tr) => tr.request_raw(method, endpoint, query, body),
+                Fixtures::Record(tr) => tr.request_raw(method, endpoint, query, body),
+                Fixtures::Replay(tr) => tr.request_raw(method, endpoint, query, body),
+            }
+        }
+
+        fn signed_request_raw(&self, method: Method, endpoint: &str, query: Vec<(String, String)>, body: String) -> BoxFuture<RawResponse> {
+            match self {
+                Fixtures::Live(tr) => tr.signed_request_raw(method, endpoint, query, body),
+                Fixtures::Record(tr) => tr.signed_request_raw(method, endpoint, query, body),
+                Fixtures::Replay(tr) => tr.signed_request_raw(method, endpoint, query, body),
+            }
+        }
+
+        fn api_key(&self) -> Option<&str> {
+            match self {
+                Fixtures::Live(tr) => tr.api_key(),
+                Fixtures::Record(tr) => tr.api_key(),
+                Fixtures::Replay(tr) => tr.api_key(),
+            }
+        }
+
+        fn base_url(&self) -> &str {
+            match self {
+                Fixtures::Live(tr) => tr.base_url(),
+                Fixtures::Record(tr) => tr.base_url(),
+                Fixtures::Replay(tr) => tr.base_url(),
+            }
+        }
+
+        fn signature(&self, method: &Method, expires: i64, url: &Url, body: &str) -> bitmex::Result<(&str, String)> {
+            match self {
+                Fixtures::Live(tr) => tr.signature(method, expires, url, body),
+                Fixtures::Record(tr) => tr.signature(method, expires, url, body),
+                Fixtures::Replay(tr) => tr.signature(method, expires, url, body),
+            }
+        }
+    }
+}
@@ -2,19 +2,20 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::execution::{GetExecutionRequest, GetExecutionTradeHistoryRequest};
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_execution() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_execution(GetExecutionRequest { ..Default::default() })?;
+    let bm = common::client("execution/get_execution")?;
+    let fut = bm.get_execution(GetExecutionRequest { ..Default::default() });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -23,9 +24,9 @@ fn get_execution() -> Result<()> {
 #[test]
 fn get_execution_history() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_execution_history(GetExecutionTradeHistoryRequest { ..Default::default() })?;
+    let rt = Runtime::new()?;
+    let bm = common::client("execution/get_execution_history")?;
+    let fut = bm.get_execution_history(GetExecutionTradeHistoryRequest { ..Default::default() });
 
     let _ = rt.block_on(fut)?;
     Ok(())
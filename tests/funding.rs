@@ -1,19 +1,22 @@
 extern crate bitmex;
 extern crate tokio;
 
+mod common;
+
 use bitmex::model::funding::GetFundingRequest;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_funding() -> Result<()> {
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
+    let bm = common::client("funding/get_funding")?;
     let fut = bm.get_funding(GetFundingRequest {
         symbol: Some("XBT".to_string()),
         ..Default::default()
-    })?;
+    });
 
     let res = rt.block_on(fut)?;
     println!("{:?}", res);
@@ -2,20 +2,21 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_global_notification() -> Result<()> {
     // This will fail for error access denied
     ::dotenv::dotenv().ok();
 
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_global_notification()?;
+    let bm = common::client("global_notification/get_global_notification")?;
+    let fut = bm.get_global_notification();
 
     assert!(rt.block_on(fut).is_err());
     Ok(())
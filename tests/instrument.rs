@@ -2,20 +2,24 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
+mod common;
+
+use bitmex::model::Columns;
 use bitmex::model::instrument::GetInstrumentRequest;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn test_get_instrument() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
+    let bm = common::client("instrument/test_get_instrument")?;
     let fut = bm.get_instrument(GetInstrumentRequest {
         symbol: Some("XBT".to_string()),
         ..Default::default()
-    })?;
+    });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -24,10 +28,10 @@ fn test_get_instrument() -> Result<()> {
 #[test]
 fn test_get_instrument_active() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_instrument_active()?;
+    let bm = common::client("instrument/test_get_instrument_active")?;
+    let fut = bm.get_instrument_active();
 
     let _ = rt.block_on(fut)?;
 
@@ -37,10 +41,10 @@ fn test_get_instrument_active() -> Result<()> {
 #[test]
 fn test_get_instrument_active_and_indices() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_instrument_active_and_indices()?;
+    let bm = common::client("instrument/test_get_instrument_active_and_indices")?;
+    let fut = bm.get_instrument_active_and_indices();
 
     let _ = rt.block_on(fut)?;
 
@@ -50,10 +54,10 @@ fn test_get_instrument_active_and_indices() -> Result<()> {
 #[test]
 fn get_instrument_active_interval() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_instrument_active_interval()?;
+    let bm = common::client("instrument/get_instrument_active_interval")?;
+    let fut = bm.get_instrument_active_interval();
 
     let _ = rt.block_on(fut)?;
 
@@ -63,13 +67,13 @@ fn get_instrument_active_interval() -> Result<()> {
 #[test]
 fn get_instrument_composite_index() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
+    let bm = common::client("instrument/get_instrument_composite_index")?;
     let fut = bm.get_instrument_composite_index(GetInstrumentRequest {
         symbol: Some("XBT".to_string()),
         ..Default::default()
-    })?;
+    });
 
     let _ = rt.block_on(fut)?;
 
@@ -79,12 +83,29 @@ fn get_instrument_composite_index() -> Result<()> {
 #[test]
 fn get_instrument_indices() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::new();
-    let fut = bm.get_instrument_indices()?;
+    let bm = common::client("instrument/get_instrument_indices")?;
+    let fut = bm.get_instrument_indices();
 
     let _ = rt.block_on(fut)?;
 
     Ok(())
 }
+
+#[test]
+fn test_get_instrument_columns() -> Result<()> {
+    ::dotenv::dotenv().ok();
+    let rt = Runtime::new()?;
+
+    let bm = common::client("instrument/test_get_instrument_columns")?;
+    let fut = bm.get_instrument(GetInstrumentRequest {
+        symbol: Some("XBTUSD".to_string()),
+        columns: Some(Columns::of(["symbol", "lastPrice"])),
+        ..Default::default()
+    });
+
+    let instruments = rt.block_on(fut)?;
+    assert!(instruments.iter().all(|i| i.root_symbol.is_none()));
+    Ok(())
+}
@@ -2,19 +2,20 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::insurance::GetInsuranceRequest;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_insurance() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_funding(GetInsuranceRequest { ..Default::default() })?;
+    let bm = common::client("insurance/get_insurance")?;
+    let fut = bm.get_funding(GetInsuranceRequest { ..Default::default() });
 
     let _ = rt.block_on(fut)?;
 
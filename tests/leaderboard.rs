@@ -2,19 +2,20 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::leaderboard::GetLeaderboardRequest;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_leaderboard() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_leaderboard(GetLeaderboardRequest { ..Default::default() })?;
+    let bm = common::client("leaderboard/get_leaderboard")?;
+    let fut = bm.get_leaderboard(GetLeaderboardRequest { ..Default::default() });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -23,10 +24,10 @@ fn get_leaderboard() -> Result<()> {
 #[test]
 fn get_leaderboard_name() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_leaderboard_name()?;
+    let bm = common::client("leaderboard/get_leaderboard_name")?;
+    let fut = bm.get_leaderboard_name();
 
     let _ = rt.block_on(fut)?;
     Ok(())
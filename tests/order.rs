@@ -3,34 +3,102 @@ extern crate dotenv;
 extern crate env_logger;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
-use bitmex::model::order::{ContingencyType, DeleteOrderAllRequest, DeleteOrderRequest, ExecInst, OrdType, PostOrderRequest, PutOrderRequest, Side};
+use bitmex::model::order::{CancelOrderRequest, ContingencyType, DeleteOrderAllRequest, DeleteOrderRequest, ExecInst, GetOrderRequest, OrdStatus, OrdType, OrderId, PostOrderRequest, PutOrderRequest, Side};
 use bitmex::model::Vararg;
 
-use bitmex::{BitMEX, Result};
-
+use serde_json::json;
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn get_order() -> Result<()> {
+    ::dotenv::dotenv().ok();
+    let rt = Runtime::new()?;
+    let bm = common::client("order/get_order")?;
+
+    let _ = rt.block_on(bm.get_order(GetOrderRequest {
+        symbol: Some("XBTUSD".to_string()),
+        count: 10,
+        reverse: Some(true),
+        ..Default::default()
+    }))?;
+    Ok(())
+}
+
+#[test]
+fn get_order_last_open() -> Result<()> {
+    ::dotenv::dotenv().ok();
+    let rt = Runtime::new()?;
+    let bm = common::client("order/get_order_last_open")?;
+
+    let orders = rt.block_on(bm.get_order(GetOrderRequest {
+        filter: Some(json!({ "open": true })),
+        count: 10,
+        reverse: Some(true),
+        ..Default::default()
+    }))?;
+    assert!(orders.len() <= 10);
+    assert!(orders.iter().all(|o| !o.order_id.is_nil() && !o.symbol.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn get_open_orders() -> Result<()> {
+    ::dotenv::dotenv().ok();
+    let rt = Runtime::new()?;
+    let bm = common::client("order/get_open_orders")?;
+
+    let orders = rt.block_on(bm.get_open_orders(Some("XBTUSD")))?;
+    assert!(orders.iter().all(|o| o.ord_status == OrdStatus::New || o.ord_status == OrdStatus::PartiallyFilled));
+    Ok(())
+}
+
 // SKip order testings otherwise we will be marked as spammer
 #[test]
 #[ignore]
 fn create_order_market() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("order/create_order_market")?;
 
     let resp = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
         order_qty: Some(1.),
         text: Some("Shine".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let _ = rt.block_on(bm.delete_order(DeleteOrderRequest {
         order_id: Some(Vararg::Single(resp.order_id)),
         ..Default::default()
-    })?)?;
+    }))?;
+    Ok(())
+}
+
+#[test]
+#[ignore]
+fn create_cancel_order_by_cl_ord_id() -> Result<()> {
+    ::dotenv::dotenv().ok();
+    let rt = Runtime::new()?;
+    let bm = common::client("order/create_cancel_order_by_cl_ord_id")?;
+
+    let resp = rt.block_on(bm.post_order(PostOrderRequest {
+        symbol: "XBTUSD".to_string(),
+        ord_type: Some(OrdType::Limit),
+        price: Some(6000.),
+        order_qty: Some(1.),
+        cl_ord_id: Some("bitmex-rs-cancel-test".into()),
+        ..Default::default()
+    }))?;
+
+    let canceled = rt.block_on(bm.cancel_order(CancelOrderRequest {
+        text: Some("Shine".into()),
+        ..OrderId::ClOrdID(resp.cl_ord_id).into()
+    }))?;
+    assert_eq!(canceled[0].order_id, resp.order_id);
     Ok(())
 }
 
@@ -38,8 +106,8 @@ fn create_order_market() -> Result<()> {
 #[ignore]
 fn create_order_limit_buy() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("order/create_order_limit_buy")?;
 
     let resp = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -48,12 +116,12 @@ fn create_order_limit_buy() -> Result<()> {
         order_qty: Some(1.),
         text: Some("Shine".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let _ = rt.block_on(bm.delete_order(DeleteOrderRequest {
         order_id: Some(Vararg::Single(resp.order_id)),
         ..Default::default()
-    })?)?;
+    }))?;
 
     Ok(())
 }
@@ -62,8 +130,8 @@ fn create_order_limit_buy() -> Result<()> {
 #[ignore]
 fn create_order_limit_sell() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("order/create_order_limit_sell")?;
 
     let resp = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -72,12 +140,12 @@ fn create_order_limit_sell() -> Result<()> {
         order_qty: Some(-1.),
         text: Some("Shine".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let _ = rt.block_on(bm.delete_order(DeleteOrderRequest {
         order_id: Some(Vararg::Single(resp.order_id)),
         ..Default::default()
-    })?)?;
+    }))?;
 
     Ok(())
 }
@@ -86,8 +154,8 @@ fn create_order_limit_sell() -> Result<()> {
 #[ignore]
 fn create_order_stop() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("order/create_order_stop")?;
 
     let resp = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -96,12 +164,12 @@ fn create_order_stop() -> Result<()> {
         order_qty: Some(-1.),
         text: Some("Shine".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let _ = rt.block_on(bm.delete_order(DeleteOrderRequest {
         order_id: Some(Vararg::Single(resp.order_id)),
         ..Default::default()
-    })?)?;
+    }))?;
 
     Ok(())
 }
@@ -110,8 +178,8 @@ fn create_order_stop() -> Result<()> {
 #[ignore]
 fn create_order_stoplimit() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("order/create_order_stoplimit")?;
 
     let resp = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -121,12 +189,12 @@ fn create_order_stoplimit() -> Result<()> {
         order_qty: Some(-1.),
         text: Some("Shine".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let _ = rt.block_on(bm.delete_order(DeleteOrderRequest {
         order_id: Some(Vararg::Single(resp.order_id)),
         ..Default::default()
-    })?)?;
+    }))?;
 
     Ok(())
 }
@@ -135,9 +203,9 @@ fn create_order_stoplimit() -> Result<()> {
 #[ignore]
 fn create_order_bracket() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("order/create_order_bracket")?;
 
     let resp1 = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -149,7 +217,7 @@ fn create_order_bracket() -> Result<()> {
         contingency_type: Some(ContingencyType::OneTriggersTheOther),
         text: Some("Entry".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let resp2 = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -161,7 +229,7 @@ fn create_order_bracket() -> Result<()> {
         order_qty: Some(1.),
         text: Some("Stoploss".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let resp3 = rt.block_on(bm.post_order(PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -172,12 +240,12 @@ fn create_order_bracket() -> Result<()> {
         exec_inst: Some(vec![ExecInst::Close]),
         text: Some("Profit".into()),
         ..Default::default()
-    })?)?;
+    }))?;
 
     let _ = rt.block_on(bm.delete_order(DeleteOrderRequest {
         order_id: Some(Vararg::Multiple(vec![resp1.order_id, resp2.order_id, resp3.order_id])),
         ..Default::default()
-    })?)?;
+    }))?;
 
     Ok(())
 }
@@ -186,9 +254,9 @@ fn create_order_bracket() -> Result<()> {
 #[ignore]
 fn create_amend_delete_order() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("order/create_amend_delete_order")?;
 
     let cor = PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -198,20 +266,20 @@ fn create_amend_delete_order() -> Result<()> {
         order_qty: Some(1000.),
         ..Default::default()
     };
-    let fut = bm.post_order(cor)?;
+    let fut = bm.post_order(cor);
     let resp = rt.block_on(fut)?;
 
     let fut = bm.put_order(PutOrderRequest {
         order_id: Some(resp.order_id),
         order_qty: Some(2.),
         ..Default::default()
-    })?;
+    });
     let _ = rt.block_on(fut)?;
 
     let fut = bm.delete_order(DeleteOrderRequest {
         order_id: Some(Vararg::Single(resp.order_id)),
         ..Default::default()
-    })?;
+    });
     let _ = rt.block_on(fut)?;
 
     Ok(())
@@ -221,9 +289,9 @@ fn create_amend_delete_order() -> Result<()> {
 #[ignore]
 fn create_delete_all_order() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("order/create_delete_all_order")?;
 
     let cor = PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -233,7 +301,7 @@ fn create_delete_all_order() -> Result<()> {
         order_qty: Some(20.),
         ..Default::default()
     };
-    let fut = bm.post_order(cor)?;
+    let fut = bm.post_order(cor);
     let _ = rt.block_on(fut)?;
     let cor = PostOrderRequest {
         symbol: "XBTUSD".to_string(),
@@ -243,13 +311,13 @@ fn create_delete_all_order() -> Result<()> {
         order_qty: Some(20.),
         ..Default::default()
     };
-    let fut = bm.post_order(cor)?;
+    let fut = bm.post_order(cor);
     let _ = rt.block_on(fut)?;
 
     let fut = bm.delete_order_all(DeleteOrderAllRequest {
         symbol: Some("XBTUSD".to_string()),
         ..Default::default()
-    })?;
+    });
     let _ = rt.block_on(fut)?;
 
     Ok(())
@@ -259,9 +327,9 @@ fn create_delete_all_order() -> Result<()> {
 #[ignore]
 fn create_amend_delete_order_bulk() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("order/create_amend_delete_order_bulk")?;
 
     let orders = vec![
         PostOrderRequest {
@@ -282,7 +350,7 @@ fn create_amend_delete_order_bulk() -> Result<()> {
         },
     ];
 
-    let fut = bm.post_order_bulk(&orders)?;
+    let fut = bm.post_order_bulk(&orders);
     let orders = rt.block_on(fut)?;
 
     let req: Vec<_> = orders
@@ -295,13 +363,13 @@ fn create_amend_delete_order_bulk() -> Result<()> {
         })
         .collect();
 
-    let fut = bm.put_order_bulk(&req)?;
+    let fut = bm.put_order_bulk(&req);
     let _ = rt.block_on(fut)?;
 
     let fut = bm.delete_order_all(DeleteOrderAllRequest {
         symbol: Some("XBTUSD".to_string()),
         ..Default::default()
-    })?;
+    });
     let _ = rt.block_on(fut)?;
 
     Ok(())
@@ -2,22 +2,23 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::order_book::GetOrderBookL2Request;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_order_book_l2() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("order_book/get_order_book_l2")?;
     let fut = bm.get_order_book_l2(GetOrderBookL2Request {
         symbol: "XBTUSD".into(),
         depth: Some(1),
-    })?;
+    });
 
     let _ = rt.block_on(fut)?;
     Ok(())
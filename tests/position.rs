@@ -2,34 +2,53 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
+use bitmex::model::Columns;
 use bitmex::model::position::{GetPositionRequest, PostPositionIsolateRequest, PostPositionLeverageRequest, PostPositionRiskLimitRequest, PostPositionTransferMarginRequest};
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_position() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
-    let fut = bm.get_position(GetPositionRequest { ..Default::default() })?;
+    let bm = common::client("position/get_position")?;
+    let fut = bm.get_position(GetPositionRequest { ..Default::default() });
 
     let _ = rt.block_on(fut)?;
     Ok(())
 }
 
+#[test]
+fn get_position_columns() -> Result<()> {
+    ::dotenv::dotenv().ok();
+    let rt = Runtime::new()?;
+
+    let bm = common::client("position/get_position_columns")?;
+    let fut = bm.get_position(GetPositionRequest {
+        columns: Some(Columns::of(["symbol", "currentQty"])),
+        count: Some(10),
+        ..Default::default()
+    });
+
+    let positions = rt.block_on(fut)?;
+    assert!(positions.iter().all(|p| !p.symbol.is_empty()));
+    Ok(())
+}
+
 #[test]
 fn post_position_isolate() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("position/post_position_isolate")?;
     let fut = bm.post_position_isolate(PostPositionIsolateRequest {
         symbol: "XBTUSD".into(),
         enabled: false,
-    })?;
+    });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -38,13 +57,13 @@ fn post_position_isolate() -> Result<()> {
 #[test]
 fn post_position_leverage() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("position/post_position_leverage")?;
     let fut = bm.post_position_leverage(PostPositionLeverageRequest {
         symbol: "XBTUSD".into(),
         leverage: 1.1,
-    })?;
+    });
 
     let _ = rt.block_on(fut)?;
     Ok(())
@@ -53,13 +72,13 @@ fn post_position_leverage() -> Result<()> {
 #[test]
 fn post_position_risk_limit() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("position/post_position_risk_limit")?;
     let fut = bm.post_position_risk_limit(PostPositionRiskLimitRequest {
         symbol: "XBTUSD".into(),
-        risk_limit: 30000000000.0,
-    })?;
+        risk_limit: 30_000_000_000,
+    });
 
     let a = rt.block_on(fut)?;
     println!("{:?}", a);
@@ -70,13 +89,13 @@ fn post_position_risk_limit() -> Result<()> {
 #[ignore]
 fn post_position_transfer_margin() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
+    let rt = Runtime::new()?;
 
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let bm = common::client("position/post_position_transfer_margin")?;
     let fut = bm.post_position_transfer_margin(PostPositionTransferMarginRequest {
         symbol: "XBTUSD".into(),
-        amount: 10.,
-    })?;
+        amount: 10,
+    });
 
     let _ = rt.block_on(fut)?;
     Ok(())
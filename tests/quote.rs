@@ -2,35 +2,36 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::quote::{GetQuoteBucketedRequest, GetQuoteRequest};
 use bitmex::model::BinSize;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_quote() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("quote/get_quote")?;
 
-    assert!(rt.block_on(bm.get_quote(GetQuoteRequest { ..Default::default() })?).is_err());
+    assert!(rt.block_on(bm.get_quote(GetQuoteRequest { ..Default::default() })).is_err());
     Ok(())
 }
 
 #[test]
 fn get_quote_bucketed() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("quote/get_quote_bucketed")?;
 
     assert!(
         rt.block_on(bm.get_quote_bucketed(GetQuoteBucketedRequest {
             partial: false,
             bin_size: BinSize::D1,
             ..Default::default()
-        })?).is_err()
+        })).is_err()
     );
     Ok(())
 }
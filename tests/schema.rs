@@ -0,0 +1,30 @@
+extern crate bitmex;
+extern crate tokio;
+
+mod common;
+
+use tokio::runtime::Runtime;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn get_schema() -> Result<()> {
+    let rt = Runtime::new()?;
+
+    let bm = common::client("schema/get_schema")?;
+    let fut = bm.get_schema("Instrument");
+
+    let _ = rt.block_on(fut)?;
+    Ok(())
+}
+
+#[test]
+fn get_websocket_help() -> Result<()> {
+    let rt = Runtime::new()?;
+
+    let bm = common::client("schema/get_websocket_help")?;
+    let fut = bm.get_websocket_help();
+
+    let _ = rt.block_on(fut)?;
+    Ok(())
+}
@@ -2,18 +2,19 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::settlement::GetSettlementRequest;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_settlement() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("settlement/get_settlement")?;
 
-    assert!(rt.block_on(bm.get_settlement(GetSettlementRequest { ..Default::default() })?).is_err());
+    assert!(rt.block_on(bm.get_settlement(GetSettlementRequest { ..Default::default() })).is_err());
     Ok(())
 }
@@ -2,35 +2,36 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::trade::{GetTradeBucketedRequest, GetTradeRequest};
 use bitmex::model::BinSize;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_trade() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("trade/get_trade")?;
 
-    let _ = rt.block_on(bm.get_trade(GetTradeRequest { ..Default::default() })?)?;
+    let _ = rt.block_on(bm.get_trade(GetTradeRequest { ..Default::default() }))?;
     Ok(())
 }
 
 #[test]
 fn get_trade_bucketed() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("trade/get_trade_bucketed")?;
 
     let _ = rt.block_on(bm.get_trade_bucketed(GetTradeBucketedRequest {
         partial: false,
         bin_size: BinSize::D1,
         count: 10,
         ..Default::default()
-    })?)?;
+    }))?;
 
     Ok(())
 }
@@ -2,41 +2,40 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::user::{GetUserWalletHistoryRequest, GetUserWalletRequest, GetUserWalletSummaryRequest};
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_user() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user/get_user")?;
 
-    let _ = rt.block_on(bm.get_user()?)?;
+    let _ = rt.block_on(bm.get_user())?;
     Ok(())
 }
 
-
 #[test]
 fn get_user_affiliate_status() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user/get_user_affiliate_status")?;
 
-    let _ = rt.block_on(bm.get_user_affiliate_status()?)?;
+    let _ = rt.block_on(bm.get_user_affiliate_status())?;
     Ok(())
 }
 
-
 #[test]
 fn get_user_wallet() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user/get_user_wallet")?;
 
-    let _ = rt.block_on(bm.get_user_wallet(GetUserWalletRequest { ..Default::default() })?)?;
+    let _ = rt.block_on(bm.get_user_wallet(GetUserWalletRequest { ..Default::default() }))?;
 
     Ok(())
 }
@@ -44,10 +43,10 @@ fn get_user_wallet() -> Result<()> {
 #[test]
 fn get_user_wallet_history() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user/get_user_wallet_history")?;
 
-    let _ = rt.block_on(bm.get_user_wallet_history(GetUserWalletHistoryRequest { ..Default::default() })?)?;
+    let _ = rt.block_on(bm.get_user_wallet_history(GetUserWalletHistoryRequest { ..Default::default() }))?;
 
     Ok(())
 }
@@ -55,10 +54,10 @@ fn get_user_wallet_history() -> Result<()> {
 #[test]
 fn get_user_wallet_summary() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user/get_user_wallet_summary")?;
 
-    let _ = rt.block_on(bm.get_user_wallet_summary(GetUserWalletSummaryRequest { ..Default::default() })?)?;
+    let _ = rt.block_on(bm.get_user_wallet_summary(GetUserWalletSummaryRequest { ..Default::default() }))?;
 
     Ok(())
 }
@@ -66,10 +65,10 @@ fn get_user_wallet_summary() -> Result<()> {
 #[test]
 fn get_user_commission() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user/get_user_commission")?;
 
-    let _ = rt.block_on(bm.get_user_commission()?)?;
+    let _ = rt.block_on(bm.get_user_commission())?;
 
     Ok(())
 }
@@ -77,10 +76,10 @@ fn get_user_commission() -> Result<()> {
 #[test]
 fn get_user_deposit_address() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user/get_user_deposit_address")?;
 
-    let _ = rt.block_on(bm.get_user_deposit_address(Default::default())?)?;
+    let _ = rt.block_on(bm.get_user_deposit_address(Default::default()))?;
 
     Ok(())
 }
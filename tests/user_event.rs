@@ -2,18 +2,30 @@ extern crate bitmex;
 extern crate dotenv;
 extern crate tokio;
 
-use std::env::var;
+mod common;
 
 use bitmex::model::user_event::GetUserEventRequest;
-use bitmex::{BitMEX, Result};
 use tokio::runtime::Runtime;
 
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 #[test]
 fn get_user_event() -> Result<()> {
     ::dotenv::dotenv().ok();
-    let mut rt = Runtime::new()?;
-    let bm = BitMEX::with_credential(&var("BITMEX_KEY")?, &var("BITMEX_SECRET")?);
+    let rt = Runtime::new()?;
+    let bm = common::client("user_event/get_user_event")?;
+
+    let _ = rt.block_on(bm.get_user_event(GetUserEventRequest::default()))?;
+    Ok(())
+}
+
+#[test]
+fn get_user_events() -> Result<()> {
+    ::dotenv::dotenv().ok();
+    let rt = Runtime::new()?;
+    let bm = common::client("user_event/get_user_events")?;
 
-    let _ = rt.block_on(bm.get_user_event(GetUserEventRequest::default())?)?;
+    let resp = rt.block_on(bm.get_user_events(Some(10), None))?;
+    assert!(resp.user_events.len() <= 10);
     Ok(())
 }